@@ -0,0 +1,7 @@
+// Operators follow C's precedence, the value of the last statement is the
+// program's exit code
+// EXIT: 18
+int a = 2;
+int b = 3;
+a += b * 4 - 1;
+a + (b << 1) - 1;
@@ -0,0 +1,26 @@
+// Bitfields pack into the units of their type, wrap around when a value
+// doesn't fit and keep the bits around them when they're written
+// EXIT: 12
+struct flags {
+    bool ready : 1;
+    int mode : 3;
+    int count : 5 = 2;
+    bool tail;
+    int big : 30;
+    int next : 4;
+};
+flags f = { true, -3, tail: false, big: 0, next: 0 };
+int m = f.mode;
+f.mode = 5;
+int wrapped = f.mode;
+f.count++;
+++f.count;
+int old = f.count--;
+f.tail = true;
+f.big = 1000000;
+f.next = 7;
+flags g = f;
+flags* p = &g;
+(*p).mode = 1;
+int n = f.ready + (m == -3) * 2 + (wrapped == -3) * 4 + (f.count == 3) * 8 + (old == 4) * 16 + (g.big == 1000000) * 32 + g.next * 64;
+n + sizeof(flags) + (*p).mode;
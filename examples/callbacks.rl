@@ -0,0 +1,21 @@
+// A function's name on its own is its address, which can be stored in a
+// variable of a function pointer type and called through
+// EXIT: 23
+typedef fn(int, int) -> int binop;
+
+fn add(int a, int b) -> int {
+    return a + b;
+}
+
+fn mul(int a, int b) -> int {
+    return a * b;
+}
+
+fn apply(binop op, int a, int b) -> int {
+    return op(a, b);
+}
+
+binop op = add;
+int sum = op(2, 3);
+op = mul;
+sum + apply(op, 3, 6);
@@ -0,0 +1,14 @@
+// Casts convert where assignments wouldn't: floats are truncated toward
+// zero, and pointers can point at the bytes of another type
+// EXIT: 24
+int x = 258;
+bool* bytes = (&x) as bool*;
+int low = *bytes;
+int high = *(bytes + 1);
+
+float f = -2.75;
+int whole = f as int;
+float half = (x as float) / 2.0;
+
+int* nothing = 0 as int*;
+low * 10 + high + whole + (half as int - 129) + (nothing == null) * 5;
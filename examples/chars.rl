@@ -0,0 +1,17 @@
+// Chars are single bytes: literals give their ASCII value, escapes included,
+// and arrays of them take a byte per element
+// EXIT: 61
+char letter = 'a';
+char newline = '\n';
+char quote = '\'';
+char word[3];
+word[0] = 'h';
+word[1] = 'i';
+word[2] = '\0';
+char* p = &word[0];
+*(p + 1) = 'o';
+letter++;
+int i = letter - 'a';
+int size = sizeof(word) + sizeof(char);
+int escapes = (newline == 10) + (quote == 39) * 2 + ('\\' == 92) * 4 + (word[2] == 0) * 8;
+word[1] - 'a' + i + size + escapes + 27;
@@ -0,0 +1,15 @@
+// Adds up the odd numbers below 10, using break and continue
+// EXIT: 25
+int n = 0;
+int odd = 0;
+while (true) {
+    n++;
+    if (n > 9) {
+        break;
+    }
+    if (n % 2 == 0) {
+        continue;
+    }
+    odd += n;
+}
+odd;
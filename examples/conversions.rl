@@ -0,0 +1,23 @@
+// Values are converted to the type of wherever they're stored: variables,
+// parameters, return values and the properties of struct literals
+// EXIT: 95
+struct point { float x; int y; };
+
+fn half(float x) -> int {
+    return x / 2;
+}
+
+fn first(int* values) -> int {
+    return *values;
+}
+
+float f = 7;
+int i = 2.75;
+bool b = 300;
+int a[3];
+a[0] = 40;
+int* p = a;
+int n = 0;
+n = 9.9;
+point q = { 1, 2.9 };
+half(f) + i + b + first(a) + *p - n * 2 + half(13) + (q.x as int) + q.y * 10;
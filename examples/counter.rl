@@ -0,0 +1,14 @@
+// Properties can be assigned to like variables
+// EXIT: 11
+struct counter {
+    int count;
+    int step;
+    bool done;
+};
+counter c = { 0, 1, false };
+while (!c.done) {
+    c.count += c.step;
+    c.step++;
+    c.done = c.count > 9;
+}
+c.count + c.done + c.step - 5;
@@ -0,0 +1,14 @@
+// Struct properties can have defaults, which literals may leave out
+// EXIT: 29
+struct config {
+    int width = 4;
+    int height = 5;
+    bool wide = false;
+};
+config standard = {};
+config big = { height: 10, wide: true };
+int area = standard.width * standard.height;
+if (big.wide) {
+    area += big.height - big.width / 4;
+}
+area;
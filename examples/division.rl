@@ -0,0 +1,7 @@
+// Integer division truncates toward zero and the remainder has the sign of
+// the dividend, like in C
+// EXIT: 73
+int a = -7;
+int b = 7;
+int q = a / 2 * 10 + a % 2;
+100 + q - b / -2 + b % -2;
@@ -0,0 +1,25 @@
+// The body of a do while loop runs before the condition is first tested,
+// so it runs once even when the condition is false. continue goes to the
+// condition, not back to the top of the body
+// EXIT: 31
+int once = 0;
+do {
+    once++;
+} while (false);
+
+int n = 0;
+int odd = 0;
+do {
+    n++;
+    if (n % 2 == 0) {
+        continue;
+    }
+    odd += n;
+} while (n < 10);
+
+int stopped = 0;
+do {
+    stopped++;
+    continue;
+} while (stopped < 5);
+once + odd + stopped;
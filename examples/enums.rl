@@ -0,0 +1,20 @@
+// Enum members count up from 0, or from the value given to the one before
+// them, and can be used wherever an int can, but an int only becomes an
+// enum with a cast
+// EXIT: 227
+enum color { red, green, blue, };
+enum level { low = -1, normal, high = 10, higher };
+
+fn brightness(color c) -> int {
+    if (c == blue) {
+        return 3;
+    }
+    return c + 1;
+}
+
+color c = green;
+int total = brightness(c) * 10 + brightness(blue);
+color last = 2 as color;
+c = last;
+int n = c;
+total + n * 100 + sizeof(color) + low + normal + higher - high;
@@ -0,0 +1,14 @@
+// Small variables are extended to the whole register when they're loaded,
+// so negative ints stay negative and the bytes next to a bool don't make it
+// true
+// EXIT: 6
+int big = -1;
+bool flag = false;
+int result = 0;
+if (flag) {
+    result = 100;
+}
+if (big < 0) {
+    result += 6;
+}
+result;
@@ -0,0 +1,13 @@
+// The locals of every call live in its own frame, so the recursive calls
+// don't overwrite them
+// EXIT: 55
+fn fib(int n) -> int {
+    if (n < 2) {
+        return n;
+    }
+    int a = fib(n - 1);
+    int b = fib(n - 2);
+    return a + b;
+}
+
+fib(10);
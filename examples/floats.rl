@@ -0,0 +1,42 @@
+// Comparisons with NaN are false, except for != which is true, and -0.0
+// equals 0.0. Each test that behaves that way adds its bit to the result
+// EXIT: 255
+float zero = 0.0;
+float nan = zero / zero;
+float negzero = -0.0;
+int result = 0;
+if (nan != nan) {
+    result += 1;
+}
+if (!(nan == nan)) {
+    result += 2;
+}
+if (!(nan < 1.0) && !(nan <= 1.0) && !(nan > 1.0) && !(nan >= 1.0)) {
+    result += 4;
+}
+if (negzero == zero && !(negzero < zero)) {
+    result += 8;
+}
+if (1.5 < 2.5 && 2.5 > 1.5 && 1.5 <= 1.5 && 2.5 >= 2.5 && !(2.5 < 1.5)) {
+    result += 16;
+}
+// Ints are converted when they meet a float
+if (zero + 1 == 1.0 && 3 / 2.0 == 1.5) {
+    result += 32;
+}
+// Both branches of a ternary are floats if either is
+bool yes = true;
+float one = yes ? 1 : 2.5;
+float half = !yes ? 1 : 0.5;
+if (one == 1.0 && half == 0.5) {
+    result += 64;
+}
+// ++ and -- add and subtract 1.0
+float f = 1.5;
+f++;
+float before = f--;
+--f;
+if (before == 2.5 && f == 0.5) {
+    result += 128;
+}
+result;
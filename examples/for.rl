@@ -0,0 +1,33 @@
+// Any clause of a for loop can be left out, and continue still runs the
+// step before the next iteration
+// EXIT: 41
+int odd = 0;
+for (int i = 0; i < 10; i++) {
+    if (i % 2 == 0) {
+        continue;
+    }
+    odd += i;
+}
+
+int j = 0;
+for (; j < 5;) {
+    j += 2;
+}
+
+int k = 0;
+for (;;) {
+    k++;
+    if (k == 7) {
+        break;
+    }
+}
+
+int first = 0;
+for (int i = 0; ; i++) {
+    if (i < 3) {
+        continue;
+    }
+    first = i;
+    break;
+}
+odd + j + k + first;
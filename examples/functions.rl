@@ -0,0 +1,12 @@
+// The first six arguments are passed in registers and the rest on the
+// stack, an argument can itself be a call
+// EXIT: 39
+fn add(int a, int b, int c, int d, int e, int f, int g, int h) -> int {
+    a - b + c + d + e + f + g * h;
+}
+
+fn twice(int a) -> int {
+    a * 2;
+}
+
+1 + add(10, 2, 3, 4, 5, 6, twice(3), 2);
@@ -0,0 +1,18 @@
+// Multidimensional arrays are laid out row by row, whatever mix of constant
+// and computed indices reaches an element
+// EXIT: 69
+int grid[3][4];
+for (int i = 0; i < 3; i++) {
+    for (int j = 0; j < 4; j++) {
+        grid[i][j] = i * 10 + j;
+    }
+}
+bool cube[2][3][5];
+int x = 1;
+cube[x][2][x + 3] = true;
+grid[2][3] += grid[x][2] + cube[1][2][4];
+int trace = 0;
+for (int k = 0; k < 3; k++) {
+    trace += grid[k][k];
+}
+trace + grid[2][x + 2];
@@ -0,0 +1,16 @@
+// Elements are read and written through constant or computed indices
+// EXIT: 46
+int a[5];
+for (int i = 0; i < 5; i++) {
+    a[i] = i * i;
+}
+a[0] = 10;
+a[a[1]] += 5;
+a[4]++;
+int sum = 0;
+int i = 0;
+while (i < 5) {
+    sum += a[i];
+    i++;
+}
+sum;
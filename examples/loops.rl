@@ -0,0 +1,7 @@
+// Sums the numbers from 1 to 10
+// EXIT: 55
+int sum = 0;
+for (int i = 1; i <= 10; i++) {
+    sum += i;
+}
+sum;
@@ -0,0 +1,12 @@
+// Every operator keeps its left operand on the stack while the right one
+// is evaluated, so operands nested arbitrarily deep, calls included, all
+// find their values again
+// EXIT: 9
+fn square(int x) -> int {
+    return x * x;
+}
+
+int x = 2;
+int a = x + (x * (x + (x * (x + (x - 1)))));
+int b = square(x + square(x - (x * (x - 1))) - 1) - (square(square(x)) - (x + (x * x)));
+a + b + ((((((1 + 1) * 2) - 1) * 2) - 2) - (((x * 3) - 1) - 1));
@@ -0,0 +1,27 @@
+// null stands for a missing pointer, and every pointer compares with it
+// EXIT: 47
+int a[4];
+for (int i = 0; i < 4; i++) {
+    a[i] = i * 10;
+}
+
+fn find(int* start, int len, int value) -> int* {
+    for (int i = 0; i < len; i++) {
+        if (*(start + i) == value) {
+            return start + i;
+        }
+    }
+    return null;
+}
+
+fn value_or(int* p, int fallback) -> int {
+    if (p == null) {
+        return fallback;
+    }
+    return *p;
+}
+
+int* found = find(a, 4, 20);
+int* missing = find(a, 4, 25);
+bool none = missing;
+value_or(found, 1) + value_or(missing, 7) + none * 100 + (found != null) * 20;
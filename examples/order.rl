@@ -0,0 +1,15 @@
+// Operands, arguments and array targets are evaluated from left to right.
+// umask returns the mask it replaces, so every call sees the one before it
+// EXIT: 241
+fn mark(int mask) -> int {
+    return syscall(95, mask);
+}
+fn digits(int a, int b, int c) -> int {
+    return a * 100 + b * 10 + c;
+}
+mark(0);
+int operands = mark(1) * 10 + mark(2);
+int args = digits(mark(3), mark(4), mark(5));
+int a[8];
+a[mark(6)] = mark(7);
+args + operands + a[5];
@@ -0,0 +1,17 @@
+// Functions can call the ones defined further down the file
+// EXIT: 1
+fn even(int n) -> bool {
+    if (n == 0) {
+        return true;
+    }
+    return odd(n - 1);
+}
+
+fn odd(int n) -> bool {
+    if (n == 0) {
+        return false;
+    }
+    return even(n - 1);
+}
+
+even(10);
@@ -0,0 +1,28 @@
+// Functions can change variables of their caller through pointers to them
+// EXIT: 39
+struct vec2 {
+    int x;
+    int y;
+};
+
+fn swap(int* a, int* b) {
+    int t = *a;
+    *a = *b;
+    *b = t;
+}
+
+fn scale(vec2* v, int by) {
+    (*v).x *= by;
+    (*v).y *= by;
+}
+
+int a = 30;
+int b = 4;
+swap(&a, &b);
+int* p = &b;
+int** pp = &p;
+**pp += 2;
+
+vec2 v = { 1, 2 };
+scale(&v, 3);
+a + b + v.x;
@@ -0,0 +1,9 @@
+// The prelude's functions can be called without defining them
+// EXIT: 7
+fn check(int x) -> int {
+    assert(x > 0);
+    return x;
+}
+check(3);
+exit(check(7));
+0;
@@ -0,0 +1,22 @@
+// Programs linked with libc can print with printf. Its output is buffered
+// when it goes to a pipe or a file, and has to be flushed before the
+// program exits to show up at all
+// LINK: c
+// OUTPUT: hi 42
+// OUTPUT: 1.500000
+// EXIT: 9
+extern fn printf(char* format, ...) -> int;
+char format[7];
+format[0] = 'h';
+format[1] = 'i';
+format[2] = ' ';
+format[3] = '%';
+format[4] = 'd';
+format[5] = '\n';
+format[6] = '\0';
+printf(&format[0], 42);
+format[0] = '%';
+format[1] = 'f';
+format[2] = '\n';
+format[3] = '\0';
+printf(&format[0], 1.5);
@@ -0,0 +1,10 @@
+// Every call gets a frame of its own, so a function can call itself
+// EXIT: 120
+fn factorial(int n) -> int {
+    if (n <= 1) {
+        return 1;
+    }
+    return n * factorial(n - 1);
+}
+
+factorial(5);
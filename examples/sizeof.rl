@@ -0,0 +1,12 @@
+// sizeof is worked out while compiling from the type of its operand, which
+// is never evaluated, so n is still 0 at the end
+// EXIT: 81
+struct vec3 { int x; int y; int z; };
+vec3 v = { 1, 2, 3 };
+int grid[4][2];
+int* p = &v.x;
+int n = 0;
+
+int types = sizeof(vec3) + sizeof(vec3*) + sizeof(bool);
+int values = sizeof(v) + sizeof(grid) + sizeof(grid[0]) + sizeof(*p) + sizeof(n++);
+types + values + n * 100;
@@ -0,0 +1,38 @@
+// Structs are passed by value, so the callee works on its own copy
+// EXIT: 93
+struct vec2 {
+    int x;
+    int y;
+};
+struct vec3 {
+    int x;
+    int y;
+    int z;
+};
+struct big {
+    int a;
+    int b;
+    int c;
+    int d;
+    int e;
+};
+fn dot(vec2 a, vec2 b) -> int {
+    return a.x * b.x + a.y * b.y;
+}
+fn sum3(vec3 v) -> int {
+    v.x = v.x + v.y + v.z;
+    return v.x;
+}
+fn total(big b) -> int {
+    return b.a + b.b + b.c + b.d + b.e;
+}
+// Only one register is left for the struct, so it goes on the stack
+fn last(int a, int b, int c, int d, int e, vec2 v) -> int {
+    return a + b + c + d + e + v.x - v.y;
+}
+vec2 u = { 1, 2 };
+vec2 w = { 3, 4 };
+vec3 t = { 5, 6, 7 };
+big b = { 1, 2, 3, 4, 5 };
+int s = sum3(t);
+dot(u, w) + s + t.x + total(b) + last(1, 2, 3, 4, 5, { 30, 1 });
@@ -0,0 +1,40 @@
+// Arrays can hold structs, which are copied in and out whole or used a
+// property at a time
+// EXIT: 86
+struct vec2 {
+    int x;
+    int y;
+};
+struct vec3 {
+    int x;
+    int y;
+    int z;
+};
+struct line {
+    vec2 start;
+    vec2 end;
+};
+fn length(vec2 v) -> int {
+    return v.x + v.y;
+}
+fn make(int n) -> vec3 {
+    return { n, n + 1, n + 2 };
+}
+vec2 points[4];
+for (int i = 0; i < 4; i++) {
+    points[i].x = i;
+    points[i].y = i * 10;
+}
+vec3 cubes[3];
+int k = 2;
+cubes[k] = make(5);
+cubes[0] = { 1, 2, 3 };
+cubes[1] = cubes[k];
+cubes[1].z++;
+line lines[2];
+lines[k - 1].start = points[3];
+lines[1].end = { 4, 5 };
+vec2 copy = points[k];
+points[k] = points[1];
+int sum = length(points[k]) + copy.x + copy.y;
+sum + cubes[1].z + cubes[0].y + lines[1].start.y + lines[1].end.x + length(lines[1].end);
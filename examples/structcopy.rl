@@ -0,0 +1,18 @@
+// Assigning a struct copies it, so changing the copy leaves the original alone
+// EXIT: 32
+struct vec2 {
+    int x;
+    int y;
+};
+struct line {
+    vec2 from;
+    vec2 to;
+};
+vec2 a = { 3, 4 };
+vec2 b = a;
+b.x = 10;
+line l = { a, b };
+vec2 c = { 0, 0 };
+vec2 d = c = l.to;
+d.y++;
+a.x + a.y + b.x + c.x + c.y + d.y - b.y;
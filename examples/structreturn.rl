@@ -0,0 +1,34 @@
+// Functions can return structs of any size
+// EXIT: 40
+struct vec2 {
+    int x;
+    int y;
+};
+struct vec3 {
+    int x;
+    int y;
+    int z;
+};
+struct big {
+    int a;
+    int b;
+    int c;
+    int d;
+    int e;
+};
+fn make_vec(int x, int y) -> vec2 {
+    return { x, y };
+}
+fn make_vec3(int x) -> vec3 {
+    vec3 v = { x, x + 1, x + 2 };
+    return v;
+}
+fn make_big(int a, int b, int c, int d, int e, int f) -> big {
+    big r = { a, b, c, d, e + f };
+    return r;
+}
+vec2 v = make_vec(3, 4);
+vec3 w = make_vec3(10);
+big b = make_big(1, 2, 3, 4, 5, 6);
+v = make_vec(v.y, v.x);
+v.x + v.y + w.z + b.a + b.b + b.c + b.d + b.e;
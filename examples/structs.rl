@@ -0,0 +1,13 @@
+// Properties are read with a dot
+// EXIT: 42
+struct rect {
+    int width;
+    int height;
+    bool square;
+};
+rect r = { 6, 7, false };
+int area = r.width * r.height;
+if (r.square) {
+    area = 0;
+}
+area;
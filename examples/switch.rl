@@ -0,0 +1,20 @@
+// Cases fall through into the next one unless they break
+// EXIT: 31
+int x = 3;
+int result = 0;
+switch (x) {
+case 1:
+    result = 10;
+    break;
+case 2:
+    result = 20;
+    break;
+case 3:
+    result = 30;
+case 4:
+    result += 1;
+    break;
+default:
+    result = 99;
+}
+result;
@@ -0,0 +1,7 @@
+// Any system call can be made directly, like exit with its own status
+// EXIT: 3
+fn quit(int status) {
+    syscall(60, status);
+}
+quit(3);
+10;
@@ -0,0 +1,6 @@
+// Conditional expressions only evaluate the branch they pick
+// EXIT: 7
+int a = 4;
+int b = 7;
+int max = a > b ? a : b;
+max;
@@ -0,0 +1,17 @@
+// The properties of a union share its bytes, so writing one changes the
+// others. It's as large as its largest property
+// EXIT: 114
+union number { int i; float f; bool b; };
+struct tagged { int kind; number value; };
+union wide { int* p; int lo; };
+fn bits(number n) -> int { return n.i; }
+number n = { f: 1.0 };
+int raw = n.i;
+n.i = 300;
+int low = n.b;
+tagged t = { 1, { i: 7 } };
+t.value.i = t.value.i + 1;
+number m = n;
+int x = 5;
+wide w = { p: &x };
+(raw == 1065353216) + low + t.value.i + bits(m) + sizeof(number) + sizeof(wide) + (*w.p);
@@ -0,0 +1,20 @@
+// Pointers move by whole elements, and subtracting two of them counts the
+// elements between them
+// EXIT: 102
+int a[5];
+for (int i = 0; i < 5; i++) {
+    a[i] = i + 1;
+}
+
+fn sum(int* start, int* end) -> int {
+    int total = 0;
+    while (start != end) {
+        total += *start;
+        start++;
+    }
+    return total;
+}
+
+int* first = &a[0];
+int* last = first + 4;
+sum(first, last + 1) + (last - first) * 20 + *(last - 1) * 2 - 1;
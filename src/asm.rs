@@ -0,0 +1,348 @@
+use std::fmt;
+
+/// The width of an operand.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Size {
+    Byte,
+    Word,
+    Dword,
+    Qword,
+}
+
+impl Size {
+    /// The operand size for a value of `bytes` bytes, if there is one.
+    pub fn from_bytes(bytes: usize) -> Option<Size> {
+        match bytes {
+            1 => Some(Size::Byte),
+            2 => Some(Size::Word),
+            4 => Some(Size::Dword),
+            8 => Some(Size::Qword),
+            _ => None,
+        }
+    }
+
+    pub fn bytes(self) -> usize {
+        match self {
+            Size::Byte => 1,
+            Size::Word => 2,
+            Size::Dword => 4,
+            Size::Qword => 8,
+        }
+    }
+}
+
+impl fmt::Display for Size {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Size::Byte => write!(f, "byte"),
+            Size::Word => write!(f, "word"),
+            Size::Dword => write!(f, "dword"),
+            Size::Qword => write!(f, "qword"),
+        }
+    }
+}
+
+/// A general purpose register, regardless of which part of it is used.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Register {
+    Rax,
+    Rbx,
+    Rcx,
+    Rdx,
+    Rsi,
+    Rdi,
+    Rbp,
+    Rsp,
+    R8,
+    R9,
+    R10,
+    R11,
+    R12,
+    R13,
+    R14,
+    R15,
+}
+
+impl Register {
+    /// The name of the register's lowest `size` bytes, e.g. `eax` for a dword of rax.
+    pub fn name(self, size: Size) -> &'static str {
+        let names = match self {
+            Register::Rax => ["al", "ax", "eax", "rax"],
+            Register::Rbx => ["bl", "bx", "ebx", "rbx"],
+            Register::Rcx => ["cl", "cx", "ecx", "rcx"],
+            Register::Rdx => ["dl", "dx", "edx", "rdx"],
+            Register::Rsi => ["sil", "si", "esi", "rsi"],
+            Register::Rdi => ["dil", "di", "edi", "rdi"],
+            Register::Rbp => ["bpl", "bp", "ebp", "rbp"],
+            Register::Rsp => ["spl", "sp", "esp", "rsp"],
+            Register::R8 => ["r8b", "r8w", "r8d", "r8"],
+            Register::R9 => ["r9b", "r9w", "r9d", "r9"],
+            Register::R10 => ["r10b", "r10w", "r10d", "r10"],
+            Register::R11 => ["r11b", "r11w", "r11d", "r11"],
+            Register::R12 => ["r12b", "r12w", "r12d", "r12"],
+            Register::R13 => ["r13b", "r13w", "r13d", "r13"],
+            Register::R14 => ["r14b", "r14w", "r14d", "r14"],
+            Register::R15 => ["r15b", "r15w", "r15d", "r15"],
+        };
+        match size {
+            Size::Byte => names[0],
+            Size::Word => names[1],
+            Size::Dword => names[2],
+            Size::Qword => names[3],
+        }
+    }
+}
+
+impl fmt::Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name(Size::Qword))
+    }
+}
+
+/// How much the index register of an address is multiplied by.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scale {
+    One = 1,
+    Two = 2,
+    Four = 4,
+    Eight = 8,
+}
+
+/// An effective address.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Address {
+    /// `base + index * scale + offset`
+    Register {
+        base: Register,
+        index: Option<(Register, Scale)>,
+        offset: isize,
+    },
+    /// `label + offset`, relative to rip so the code still works when it's
+    /// loaded at another address, like in a position independent executable
+    Label { label: String, offset: isize },
+}
+
+impl Address {
+    pub fn new(base: Register, offset: isize) -> Address {
+        Address::Register {
+            base,
+            index: None,
+            offset,
+        }
+    }
+
+    pub fn label(label: &str) -> Address {
+        Address::Label {
+            label: label.to_owned(),
+            offset: 0,
+        }
+    }
+
+    pub fn offset_by(&self, by: usize) -> Address {
+        let mut address = self.clone();
+        match &mut address {
+            Address::Register { offset, .. } | Address::Label { offset, .. } => {
+                *offset += by as isize
+            }
+        }
+        address
+    }
+
+    /// The memory operand of `size` bytes at this address.
+    pub fn sized(self, size: Size) -> Memory {
+        Memory {
+            address: self,
+            size,
+        }
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let offset = match self {
+            Address::Register {
+                base,
+                index,
+                offset,
+            } => {
+                write!(f, "[{}", base)?;
+                if let Some((index, scale)) = index {
+                    write!(f, "+{}*{}", index, *scale as u8)?;
+                }
+                offset
+            }
+            Address::Label { label, offset } => {
+                write!(f, "[rel {}", label)?;
+                offset
+            }
+        };
+        if *offset != 0 {
+            write!(f, "{:+}", offset)?;
+        }
+        write!(f, "]")
+    }
+}
+
+/// A memory operand, which always knows how many bytes it reads or writes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Memory {
+    pub address: Address,
+    pub size: Size,
+}
+
+impl fmt::Display for Memory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.size, self.address)
+    }
+}
+
+/// An instruction whose operands can't disagree on their size: registers are
+/// used at the width of the memory operand they're moved to or from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Instruction {
+    /// `mov reg, imm` on the whole register
+    MovImmediate {
+        dst: Register,
+        value: i64,
+    },
+    /// Reads the memory operand into the same width of `dst`
+    Load {
+        dst: Register,
+        src: Memory,
+    },
+    /// Reads the memory operand into the whole of `dst`, filling the bytes
+    /// above it with copies of its sign bit or with zeroes
+    Extend {
+        dst: Register,
+        src: Memory,
+        signed: bool,
+    },
+    /// Copies the lowest `size` bytes of `src` into the whole of `dst`,
+    /// extended like `Extend` does
+    ExtendRegister {
+        dst: Register,
+        src: Register,
+        size: Size,
+        signed: bool,
+    },
+    /// Writes the same width of `src` to the memory operand
+    Store {
+        dst: Memory,
+        src: Register,
+    },
+    /// Computes an address without accessing memory
+    Lea {
+        dst: Register,
+        src: Address,
+    },
+    Inc {
+        dst: Memory,
+    },
+    Dec {
+        dst: Memory,
+    },
+    AddImmediate {
+        dst: Memory,
+        value: i64,
+    },
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::MovImmediate { dst, value } => write!(f, "mov {}, {}", dst, value),
+            Instruction::Load { dst, src } => write!(f, "mov {}, {}", dst.name(src.size), src),
+            Instruction::Extend { dst, src, signed } => match (src.size, signed) {
+                (Size::Qword, _) => write!(f, "mov {}, {}", dst, src),
+                (Size::Dword, true) => write!(f, "movsxd {}, {}", dst, src),
+                // Writing the low dword of a register clears the rest of it
+                (Size::Dword, false) => write!(f, "mov {}, {}", dst.name(Size::Dword), src),
+                (_, true) => write!(f, "movsx {}, {}", dst, src),
+                (_, false) => write!(f, "movzx {}, {}", dst, src),
+            },
+            Instruction::ExtendRegister {
+                dst,
+                src,
+                size,
+                signed,
+            } => match (size, signed) {
+                (Size::Qword, _) => write!(f, "mov {}, {}", dst, src),
+                (Size::Dword, true) => write!(f, "movsxd {}, {}", dst, src.name(*size)),
+                (Size::Dword, false) => {
+                    write!(f, "mov {}, {}", dst.name(Size::Dword), src.name(*size))
+                }
+                (_, true) => write!(f, "movsx {}, {}", dst, src.name(*size)),
+                (_, false) => write!(f, "movzx {}, {}", dst, src.name(*size)),
+            },
+            Instruction::Store { dst, src } => write!(f, "mov {}, {}", dst, src.name(dst.size)),
+            Instruction::Lea { dst, src } => write!(f, "lea {}, {}", dst, src),
+            Instruction::Inc { dst } => write!(f, "inc {}", dst),
+            Instruction::Dec { dst } => write!(f, "dec {}", dst),
+            Instruction::AddImmediate { dst, value } => write!(f, "add {}, {}", dst, value),
+        }
+    }
+}
+
+/// A labelled value in one of the data sections, e.g. `float_0: dd 0x3fc00000`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Constant {
+    pub label: String,
+    pub size: Size,
+    pub values: Vec<u64>,
+}
+
+impl fmt::Display for Constant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let directive = match self.size {
+            Size::Byte => "db",
+            Size::Word => "dw",
+            Size::Dword => "dd",
+            Size::Qword => "dq",
+        };
+        let values: Vec<String> = self.values.iter().map(|v| format!("{:#x}", v)).collect();
+        write!(f, "{}: {} {}", self.label, directive, values.join(", "))
+    }
+}
+
+/// A function in the text section. Every function, the entry point included,
+/// is laid out the same way: aligned to 16 bytes like a C compiler's, the
+/// frame set up and torn down around its body, and an `.end` label after it
+/// to measure it by.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Function {
+    pub name: String,
+    /// Whether the linker sees the symbol. nasm can only give global symbols
+    /// a type and size in the ELF symbol table
+    pub global: bool,
+    /// Whether the function sets up a stack frame. Functions without one
+    /// keep everything in registers and can't address rbp
+    pub frame: bool,
+    /// Everything between pushing rbp and restoring rsp, ending with the
+    /// indentation of the next instruction
+    pub body: String,
+}
+
+impl fmt::Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = &self.name;
+        writeln!(f, "\n    align 16")?;
+        if self.global {
+            writeln!(f, "    global {name}:function ({name}.end - {name})")?;
+        }
+        if !self.frame {
+            return write!(f, "{name}:\n    {}ret\n{name}.end:\n", self.body);
+        }
+        write!(
+            f,
+            "{name}:
+    push rbp
+    mov rbp, rsp
+    {}mov rsp, rbp
+    pop rbp
+    ret
+{name}.end:
+",
+            self.body
+        )
+    }
+}
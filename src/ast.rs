@@ -3,6 +3,54 @@ use std::fmt;
 #[derive(Clone)]
 pub enum Operator {
     Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    BitAnd,
+    BitOr,
+    BitXor,
+    ShiftLeft,
+    ShiftRight,
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    LogicalAnd,
+    LogicalOr,
+}
+
+impl Operator {
+    /// Whether the operator compares its operands, giving 1 or 0.
+    pub fn compares(&self) -> bool {
+        matches!(
+            self,
+            Operator::Equal
+                | Operator::NotEqual
+                | Operator::Less
+                | Operator::LessEqual
+                | Operator::Greater
+                | Operator::GreaterEqual
+        )
+    }
+
+    /// Binding strength of the operator, higher binds tighter. Follows C.
+    pub fn precedence(&self) -> u8 {
+        match self {
+            Operator::Mul | Operator::Div | Operator::Mod => 10,
+            Operator::Add | Operator::Sub => 9,
+            Operator::ShiftLeft | Operator::ShiftRight => 8,
+            Operator::Less | Operator::LessEqual | Operator::Greater | Operator::GreaterEqual => 7,
+            Operator::Equal | Operator::NotEqual => 6,
+            Operator::BitAnd => 5,
+            Operator::BitXor => 4,
+            Operator::BitOr => 3,
+            Operator::LogicalAnd => 2,
+            Operator::LogicalOr => 1,
+        }
+    }
 }
 
 impl fmt::Display for Operator {
@@ -12,107 +60,750 @@ impl fmt::Display for Operator {
             "{}",
             match self {
                 Operator::Add => "+",
+                Operator::Sub => "-",
+                Operator::Mul => "*",
+                Operator::Div => "/",
+                Operator::Mod => "%",
+                Operator::BitAnd => "&",
+                Operator::BitOr => "|",
+                Operator::BitXor => "^",
+                Operator::ShiftLeft => "<<",
+                Operator::ShiftRight => ">>",
+                Operator::Equal => "==",
+                Operator::NotEqual => "!=",
+                Operator::Less => "<",
+                Operator::LessEqual => "<=",
+                Operator::Greater => ">",
+                Operator::GreaterEqual => ">=",
+                Operator::LogicalAnd => "&&",
+                Operator::LogicalOr => "||",
+            }
+        )
+    }
+}
+
+#[derive(Clone)]
+pub enum UnaryOperator {
+    Neg,
+    Not,
+    BitNot,
+    /// `*p`, the value a pointer points to
+    Deref,
+    /// `&x`, a pointer to an l-value
+    AddressOf,
+}
+
+impl fmt::Display for UnaryOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                UnaryOperator::Neg => "-",
+                UnaryOperator::Not => "!",
+                UnaryOperator::BitNot => "~",
+                UnaryOperator::Deref => "*",
+                UnaryOperator::AddressOf => "&",
             }
         )
     }
 }
 
+/// Identifies a node of the program. The parser numbers the nodes in the
+/// order it finishes them, so children come before their parent, and a node
+/// keeps its id through every pass. What's found out about a node is kept in
+/// side tables keyed by its id rather than in the tree itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(pub usize);
+
+impl NodeId {
+    /// The id of nodes the compiler makes up on the spot. They aren't part of
+    /// the program, so nothing is recorded about them
+    pub const DETACHED: NodeId = NodeId(usize::MAX);
+}
+
+/// A property of a struct or union, `int flags : 3 = 0`
+#[derive(Clone)]
+pub struct Property {
+    pub datatype: String,
+    pub name: String,
+    /// How many bits wide the property is, if it's a bitfield
+    pub bits: Option<u32>,
+    pub default: Option<Node>,
+}
+
+impl fmt::Display for Property {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.datatype, self.name)?;
+        if let Some(bits) = self.bits {
+            write!(f, " : {}", bits)?;
+        }
+        if let Some(default) = &self.default {
+            write!(f, " = {}", default)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone)]
 pub enum Node {
     Program {
         body: Vec<Node>,
+        id: NodeId,
     },
     Scope {
         body: Vec<Node>,
+        id: NodeId,
     },
     BinOp {
         left: Box<Node>,
         right: Box<Node>,
         op: Operator,
+        id: NodeId,
+    },
+    UnaryOp {
+        value: Box<Node>,
+        op: UnaryOperator,
+        id: NodeId,
+    },
+    /// `x as int`, the value converted to the type even where it
+    /// wouldn't be implicitly
+    Cast {
+        value: Box<Node>,
+        datatype: String,
+        id: NodeId,
+    },
+    /// `sizeof(int*)`, the size of a type in bytes. The parser can't tell
+    /// a type from a variable, so `sizeof(x)` is the size of the variable
+    /// `x` when there is one
+    SizeOfType {
+        datatype: String,
+        id: NodeId,
+    },
+    /// `sizeof(a[0])`, the size of what the expression evaluates to. The
+    /// expression itself isn't evaluated
+    SizeOfValue {
+        value: Box<Node>,
+        id: NodeId,
+    },
+    Integer {
+        value: i32,
+        id: NodeId,
+    },
+    Bool {
+        value: bool,
+        id: NodeId,
+    },
+    /// A character literal, as the byte it stands for
+    Char {
+        value: u8,
+        id: NodeId,
+    },
+    Float {
+        value: f32,
+        id: NodeId,
+    },
+    /// The pointer to nothing, which every pointer type can hold
+    Null {
+        id: NodeId,
     },
-    Integer(i32),
-    Float(f32),
     VarDecl {
         datatype: String,
         name: String,
         value: Box<Node>,
+        id: NodeId,
+    },
+    /// `int a[10]`, an array whose elements are left uninitialized like
+    /// in C. `int grid[8][4]` is an array of 8 arrays of 4 ints
+    ArrayDecl {
+        datatype: String,
+        name: String,
+        /// The length of every dimension, outermost first
+        lens: Vec<usize>,
+        id: NodeId,
     },
     StructDecl {
         name: String,
-        properties: Vec<(String, String)>,
+        properties: Vec<Property>,
+        /// Whether the properties are laid out without padding
+        packed: bool,
+        id: NodeId,
+    },
+    /// `enum color { red, green = 4, blue }`, a type of int whose members
+    /// count up from 0, or from the value given to the one before them
+    EnumDecl {
+        name: String,
+        members: Vec<(String, Option<i64>)>,
+        id: NodeId,
+    },
+    /// `union number { int i; float f; }`, whose members all start at the
+    /// same address and share its bytes
+    UnionDecl {
+        name: String,
+        /// Unions can't have defaults, but the parser accepts them like
+        /// for structs
+        properties: Vec<Property>,
+        id: NodeId,
     },
     TypeDef {
         name: String,
         value: Box<Node>,
+        id: NodeId,
     },
     StructType {
-        properties: Vec<(String, String)>,
+        properties: Vec<Property>,
+        packed: bool,
+        id: NodeId,
+    },
+    /// The type of a pointer to a function, `fn(int, int) -> int`
+    FnType {
+        params: Vec<String>,
+        return_type: Option<String>,
+        id: NodeId,
     },
     Identifier {
         value: String,
+        id: NodeId,
     },
+    /// `{ 1, 2 }` or `{ y: 2, x: 1 }`. Values without a property name
+    /// initialize the property after the previous one, like in C
     StructData {
-        data: Vec<Node>,
+        data: Vec<(Option<String>, Node)>,
+        id: NodeId,
+    },
+    If {
+        cond: Box<Node>,
+        then: Box<Node>,
+        else_: Option<Box<Node>>,
+        id: NodeId,
+    },
+    While {
+        cond: Box<Node>,
+        body: Box<Node>,
+        id: NodeId,
+    },
+    For {
+        init: Option<Box<Node>>,
+        cond: Option<Box<Node>>,
+        step: Option<Box<Node>>,
+        body: Box<Node>,
+        id: NodeId,
+    },
+    DoWhile {
+        body: Box<Node>,
+        cond: Box<Node>,
+        id: NodeId,
+    },
+    Break {
+        id: NodeId,
+    },
+    Continue {
+        id: NodeId,
+    },
+    Switch {
+        value: Box<Node>,
+        /// The value of each case label, `None` for `default`, along with the
+        /// statements up to the next label
+        cases: Vec<(Option<i32>, Vec<Node>)>,
+        id: NodeId,
+    },
+    Label {
+        name: String,
+        id: NodeId,
+    },
+    Goto {
+        name: String,
+        id: NodeId,
+    },
+    Ternary {
+        cond: Box<Node>,
+        then: Box<Node>,
+        else_: Box<Node>,
+        id: NodeId,
+    },
+    Assign {
+        target: Box<Node>,
+        value: Box<Node>,
+        id: NodeId,
+    },
+    /// `++x`, `x++`, `--x` or `x--`
+    IncDec {
+        target: Box<Node>,
+        increment: bool,
+        /// Postfix forms evaluate to the value from before the change
+        postfix: bool,
+        id: NodeId,
+    },
+    /// `fn name(int a, int b) -> int { ... }`
+    FnDecl {
+        name: String,
+        /// The datatype and name of every parameter
+        params: Vec<(String, String)>,
+        /// `None` if the function doesn't return a value
+        return_type: Option<String>,
+        /// The section the code is placed in, `@section(".name")`. `None`
+        /// for the usual `.text`
+        section: Option<String>,
+        body: Box<Node>,
+        id: NodeId,
+    },
+    Call {
+        name: String,
+        args: Vec<Node>,
+        id: NodeId,
+    },
+    /// `v.x`, a property of a struct
+    MemberAccess {
+        target: Box<Node>,
+        member: String,
+        id: NodeId,
+    },
+    /// `a[i]`, an element of an array
+    Index {
+        target: Box<Node>,
+        index: Box<Node>,
+        id: NodeId,
+    },
+    /// A function defined outside of the program, like one from the C library
+    Extern {
+        name: String,
+        params: Vec<(String, String)>,
+        /// Whether more arguments can follow the parameters, `...` in C
+        variadic: bool,
+        return_type: Option<String>,
+        id: NodeId,
+    },
+    /// `None` in functions that don't return a value
+    Return {
+        value: Option<Box<Node>>,
+        id: NodeId,
     },
 }
 
+impl Node {
+    pub fn id(&self) -> NodeId {
+        match self {
+            Node::Program { id, .. }
+            | Node::Scope { id, .. }
+            | Node::BinOp { id, .. }
+            | Node::UnaryOp { id, .. }
+            | Node::Cast { id, .. }
+            | Node::SizeOfType { id, .. }
+            | Node::SizeOfValue { id, .. }
+            | Node::Integer { id, .. }
+            | Node::Bool { id, .. }
+            | Node::Char { id, .. }
+            | Node::Float { id, .. }
+            | Node::Null { id, .. }
+            | Node::VarDecl { id, .. }
+            | Node::ArrayDecl { id, .. }
+            | Node::StructDecl { id, .. }
+            | Node::EnumDecl { id, .. }
+            | Node::UnionDecl { id, .. }
+            | Node::TypeDef { id, .. }
+            | Node::StructType { id, .. }
+            | Node::FnType { id, .. }
+            | Node::Identifier { id, .. }
+            | Node::StructData { id, .. }
+            | Node::If { id, .. }
+            | Node::While { id, .. }
+            | Node::For { id, .. }
+            | Node::DoWhile { id, .. }
+            | Node::Break { id, .. }
+            | Node::Continue { id, .. }
+            | Node::Switch { id, .. }
+            | Node::Label { id, .. }
+            | Node::Goto { id, .. }
+            | Node::Ternary { id, .. }
+            | Node::Assign { id, .. }
+            | Node::IncDec { id, .. }
+            | Node::FnDecl { id, .. }
+            | Node::Call { id, .. }
+            | Node::MemberAccess { id, .. }
+            | Node::Index { id, .. }
+            | Node::Extern { id, .. }
+            | Node::Return { id, .. } => *id,
+        }
+    }
+
+    /// One more than the largest id in the tree, the first id that's free
+    /// for nodes added to it.
+    pub fn next_id(&self) -> NodeId {
+        let below = self.children().into_iter().map(Node::next_id);
+        NodeId(below.map(|id| id.0).max().unwrap_or(0).max(self.id().0 + 1))
+    }
+
+    /// Whether the statement is written without a trailing semicolon, either
+    /// because it ends in a block or because its body brings its own.
+    pub fn is_block(&self) -> bool {
+        matches!(
+            self,
+            Node::Scope { .. }
+                | Node::If { .. }
+                | Node::While { .. }
+                | Node::For { .. }
+                | Node::Switch { .. }
+                | Node::Label { .. }
+                | Node::FnDecl { .. }
+        )
+    }
+
+    /// The nodes directly below this one, in source order.
+    pub fn children(&self) -> Vec<&Node> {
+        match self {
+            Node::Program { body, .. } | Node::Scope { body, .. } => body.iter().collect(),
+            Node::BinOp { left, right, .. } => vec![left, right],
+            Node::UnaryOp { value, .. }
+            | Node::Cast { value, .. }
+            | Node::SizeOfValue { value, .. }
+            | Node::VarDecl { value, .. }
+            | Node::TypeDef { value, .. } => vec![value],
+            Node::StructData { data, .. } => data.iter().map(|(_, value)| value).collect(),
+            Node::Call { args, .. } => args.iter().collect(),
+            Node::If {
+                cond, then, else_, ..
+            } => {
+                let mut children = vec![&**cond, then];
+                children.extend(else_.as_deref());
+                children
+            }
+            Node::While { cond, body, .. } => vec![cond, body],
+            Node::For {
+                init,
+                cond,
+                step,
+                body,
+                ..
+            } => init
+                .iter()
+                .chain(cond)
+                .chain(step)
+                .map(|node| &**node)
+                .chain([&**body])
+                .collect(),
+            Node::DoWhile { body, cond, .. } => vec![body, cond],
+            Node::Ternary {
+                cond, then, else_, ..
+            } => vec![cond, then, else_],
+            Node::Assign { target, value, .. } => vec![target, value],
+            Node::Index { target, index, .. } => vec![target, index],
+            Node::IncDec { target, .. } | Node::MemberAccess { target, .. } => vec![target],
+            Node::FnDecl { body, .. } => vec![body],
+            Node::Return { value, .. } => value.iter().map(|value| &**value).collect(),
+            Node::Switch { value, cases, .. } => [&**value]
+                .into_iter()
+                .chain(cases.iter().flat_map(|(_, body)| body))
+                .collect(),
+            Node::SizeOfType { .. }
+            | Node::Integer { .. }
+            | Node::Bool { .. }
+            | Node::Char { .. }
+            | Node::Null { .. }
+            | Node::Float { .. }
+            | Node::ArrayDecl { .. }
+            | Node::StructDecl { .. }
+            | Node::EnumDecl { .. }
+            | Node::UnionDecl { .. }
+            | Node::StructType { .. }
+            | Node::FnType { .. }
+            | Node::Extern { .. }
+            | Node::Identifier { .. }
+            | Node::Break { .. }
+            | Node::Continue { .. }
+            | Node::Label { .. }
+            | Node::Goto { .. } => vec![],
+        }
+    }
+}
+
+// Writes a statement with the semicolon it needs, if any
+fn write_stmt(f: &mut fmt::Formatter<'_>, stmt: &Node) -> fmt::Result {
+    write!(f, "{}", stmt)?;
+    if !stmt.is_block() {
+        write!(f, ";")?;
+    }
+    Ok(())
+}
+
+// Writes an operand of a binary operator, in parentheses if it binds looser
+// than `min_precedence`
+fn write_operand(f: &mut fmt::Formatter<'_>, operand: &Node, min_precedence: u8) -> fmt::Result {
+    match operand {
+        Node::BinOp { op, .. } if op.precedence() < min_precedence => write!(f, "({})", operand),
+        Node::Ternary { .. } | Node::Assign { .. } => write!(f, "({})", operand),
+        _ => write!(f, "{}", operand),
+    }
+}
+
 impl fmt::Display for Node {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Node::Program { body } => {
+            Node::Program { body, .. } => {
                 for expr in body {
-                    write!(f, "{}", expr)?;
-                    match expr {
-                        Node::Scope { body: _ } => {}
-                        _ => write!(f, ";")?,
-                    }
-                    write!(f, "\n")?;
+                    write_stmt(f, expr)?;
+                    writeln!(f)?;
                 }
                 Ok(())
             }
-            Node::Scope { body } => {
-                write!(f, "{{\n")?;
+            Node::Scope { body, .. } => {
+                writeln!(f, "{{")?;
                 for expr in body {
-                    write!(f, "    {};\n", expr)?;
+                    write!(f, "    ")?;
+                    write_stmt(f, expr)?;
+                    writeln!(f)?;
                 }
                 write!(f, "}}")
             }
-            Node::BinOp { left, right, op } => write!(f, "{} {} {}", *left, op, *right),
-            Node::Integer(value) => write!(f, "{}", value),
-            Node::Float(value) => write!(f, "{}", value),
+            Node::BinOp {
+                left, right, op, ..
+            } => {
+                // Operators are left associative, so a right operand of the same
+                // precedence needs parentheses too
+                write_operand(f, left, op.precedence())?;
+                write!(f, " {} ", op)?;
+                write_operand(f, right, op.precedence() + 1)
+            }
+            Node::UnaryOp { value, op, .. } => match **value {
+                Node::BinOp { .. } => write!(f, "{}({})", op, *value),
+                _ => write!(f, "{}{}", op, *value),
+            },
+            // Casts bind like postfix operators
+            Node::Cast {
+                value, datatype, ..
+            } => match **value {
+                Node::BinOp { .. }
+                | Node::UnaryOp { .. }
+                | Node::Ternary { .. }
+                | Node::Assign { .. } => write!(f, "({}) as {}", value, datatype),
+                _ => write!(f, "{} as {}", value, datatype),
+            },
+            Node::SizeOfType { datatype, .. } => write!(f, "sizeof({})", datatype),
+            Node::SizeOfValue { value, .. } => write!(f, "sizeof({})", value),
+            Node::Integer { value, .. } => write!(f, "{}", value),
+            Node::Bool { value, .. } => write!(f, "{}", value),
+            Node::Char { value, .. } => write!(f, "{:?}", *value as char),
+            Node::Null { .. } => write!(f, "null"),
+            Node::Float { value, .. } => write!(f, "{}", value),
             Node::VarDecl {
                 datatype,
                 name,
                 value,
+                ..
             } => write!(f, "{} {} = {}", datatype, name, value),
-            Node::StructDecl { name, properties } => {
-                write!(f, "struct {} {{\n", name)?;
-                for prop in properties {
-                    write!(f, "    {} {};\n", prop.0, prop.1)?;
+            Node::ArrayDecl {
+                datatype,
+                name,
+                lens,
+                ..
+            } => {
+                write!(f, "{} {}", datatype, name)?;
+                for len in lens {
+                    write!(f, "[{}]", len)?;
+                }
+                Ok(())
+            }
+            Node::StructDecl {
+                name,
+                properties,
+                packed,
+                ..
+            } => {
+                write!(f, "struct ")?;
+                if *packed {
+                    write!(f, "packed ")?;
+                }
+                writeln!(f, "{} {{", name)?;
+                for property in properties {
+                    writeln!(f, "    {};", property)?;
+                }
+                write!(f, "}}")
+            }
+            Node::UnionDecl {
+                name, properties, ..
+            } => {
+                writeln!(f, "union {} {{", name)?;
+                for property in properties {
+                    writeln!(f, "    {};", property)?;
                 }
                 write!(f, "}}")
             }
-            Node::TypeDef { name, value } => write!(f, "typedef {} {}", *value, name),
-            Node::StructType { properties } => {
-                write!(f, "struct {{\n")?;
-                for prop in properties {
-                    write!(f, "    {} {};\n", prop.0, prop.1)?;
+            Node::EnumDecl { name, members, .. } => {
+                write!(f, "enum {} {{ ", name)?;
+                for (i, (member, value)) in members.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", member)?;
+                    if let Some(value) = value {
+                        write!(f, " = {}", value)?;
+                    }
+                }
+                write!(f, " }}")
+            }
+            Node::TypeDef { name, value, .. } => write!(f, "typedef {} {}", *value, name),
+            Node::StructType {
+                properties, packed, ..
+            } => {
+                write!(f, "struct ")?;
+                if *packed {
+                    write!(f, "packed ")?;
+                }
+                writeln!(f, "{{")?;
+                for property in properties {
+                    writeln!(f, "    {};", property)?;
                 }
                 write!(f, "}}")
             }
-            Node::Identifier { value } => write!(f, "{}", value),
-            Node::StructData { data } => {
+            Node::FnType {
+                params,
+                return_type,
+                ..
+            } => {
+                write!(f, "fn({})", params.join(", "))?;
+                if let Some(return_type) = return_type {
+                    write!(f, " -> {}", return_type)?;
+                }
+                Ok(())
+            }
+            Node::Identifier { value, .. } => write!(f, "{}", value),
+            Node::StructData { data, .. } => {
                 write!(f, "{{ ")?;
-                for i in 0..data.len() {
-                    write!(
-                        f,
-                        "{}{}",
-                        data[i],
-                        if i < data.len() - 1 { ", " } else { "" }
-                    )?;
+                for (i, (name, value)) in data.iter().enumerate() {
+                    if let Some(name) = name {
+                        write!(f, "{}: ", name)?;
+                    }
+                    write!(f, "{}{}", value, if i < data.len() - 1 { ", " } else { "" })?;
                 }
                 write!(f, " }}")
             }
+            Node::If {
+                cond, then, else_, ..
+            } => {
+                write!(f, "if ({}) ", cond)?;
+                write_stmt(f, then)?;
+                if let Some(else_) = else_ {
+                    write!(f, " else {}", else_)?;
+                }
+                Ok(())
+            }
+            Node::While { cond, body, .. } => {
+                write!(f, "while ({}) ", cond)?;
+                write_stmt(f, body)
+            }
+            Node::For {
+                init,
+                cond,
+                step,
+                body,
+                ..
+            } => {
+                write!(f, "for (")?;
+                match init {
+                    Some(init) => write_stmt(f, init)?,
+                    None => write!(f, ";")?,
+                }
+                if let Some(cond) = cond {
+                    write!(f, " {}", cond)?;
+                }
+                write!(f, ";")?;
+                if let Some(step) = step {
+                    write!(f, " {}", step)?;
+                }
+                write!(f, ") ")?;
+                write_stmt(f, body)
+            }
+            Node::DoWhile { body, cond, .. } => {
+                write!(f, "do ")?;
+                write_stmt(f, body)?;
+                write!(f, " while ({})", cond)
+            }
+            Node::Break { .. } => write!(f, "break"),
+            Node::Continue { .. } => write!(f, "continue"),
+            Node::Switch { value, cases, .. } => {
+                writeln!(f, "switch ({}) {{", value)?;
+                for (case, body) in cases {
+                    match case {
+                        Some(case) => writeln!(f, "case {}:", case)?,
+                        None => writeln!(f, "default:")?,
+                    }
+                    for stmt in body {
+                        write!(f, "    ")?;
+                        write_stmt(f, stmt)?;
+                        writeln!(f)?;
+                    }
+                }
+                write!(f, "}}")
+            }
+            Node::Label { name, .. } => write!(f, "{}:", name),
+            Node::Goto { name, .. } => write!(f, "goto {}", name),
+            Node::Ternary {
+                cond, then, else_, ..
+            } => write!(f, "{} ? {} : {}", cond, then, else_),
+            Node::Assign { target, value, .. } => write!(f, "{} = {}", target, value),
+            Node::IncDec {
+                target,
+                increment,
+                postfix,
+                ..
+            } => {
+                let op = if *increment { "++" } else { "--" };
+                if *postfix {
+                    write!(f, "{}{}", target, op)
+                } else {
+                    write!(f, "{}{}", op, target)
+                }
+            }
+            Node::FnDecl {
+                name,
+                params,
+                return_type,
+                section,
+                body,
+                ..
+            } => {
+                if let Some(section) = section {
+                    write!(f, "@section(\"{}\") ", section)?;
+                }
+                let params: Vec<String> = params
+                    .iter()
+                    .map(|(datatype, name)| format!("{} {}", datatype, name))
+                    .collect();
+                write!(f, "fn {}({})", name, params.join(", "))?;
+                if let Some(return_type) = return_type {
+                    write!(f, " -> {}", return_type)?;
+                }
+                write!(f, " {}", body)
+            }
+            Node::Call { name, args, .. } => {
+                let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+                write!(f, "{}({})", name, args.join(", "))
+            }
+            Node::MemberAccess { target, member, .. } => write!(f, "{}.{}", target, member),
+            Node::Index { target, index, .. } => write!(f, "{}[{}]", target, index),
+            Node::Extern {
+                name,
+                params,
+                variadic,
+                return_type,
+                ..
+            } => {
+                let mut params: Vec<String> = params
+                    .iter()
+                    .map(|(datatype, name)| format!("{} {}", datatype, name))
+                    .collect();
+                if *variadic {
+                    params.push("...".to_owned());
+                }
+                write!(f, "extern fn {}({})", name, params.join(", "))?;
+                if let Some(return_type) = return_type {
+                    write!(f, " -> {}", return_type)?;
+                }
+                Ok(())
+            }
+            Node::Return {
+                value: Some(value), ..
+            } => write!(f, "return {}", value),
+            Node::Return { value: None, .. } => write!(f, "return"),
         }
     }
 }
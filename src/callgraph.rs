@@ -0,0 +1,164 @@
+use crate::ast;
+use std::collections::HashSet;
+
+/// The entry point every program starts executing at
+pub const ENTRY: &str = "_start";
+
+/// Which functions call which. Nodes are function names, an edge `(a, b)`
+/// means `a` contains a call to `b`.
+#[derive(Debug, Default)]
+pub struct CallGraph {
+    /// The node of the top-level code, named after the symbol it runs from
+    pub entry: String,
+    pub nodes: Vec<String>,
+    pub edges: Vec<(String, String)>,
+}
+
+/// The calls between the functions of `program`. Top-level code outside of
+/// any function belongs to the entry point, the node `entry`. Taking a
+/// function's address counts as calling it, as it can be called through the
+/// pointer later.
+pub fn build(program: &ast::Node, entry: &str) -> CallGraph {
+    let mut graph = CallGraph {
+        entry: entry.to_owned(),
+        ..CallGraph::default()
+    };
+    graph.nodes.push(entry.to_owned());
+    for expr in program.children() {
+        if let ast::Node::FnDecl { name, .. } = expr {
+            graph.nodes.push(name.clone());
+        }
+    }
+
+    for expr in program.children() {
+        match expr {
+            ast::Node::FnDecl { name, body, .. } => calls(body, name, &mut graph),
+            _ => calls(expr, entry, &mut graph),
+        }
+    }
+    graph
+}
+
+// Adds an edge from `caller` to every function `node` refers to, once per
+// function. Names that aren't functions are calls through a variable
+fn calls(node: &ast::Node, caller: &str, graph: &mut CallGraph) {
+    if let ast::Node::Call { name, .. } | ast::Node::Identifier { value: name, .. } = node {
+        let edge = (caller.to_owned(), name.clone());
+        if graph.nodes.contains(name) && !graph.edges.contains(&edge) {
+            graph.edges.push(edge);
+        }
+    }
+    for child in node.children() {
+        calls(child, caller, graph);
+    }
+}
+
+/// Drops the top-level items of `program` that are unreachable from the entry
+/// point, so only code that can run ends up in the output.
+pub fn prune(program: &mut ast::Node, graph: &CallGraph) {
+    let reachable = graph.reachable();
+    if let ast::Node::Program { body, .. } = program {
+        body.retain(|expr| {
+            let keep = is_reachable(expr, &reachable);
+            if !keep {
+                crate::debug!("callgraph", "pruned {expr}");
+            }
+            keep
+        });
+    }
+}
+
+// Only function definitions can be unreachable, everything else at the top
+// level is executed by the entry point itself
+fn is_reachable(node: &ast::Node, reachable: &HashSet<&str>) -> bool {
+    match node {
+        ast::Node::Program { .. }
+        | ast::Node::Scope { .. }
+        | ast::Node::BinOp { .. }
+        | ast::Node::UnaryOp { .. }
+        | ast::Node::Cast { .. }
+        | ast::Node::SizeOfType { .. }
+        | ast::Node::SizeOfValue { .. }
+        | ast::Node::Integer { .. }
+        | ast::Node::Bool { .. }
+        | ast::Node::Char { .. }
+        | ast::Node::Null { .. }
+        | ast::Node::Float { .. }
+        | ast::Node::VarDecl { .. }
+        | ast::Node::ArrayDecl { .. }
+        | ast::Node::StructDecl { .. }
+        | ast::Node::EnumDecl { .. }
+        | ast::Node::UnionDecl { .. }
+        | ast::Node::TypeDef { .. }
+        | ast::Node::StructType { .. }
+        | ast::Node::FnType { .. }
+        | ast::Node::Extern { .. }
+        | ast::Node::Identifier { .. }
+        | ast::Node::StructData { .. }
+        | ast::Node::If { .. }
+        | ast::Node::While { .. }
+        | ast::Node::For { .. }
+        | ast::Node::DoWhile { .. }
+        | ast::Node::Break { .. }
+        | ast::Node::Continue { .. }
+        | ast::Node::Switch { .. }
+        | ast::Node::Label { .. }
+        | ast::Node::Goto { .. }
+        | ast::Node::Ternary { .. }
+        | ast::Node::Assign { .. }
+        | ast::Node::IncDec { .. }
+        | ast::Node::Call { .. }
+        | ast::Node::MemberAccess { .. }
+        | ast::Node::Index { .. }
+        | ast::Node::Return { .. } => true,
+        ast::Node::FnDecl { name, .. } => reachable.contains(name.as_str()),
+    }
+}
+
+impl CallGraph {
+    /// Every function that can be called, directly or indirectly, from the entry point.
+    pub fn reachable(&self) -> HashSet<&str> {
+        self.reachable_from(&[&self.entry])
+    }
+
+    /// Every function that can be called, directly or indirectly, from one
+    /// of `roots`, including the roots themselves.
+    pub fn reachable_from<'a>(&'a self, roots: &[&'a str]) -> HashSet<&'a str> {
+        let mut reachable: HashSet<&str> = roots.iter().copied().collect();
+        let mut stack = roots.to_vec();
+        while let Some(caller) = stack.pop() {
+            for (from, to) in &self.edges {
+                if from == caller && reachable.insert(to) {
+                    stack.push(to);
+                }
+            }
+        }
+        reachable
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph callgraph {\n");
+        for node in &self.nodes {
+            dot += &format!("    \"{}\";\n", node);
+        }
+        for (caller, callee) in &self.edges {
+            dot += &format!("    \"{}\" -> \"{}\";\n", caller, callee);
+        }
+        dot += "}\n";
+        dot
+    }
+
+    pub fn to_json(&self) -> String {
+        let nodes: Vec<String> = self.nodes.iter().map(|n| format!("\"{}\"", n)).collect();
+        let edges: Vec<String> = self
+            .edges
+            .iter()
+            .map(|(caller, callee)| format!("[\"{}\", \"{}\"]", caller, callee))
+            .collect();
+        format!(
+            "{{\"nodes\": [{}], \"edges\": [{}]}}\n",
+            nodes.join(", "),
+            edges.join(", ")
+        )
+    }
+}
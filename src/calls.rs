@@ -0,0 +1,277 @@
+use crate::conversions::{self, Class};
+use crate::cst;
+use crate::lexer;
+use crate::resolver;
+
+/// A span of the source with a message about it, shown along with an error
+/// to point at a second place, like the declaration a call doesn't match.
+#[derive(Debug)]
+pub struct Label {
+    pub message: String,
+    pub start_index: usize,
+    pub end_index: usize,
+}
+
+/// A call that doesn't match the function it calls.
+#[derive(Debug)]
+pub struct CallError {
+    pub message: String,
+    pub start_index: usize,
+    pub end_index: usize,
+    /// Where the mismatched declaration is
+    pub label: Label,
+}
+
+/// Checks the arguments of every call against the parameters of the function
+/// it calls. Only functions declared in the file can be pointed at, and only
+/// arguments whose type is clear from the syntax are compared, the
+/// generator catches the rest.
+pub fn check(cst: &cst::SyntaxNode, resolution: &resolver::Resolution) -> Vec<CallError> {
+    let mut errors = vec![];
+    let mut enums = vec![];
+    enum_names(cst, &mut enums);
+    calls(cst, cst, resolution, &enums, &mut errors);
+    errors
+}
+
+fn calls(
+    node: &cst::SyntaxNode,
+    program: &cst::SyntaxNode,
+    resolution: &resolver::Resolution,
+    enums: &[String],
+    errors: &mut Vec<CallError>,
+) {
+    if node.kind == cst::NodeKind::Call {
+        call(node, program, resolution, enums, errors);
+    }
+    for child in node.child_nodes() {
+        calls(child, program, resolution, enums, errors);
+    }
+}
+
+// The name of every enum declared anywhere in the file
+fn enum_names(node: &cst::SyntaxNode, enums: &mut Vec<String>) {
+    if node.kind == cst::NodeKind::EnumDecl {
+        enums.extend(
+            node.child_tokens()
+                .find(is_identifier)
+                .map(|name| name.text.clone()),
+        );
+    }
+    for child in node.child_nodes() {
+        enum_names(child, enums);
+    }
+}
+
+fn call(
+    node: &cst::SyntaxNode,
+    program: &cst::SyntaxNode,
+    resolution: &resolver::Resolution,
+    enums: &[String],
+    errors: &mut Vec<CallError>,
+) {
+    // Builtins like syscall aren't declared, and calls through a variable
+    // holding a function's address don't know which function they reach
+    let Some(name) = node.child_tokens().find(is_identifier) else {
+        return;
+    };
+    let Some(function) = declaration(program, resolution, name)
+        .filter(|node| matches!(node.kind, cst::NodeKind::FnDecl | cst::NodeKind::Extern))
+    else {
+        return;
+    };
+
+    let params: Vec<&cst::SyntaxNode> = function
+        .child_nodes()
+        .filter(|node| node.kind == cst::NodeKind::Param)
+        .collect();
+    let variadic = function
+        .child_tokens()
+        .any(|token| token.token_type == lexer::TokenType::Ellipsis);
+    let args: Vec<&cst::SyntaxNode> = node.child_nodes().collect();
+    let (Some((start_index, end_index)), Some(signature)) = (node.range(), signature(function))
+    else {
+        return;
+    };
+
+    if args.len() != params.len() && !(variadic && args.len() > params.len()) {
+        let at_least = if variadic { "at least " } else { "" };
+        errors.push(CallError {
+            message: format!(
+                "expected {at_least}{}, found {}",
+                count(params.len(), "argument"),
+                args.len()
+            ),
+            start_index,
+            end_index,
+            label: Label {
+                message: format!("{} is declared here", name.text),
+                start_index: signature.0,
+                end_index: signature.1,
+            },
+        });
+        return;
+    }
+
+    for (arg, param) in args.iter().zip(params) {
+        let (Some(found), Some(expected)) =
+            (datatype(arg, program, resolution), declared_type(param))
+        else {
+            continue;
+        };
+        let (Some((start_index, end_index)), Some((param_start, param_end))) =
+            (arg.range(), param.range())
+        else {
+            continue;
+        };
+        if !compatible(&expected, &found, enums) {
+            errors.push(CallError {
+                message: format!("mismatched types: expected {expected}, found {found}"),
+                start_index,
+                end_index,
+                label: Label {
+                    message: format!("parameter of {} declared here", name.text),
+                    start_index: param_start,
+                    end_index: param_end,
+                },
+            });
+        }
+    }
+}
+
+// The function, variable or parameter `name` refers to
+fn declaration<'a>(
+    program: &'a cst::SyntaxNode,
+    resolution: &resolver::Resolution,
+    name: &cst::SyntaxToken,
+) -> Option<&'a cst::SyntaxNode> {
+    let symbol = resolution.reference_at(name.start_index)?.symbol?;
+    declaring(program, resolution.symbols[symbol].definition)
+}
+
+// The node whose name starts at `start`
+fn declaring(node: &cst::SyntaxNode, start: usize) -> Option<&cst::SyntaxNode> {
+    let mut identifiers = node.child_tokens().filter(is_identifier);
+    let name = match node.kind {
+        cst::NodeKind::FnDecl | cst::NodeKind::Extern => identifiers.next(),
+        // The type comes first
+        cst::NodeKind::VarDecl | cst::NodeKind::Param => identifiers.nth(1),
+        _ => None,
+    };
+    if name.is_some_and(|name| name.start_index == start) {
+        return Some(node);
+    }
+    node.child_nodes().find_map(|child| declaring(child, start))
+}
+
+// The range of a function's signature, from `fn` (or `extern`) to the end
+// of its return type
+fn signature(function: &cst::SyntaxNode) -> Option<(usize, usize)> {
+    let (start, end) = function.range()?;
+    let body = function
+        .child_nodes()
+        .find(|node| node.kind == cst::NodeKind::Scope)
+        .and_then(|body| body.range());
+    let Some((body, _)) = body else {
+        return Some((start, end));
+    };
+    let last = function
+        .tokens()
+        .into_iter()
+        .take_while(|token| token.start_index < body)
+        .last()?;
+    Some((start, last.end_index()))
+}
+
+// The type a declaration, parameter or cast is written with, like `int*`.
+// Arrays aren't values, so they don't have one
+fn declared_type(node: &cst::SyntaxNode) -> Option<String> {
+    if !matches!(
+        node.kind,
+        cst::NodeKind::VarDecl | cst::NodeKind::Param | cst::NodeKind::Cast
+    ) {
+        return None;
+    }
+    let mut tokens = node
+        .child_tokens()
+        .skip_while(|token| !is_identifier(token));
+    let mut datatype = tokens.next()?.text.clone();
+    for token in tokens.by_ref() {
+        match token.token_type {
+            lexer::TokenType::Star => datatype.push('*'),
+            _ => break,
+        }
+    }
+    match tokens.next() {
+        Some(token) if token.token_type == lexer::TokenType::OpenBracket => None,
+        _ => Some(datatype),
+    }
+}
+
+// The type of an argument, if it's clear without the generator: literals,
+// variables, casts, sizes, and the pointers made from and read through them
+fn datatype(
+    node: &cst::SyntaxNode,
+    program: &cst::SyntaxNode,
+    resolution: &resolver::Resolution,
+) -> Option<String> {
+    let first = node.child_tokens().next();
+    match node.kind {
+        cst::NodeKind::Literal => match first?.token_type {
+            lexer::TokenType::Integer => Some("int".to_owned()),
+            lexer::TokenType::Float => Some("float".to_owned()),
+            lexer::TokenType::True | lexer::TokenType::False => Some("bool".to_owned()),
+            lexer::TokenType::Char => Some("char".to_owned()),
+            lexer::TokenType::Null => Some("null".to_owned()),
+            _ => None,
+        },
+        cst::NodeKind::Paren => datatype(node.child_nodes().next()?, program, resolution),
+        cst::NodeKind::Identifier => declared_type(declaration(program, resolution, first?)?),
+        cst::NodeKind::Cast => declared_type(node),
+        cst::NodeKind::SizeOf => Some("int".to_owned()),
+        cst::NodeKind::UnaryOp => {
+            let operand = datatype(node.child_nodes().next()?, program, resolution)?;
+            match first?.token_type {
+                lexer::TokenType::Ampersand => Some(format!("{operand}*")),
+                lexer::TokenType::Star => operand.strip_suffix('*').map(str::to_owned),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+// Arguments convert like any other stored value: numbers to each other
+// like in C, pointers to bools and null to any pointer. Pointers are only
+// passed as the type they're declared with. Which enum an int can be, and
+// structs and typedefs, which are the same type under different names, are
+// left to the generator
+fn compatible(expected: &str, found: &str, enums: &[String]) -> bool {
+    let class = |datatype: &str| match datatype {
+        "bool" => Some(Class::Bool),
+        "int" | "char" => Some(Class::Integer),
+        "float" => Some(Class::Float),
+        "null" => Some(Class::Null),
+        _ if enums.iter().any(|name| name == datatype) => Some(Class::Enum),
+        _ if datatype.ends_with('*') => Some(Class::Pointer),
+        _ => None,
+    };
+    expected == found
+        || match (class(found), class(expected)) {
+            (Some(Class::Pointer), Some(Class::Pointer)) => false,
+            (Some(Class::Integer | Class::Bool), Some(Class::Enum)) => true,
+            (Some(from), Some(to)) => conversions::converts(from, to),
+            _ => true,
+        }
+}
+
+fn count(n: usize, noun: &str) -> String {
+    match n {
+        1 => format!("1 {noun}"),
+        _ => format!("{n} {noun}s"),
+    }
+}
+
+fn is_identifier(token: &&cst::SyntaxToken) -> bool {
+    token.token_type == lexer::TokenType::Identifier
+}
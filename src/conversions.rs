@@ -0,0 +1,147 @@
+use crate::generator::Datatype;
+
+/// The group of types a datatype belongs to as far as conversions go. A new
+/// datatype only has to be put in a class to convert like the rest of it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Class {
+    Bool,
+    Integer,
+    Enum,
+    Float,
+    Pointer,
+    /// Only `null` is in it
+    Null,
+    Array,
+    Struct,
+    Function,
+}
+
+impl Class {
+    pub fn of(datatype: &Datatype) -> Class {
+        match datatype {
+            // Only bool is a single unsigned byte
+            Datatype::Single {
+                size: 1,
+                signed: false,
+            } => Class::Bool,
+            Datatype::Single { .. } => Class::Integer,
+            Datatype::Enum { .. } => Class::Enum,
+            Datatype::Float { .. } => Class::Float,
+            Datatype::Pointer { .. } => Class::Pointer,
+            Datatype::Null => Class::Null,
+            Datatype::Array { .. } => Class::Array,
+            // Unions convert like structs, only to themselves
+            Datatype::Struct { .. } | Datatype::Union { .. } => Class::Struct,
+            Datatype::Function { .. } => Class::Function,
+        }
+    }
+}
+
+/// What's done to a value stored where a value of another type is expected,
+/// like a variable, parameter or return value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Conversion {
+    /// The value is used as it is. Integers of another width are extended
+    /// when they're loaded and truncated when they're stored
+    Keep,
+    /// Any value other than zero becomes 1
+    ToBool,
+    IntToFloat,
+    /// Truncated toward zero, like in C
+    FloatToInt,
+    /// An array becomes a pointer to its first element
+    Decay,
+    /// Only the bytes that fit in the narrower type are kept, and extended
+    /// the way a value of that type is when it's loaded
+    Narrow,
+}
+
+// Every implicit conversion: the class converted from, the class converted
+// to, and how. Pairs that aren't listed need a cast
+const CONVERSIONS: &[(Class, Class, Conversion)] = &[
+    (Class::Bool, Class::Bool, Conversion::Keep),
+    (Class::Bool, Class::Integer, Conversion::Keep),
+    (Class::Bool, Class::Float, Conversion::IntToFloat),
+    (Class::Integer, Class::Bool, Conversion::ToBool),
+    (Class::Integer, Class::Integer, Conversion::Keep),
+    (Class::Integer, Class::Float, Conversion::IntToFloat),
+    (Class::Enum, Class::Bool, Conversion::ToBool),
+    (Class::Enum, Class::Integer, Conversion::Keep),
+    (Class::Enum, Class::Enum, Conversion::Keep),
+    (Class::Enum, Class::Float, Conversion::IntToFloat),
+    (Class::Float, Class::Integer, Conversion::FloatToInt),
+    (Class::Float, Class::Float, Conversion::Keep),
+    (Class::Pointer, Class::Bool, Conversion::ToBool),
+    (Class::Pointer, Class::Pointer, Conversion::Keep),
+    (Class::Null, Class::Bool, Conversion::ToBool),
+    (Class::Null, Class::Pointer, Conversion::Keep),
+    (Class::Null, Class::Function, Conversion::Keep),
+    (Class::Array, Class::Pointer, Conversion::Decay),
+    (Class::Struct, Class::Struct, Conversion::Keep),
+    (Class::Function, Class::Function, Conversion::Keep),
+];
+
+// The conversions only a cast makes, on top of the implicit ones. The
+// target of a pointer and the enum an int is cast to don't matter to a cast
+const CASTS: &[(Class, Class, Conversion)] = &[
+    (Class::Integer, Class::Enum, Conversion::Keep),
+    (Class::Enum, Class::Enum, Conversion::Keep),
+    (Class::Integer, Class::Pointer, Conversion::Keep),
+    (Class::Pointer, Class::Integer, Conversion::Narrow),
+    (Class::Pointer, Class::Pointer, Conversion::Keep),
+    (Class::Array, Class::Pointer, Conversion::Decay),
+];
+
+/// Whether values of class `from` convert to class `to` without a cast, for
+/// checks that only know the class of a type.
+pub fn converts(from: Class, to: Class) -> bool {
+    CONVERSIONS.iter().any(|(f, t, _)| *f == from && *t == to)
+}
+
+/// How a value of type `from` is converted to `to` without a cast, `None`
+/// if it can't be.
+pub fn implicit(from: &Datatype, to: &Datatype) -> Option<Conversion> {
+    let (from_class, to_class) = (Class::of(from), Class::of(to));
+    let (_, _, conversion) = CONVERSIONS
+        .iter()
+        .find(|(from, to, _)| *from == from_class && *to == to_class)?;
+
+    // Beyond their class, pointers have to point to the same type, arrays
+    // decay into pointers to their elements, and enums, structs and
+    // functions only convert to themselves
+    let compatible = match (from, to) {
+        (Datatype::Pointer { target: from }, Datatype::Pointer { target: to }) => from == to,
+        (Datatype::Array { element, .. }, Datatype::Pointer { target }) => element == target,
+        (Datatype::Enum { .. }, Datatype::Enum { .. })
+        | (Datatype::Struct { .. }, _)
+        | (Datatype::Union { .. }, _)
+        | (Datatype::Function { .. }, _) => from == to,
+        _ => true,
+    };
+    compatible.then_some(*conversion)
+}
+
+/// How a value of type `from` is converted to `to` by a cast, `None` if it
+/// can't be.
+pub fn explicit(from: &Datatype, to: &Datatype) -> Option<Conversion> {
+    if let Some(conversion) = implicit(from, to) {
+        return Some(conversion);
+    }
+    let (from_class, to_class) = (Class::of(from), Class::of(to));
+    CASTS
+        .iter()
+        .find(|(from, to, _)| *from == from_class && *to == to_class)
+        .map(|(_, _, conversion)| *conversion)
+}
+
+/// Whether two values can be compared. Pointers compare with pointers to the
+/// same type and with `null`, but not with numbers.
+pub fn comparable(left: &Datatype, right: &Datatype) -> bool {
+    match (Class::of(left), Class::of(right)) {
+        (Class::Null, Class::Null | Class::Pointer | Class::Function)
+        | (Class::Pointer | Class::Function, Class::Null) => true,
+        (Class::Pointer, Class::Pointer) => left == right,
+        (Class::Pointer, _) | (_, Class::Pointer) | (Class::Null, _) | (_, Class::Null) => false,
+        _ => true,
+    }
+}
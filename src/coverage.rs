@@ -0,0 +1,54 @@
+use crate::source;
+
+/// The mapping file written next to a program built with
+/// `--instrument-coverage`: the path of the source file on the first line,
+/// then the start index of every counted statement in the order of their
+/// counters.
+pub fn map(source: &str, statements: &[String]) -> String {
+    let mut map = format!("{source}\n");
+    for statement in statements {
+        map += &format!("{statement}\n");
+    }
+    map
+}
+
+/// Reads a mapping file back into the source path and statement indices.
+pub fn parse_map(data: &str) -> Result<(String, Vec<usize>), String> {
+    let mut lines = data.lines();
+    let source = lines.next().ok_or("The coverage map is empty")?;
+    let statements = lines
+        .map(|line| {
+            line.parse()
+                .map_err(|_| format!("{line} in the coverage map is not a statement index"))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok((source.to_owned(), statements))
+}
+
+/// Prefixes every line of `source` with how often the first statement
+/// starting on it ran, so `for (...) {` shows how often the loop was
+/// entered rather than how often its body ran. Lines where that statement
+/// never ran are marked with #####. Tabs are expanded to stops every
+/// `tab_width` columns, so the source lines up behind the counts.
+pub fn render(source: &str, statements: &[usize], counts: &[u64], tab_width: usize) -> String {
+    let mut report = String::new();
+    let mut start = 0;
+    for line in source.split_inclusive('\n') {
+        // Statements are character indices, like the lexer's
+        let end = start + line.chars().count();
+        let hits = statements
+            .iter()
+            .zip(counts)
+            .filter(|(statement, _)| (start..end).contains(*statement))
+            .min_by_key(|(statement, _)| **statement);
+        let hits = match hits.map(|(_, count)| *count) {
+            Some(0) => "#####".to_owned(),
+            Some(hits) => hits.to_string(),
+            None => String::new(),
+        };
+        let line = source::expand_tabs(line.trim_end_matches(['\r', '\n']), tab_width);
+        report += &format!("{hits:>8} | {line}\n");
+        start = end;
+    }
+    report
+}
@@ -0,0 +1,241 @@
+use crate::lexer;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NodeKind {
+    Program,
+    Scope,
+    BinOp,
+    UnaryOp,
+    Cast,
+    SizeOf,
+    Paren,
+    Literal,
+    VarDecl,
+    StructDecl,
+    StructType,
+    EnumDecl,
+    UnionDecl,
+    FnType,
+    Property,
+    TypeDef,
+    Identifier,
+    StructData,
+    If,
+    While,
+    For,
+    DoWhile,
+    Break,
+    Continue,
+    Switch,
+    Case,
+    Label,
+    Goto,
+    Ternary,
+    Assign,
+    IncDec,
+    FnDecl,
+    Attribute,
+    Param,
+    Call,
+    MemberAccess,
+    Index,
+    Return,
+    Extern,
+}
+
+#[derive(Debug, Clone)]
+pub struct SyntaxToken {
+    pub token_type: lexer::TokenType,
+    pub text: String,
+    pub start_index: usize,
+}
+
+impl SyntaxToken {
+    /// The index of the token's last character. Indices count characters,
+    /// so it's not the length of the text in bytes that matters.
+    pub fn end_index(&self) -> usize {
+        self.start_index + self.text.chars().count() - 1
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum SyntaxElement {
+    Node(SyntaxNode),
+    Token(SyntaxToken),
+    // Whitespace (and anything else the lexer skips) between two tokens
+    Trivia { text: String, start_index: usize },
+}
+
+/// A lossless syntax tree: every character of the source, including whitespace, is
+/// stored in exactly one leaf, so printing the tree reproduces the input.
+#[derive(Clone)]
+pub struct SyntaxNode {
+    pub kind: NodeKind,
+    pub children: Vec<SyntaxElement>,
+}
+
+impl SyntaxNode {
+    pub fn child_nodes(&self) -> impl Iterator<Item = &SyntaxNode> {
+        self.children.iter().filter_map(|child| match child {
+            SyntaxElement::Node(node) => Some(node),
+            _ => None,
+        })
+    }
+
+    /// All tokens in the subtree, in source order.
+    pub fn tokens(&self) -> Vec<&SyntaxToken> {
+        let mut tokens = vec![];
+        for child in &self.children {
+            match child {
+                SyntaxElement::Node(node) => tokens.extend(node.tokens()),
+                SyntaxElement::Token(token) => tokens.push(token),
+                SyntaxElement::Trivia { .. } => {}
+            }
+        }
+        tokens
+    }
+
+    /// The indices of the first and last character of the node's tokens.
+    pub fn range(&self) -> Option<(usize, usize)> {
+        let tokens = self.tokens();
+        let first = tokens.first()?;
+        let last = tokens.last()?;
+        Some((first.start_index, last.end_index()))
+    }
+
+    pub fn child_tokens(&self) -> impl Iterator<Item = &SyntaxToken> {
+        self.children.iter().filter_map(|child| match child {
+            SyntaxElement::Token(token) => Some(token),
+            _ => None,
+        })
+    }
+
+    fn fmt_tree(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        writeln!(f, "{:indent$}{:?}", "", self.kind, indent = depth * 2)?;
+        for child in &self.children {
+            match child {
+                SyntaxElement::Node(node) => node.fmt_tree(f, depth + 1)?,
+                SyntaxElement::Token(token) => writeln!(
+                    f,
+                    "{:indent$}{:?} {:?}@{}",
+                    "",
+                    token.token_type,
+                    token.text,
+                    token.start_index,
+                    indent = (depth + 1) * 2
+                )?,
+                SyntaxElement::Trivia { text, start_index } => writeln!(
+                    f,
+                    "{:indent$}Trivia {:?}@{}",
+                    "",
+                    text,
+                    start_index,
+                    indent = (depth + 1) * 2
+                )?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for SyntaxNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for child in &self.children {
+            match child {
+                SyntaxElement::Node(node) => write!(f, "{}", node)?,
+                SyntaxElement::Token(token) => write!(f, "{}", token.text)?,
+                SyntaxElement::Trivia { text, .. } => write!(f, "{}", text)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for SyntaxNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_tree(f, 0)
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Checkpoint(usize);
+
+/// Builds a `SyntaxNode` from the tokens consumed by the parser. The gaps
+/// between consecutive tokens are recovered from the source as trivia.
+pub struct Builder {
+    // Characters rather than a string, since the tokens' indices count them
+    source: Vec<char>,
+    position: usize,
+    stack: Vec<(NodeKind, Vec<SyntaxElement>)>,
+}
+
+impl Builder {
+    pub fn new(source: &str) -> Builder {
+        Builder {
+            source: source.chars().collect(),
+            position: 0,
+            stack: vec![(NodeKind::Program, vec![])],
+        }
+    }
+
+    fn children(&mut self) -> &mut Vec<SyntaxElement> {
+        &mut self.stack.last_mut().unwrap().1
+    }
+
+    /// Attaches everything between the last token and `index` to the current node.
+    pub fn trivia(&mut self, index: usize) {
+        if index <= self.position {
+            return;
+        }
+
+        let text = self
+            .source
+            .get(self.position..index)
+            .unwrap_or_default()
+            .iter()
+            .collect();
+        let start_index = self.position;
+        self.position = index;
+        self.children()
+            .push(SyntaxElement::Trivia { text, start_index });
+    }
+
+    pub fn token(&mut self, token: &lexer::Token) {
+        self.trivia(token.start_index);
+        self.children().push(SyntaxElement::Token(SyntaxToken {
+            token_type: token.token_type.clone(),
+            text: token.value.clone(),
+            start_index: token.start_index,
+        }));
+        self.position = token.end_index + 1;
+    }
+
+    pub fn start_node(&mut self, kind: NodeKind) {
+        self.stack.push((kind, vec![]));
+    }
+
+    pub fn checkpoint(&mut self) -> Checkpoint {
+        Checkpoint(self.children().len())
+    }
+
+    /// Starts a node that adopts every child added since `checkpoint`, used for
+    /// left-recursive constructs like binary operators.
+    pub fn start_node_at(&mut self, checkpoint: Checkpoint, kind: NodeKind) {
+        let children = self.children().split_off(checkpoint.0);
+        self.stack.push((kind, children));
+    }
+
+    pub fn finish_node(&mut self) {
+        let (kind, children) = self.stack.pop().unwrap();
+        self.children()
+            .push(SyntaxElement::Node(SyntaxNode { kind, children }));
+    }
+
+    pub fn finish(mut self) -> SyntaxNode {
+        let end = self.source.len();
+        self.trivia(end);
+        let (kind, children) = self.stack.pop().unwrap();
+        SyntaxNode { kind, children }
+    }
+}
@@ -0,0 +1,135 @@
+use crate::cst;
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+/// A version of the language. Every edition is a stable set of features, so
+/// programs written for it keep compiling as the language evolves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Edition {
+    #[default]
+    E2024,
+}
+
+impl FromStr for Edition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "2024" => Ok(Edition::E2024),
+            _ => Err(format!("Unknown edition {s}, the latest is 2024")),
+        }
+    }
+}
+
+impl fmt::Display for Edition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Edition::E2024 => write!(f, "2024"),
+        }
+    }
+}
+
+/// Syntax that has to be opted into, either because it's experimental or
+/// because it's only part of newer editions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Feature {
+    /// Labels and `goto`. They shipped before editions existed, so they're
+    /// part of the first one
+    Goto,
+}
+
+impl Feature {
+    /// The first edition the feature is enabled in by default, `None` while
+    /// it's experimental.
+    pub fn edition(self) -> Option<Edition> {
+        match self {
+            Feature::Goto => Some(Edition::E2024),
+        }
+    }
+}
+
+impl FromStr for Feature {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "goto" => Ok(Feature::Goto),
+            _ => Err(format!("Unknown feature {s}")),
+        }
+    }
+}
+
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Feature::Goto => write!(f, "goto"),
+        }
+    }
+}
+
+/// The edition a program is written for plus the features enabled on top of it.
+#[derive(Debug, Clone, Default)]
+pub struct Features {
+    pub edition: Edition,
+    pub enabled: HashSet<Feature>,
+}
+
+impl Features {
+    pub fn new(edition: Edition, enabled: impl IntoIterator<Item = Feature>) -> Features {
+        Features {
+            edition,
+            enabled: enabled.into_iter().collect(),
+        }
+    }
+
+    pub fn is_enabled(&self, feature: Feature) -> bool {
+        self.enabled.contains(&feature)
+            || feature
+                .edition()
+                .is_some_and(|edition| edition <= self.edition)
+    }
+}
+
+#[derive(Debug)]
+pub struct FeatureError {
+    pub feature: Feature,
+    /// Start index of the first token that uses the feature
+    pub start_index: usize,
+}
+
+impl fmt::Display for FeatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.feature.edition() {
+            Some(edition) => write!(
+                f,
+                "{} requires edition {edition}, enable it with --edition {edition} or --feature {}",
+                self.feature, self.feature
+            ),
+            None => write!(
+                f,
+                "{} is experimental, enable it with --feature {}",
+                self.feature, self.feature
+            ),
+        }
+    }
+}
+
+/// Finds the first use of a feature that isn't enabled.
+pub fn check(cst: &cst::SyntaxNode, features: &Features) -> Result<(), FeatureError> {
+    let feature = match cst.kind {
+        cst::NodeKind::Label | cst::NodeKind::Goto => Some(Feature::Goto),
+        _ => None,
+    };
+    if let Some(feature) = feature.filter(|feature| !features.is_enabled(*feature)) {
+        return Err(FeatureError {
+            feature,
+            start_index: cst.range().map_or(0, |(start, _)| start),
+        });
+    }
+
+    for child in cst.child_nodes() {
+        check(child, features)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,64 @@
+/// A small FileCheck: matches the `CHECK` directives found in `directives`
+/// against `output`, e.g. the assembly generated for a fixture.
+///
+/// - `CHECK: text` finds a line containing `text` after the previous match
+/// - `CHECK-NEXT: text` requires `text` on the line right after the previous match
+/// - `CHECK-NOT: text` requires that `text` doesn't appear between the
+///   previous match and the next one (or the end of the output)
+///
+/// Directives may be prefixed by anything, so they can live in comments.
+pub fn check(directives: &str, output: &str) -> Result<(), String> {
+    let lines: Vec<&str> = output.lines().collect();
+    // Index of the first line the next CHECK may match
+    let mut position = 0;
+    let mut not: Vec<&str> = vec![];
+
+    for directive in directives.lines() {
+        if let Some(pattern) = pattern(directive, "CHECK-NOT:") {
+            not.push(pattern);
+        } else if let Some(pattern) = pattern(directive, "CHECK-NEXT:") {
+            if position == 0 {
+                return Err(format!("CHECK-NEXT: {pattern} has no previous match"));
+            }
+            match lines.get(position) {
+                Some(line) if line.contains(pattern) => position += 1,
+                line => {
+                    return Err(format!(
+                        "CHECK-NEXT: expected {pattern:?} on line {}, found {:?}",
+                        position + 1,
+                        line.unwrap_or(&"")
+                    ))
+                }
+            }
+            // There's nothing between two adjacent lines for CHECK-NOT to reject
+            not.clear();
+        } else if let Some(pattern) = pattern(directive, "CHECK:") {
+            let found = lines[position..]
+                .iter()
+                .position(|line| line.contains(pattern))
+                .ok_or(format!(
+                    "CHECK: {pattern:?} not found after line {}",
+                    position
+                ))?;
+            ensure_absent(&not, &lines[position..position + found])?;
+            not.clear();
+            position += found + 1;
+        }
+    }
+
+    ensure_absent(&not, &lines[position..])
+}
+
+fn pattern<'a>(directive: &'a str, prefix: &str) -> Option<&'a str> {
+    let start = directive.find(prefix)? + prefix.len();
+    Some(directive[start..].trim())
+}
+
+fn ensure_absent(patterns: &[&str], lines: &[&str]) -> Result<(), String> {
+    for pattern in patterns {
+        if let Some(line) = lines.iter().find(|line| line.contains(pattern)) {
+            return Err(format!("CHECK-NOT: {pattern:?} found in {line:?}"));
+        }
+    }
+    Ok(())
+}
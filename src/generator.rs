@@ -1,22 +1,137 @@
+use crate::asm::{self, Address, Instruction, Register, Scale};
 use crate::ast;
+use crate::conversions::{self, Conversion};
+use crate::tables::SideTable;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Datatype {
     Single {
         size: usize,
+        /// Whether the value is sign extended when it's loaded into a
+        /// register, rather than zero extended
+        signed: bool,
     },
+    /// An IEEE 754 floating point number. Its bits are kept in the general
+    /// purpose registers and only moved to an xmm register for arithmetic
+    Float { size: usize },
     Struct {
         size: usize,
-        offsets: Vec<(String, usize)>,
+        /// The largest alignment of its properties
+        align: usize,
+        properties: Vec<Property>,
+    },
+    /// Properties that all start at offset 0 and share their bytes
+    Union {
+        /// The size of its largest property, padded to its alignment
+        size: usize,
+        /// The largest alignment of its properties
+        align: usize,
+        properties: Vec<Property>,
+    },
+    /// `len` values of the element type one after another
+    Array { element: Box<Datatype>, len: usize },
+    /// The address of a value of the target type, `int*`
+    Pointer { target: Box<Datatype> },
+    /// The type of `null`, which converts to every pointer type
+    Null,
+    /// An int whose values have names, declared with `enum`
+    Enum { name: String },
+    /// A pointer to a function with this signature
+    Function {
+        params: Vec<Datatype>,
+        return_type: Option<Box<Datatype>>,
     },
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct Property {
+    pub name: String,
+    /// Distance from the start of the struct
+    pub offset: usize,
+    pub datatype: Datatype,
+    /// The bits stored when a struct literal leaves the property out
+    pub default: Option<i64>,
+    /// For a bitfield, the lowest of its bits and how many it has. It's
+    /// stored in the unit of its type's size at `offset`, which other
+    /// bitfields can share
+    pub bits: Option<(u32, u32)>,
+}
+
 impl Datatype {
     pub fn size(&self) -> usize {
         match *self {
-            Datatype::Single { size } => size,
-            Datatype::Struct { size, offsets: _ } => size,
+            Datatype::Single { size, .. } | Datatype::Float { size } => size,
+            Datatype::Enum { .. } => 4,
+            Datatype::Struct { size, .. } | Datatype::Union { size, .. } => size,
+            Datatype::Array { ref element, len } => element.size() * len,
+            Datatype::Pointer { .. } | Datatype::Null | Datatype::Function { .. } => 8,
+        }
+    }
+
+    /// What the address of a value of this type has to be a multiple of.
+    /// Like in C, scalars are aligned to their size.
+    pub fn align(&self) -> usize {
+        match *self {
+            Datatype::Single { size, .. } | Datatype::Float { size } => size.max(1),
+            Datatype::Enum { .. } => 4,
+            Datatype::Struct { align, .. } | Datatype::Union { align, .. } => align,
+            Datatype::Array { ref element, .. } => element.align(),
+            Datatype::Pointer { .. } | Datatype::Null | Datatype::Function { .. } => 8,
+        }
+    }
+
+    /// The operand size values of this type are moved with, structs and
+    /// arrays don't have one.
+    pub fn operand_size(&self) -> Result<asm::Size, GeneratorError> {
+        match self {
+            Datatype::Single { size, .. } | Datatype::Float { size } => {
+                asm::Size::from_bytes(*size).ok_or(GeneratorError::NotAScalar)
+            }
+            Datatype::Enum { .. } => Ok(asm::Size::Dword),
+            Datatype::Struct { .. } | Datatype::Union { .. } | Datatype::Array { .. } => {
+                Err(GeneratorError::NotAScalar)
+            }
+            Datatype::Pointer { .. } | Datatype::Null | Datatype::Function { .. } => {
+                Ok(asm::Size::Qword)
+            }
+        }
+    }
+
+    /// Loads a value of this type at `address` into the whole of `dst`, so
+    /// the bytes next to a small variable don't end up in the register.
+    pub fn load(&self, dst: Register, address: Address) -> Result<Instruction, GeneratorError> {
+        Ok(Instruction::Extend {
+            dst,
+            src: address.sized(self.operand_size()?),
+            signed: self.is_signed(),
+        })
+    }
+
+    /// Like `load`, for a value of this type that's in `src` instead.
+    pub fn extend(&self, dst: Register, src: Register) -> Result<Instruction, GeneratorError> {
+        Ok(Instruction::ExtendRegister {
+            dst,
+            src,
+            size: self.operand_size()?,
+            signed: self.is_signed(),
+        })
+    }
+
+    // Whether a value of this type is sign extended when it's loaded into
+    // a register, rather than zero extended
+    fn is_signed(&self) -> bool {
+        match self {
+            Datatype::Single { signed, .. } => *signed,
+            Datatype::Enum { .. } => true,
+            Datatype::Float { .. }
+            | Datatype::Struct { .. }
+            | Datatype::Union { .. }
+            | Datatype::Array { .. }
+            | Datatype::Pointer { .. }
+            | Datatype::Null
+            | Datatype::Function { .. } => false,
         }
     }
 }
@@ -24,7 +139,89 @@ impl Datatype {
 #[derive(Debug)]
 pub struct VariableData {
     pub datatype: Datatype,
+    /// Distance from rbp down to the first byte of the variable
     pub location: usize,
+    /// The register a parameter of a fastcall function stays in, instead of
+    /// a slot of the frame. It has no address
+    pub register: Option<Register>,
+}
+
+/// The signature of a function that can be called.
+#[derive(Debug, Clone)]
+pub struct FunctionData {
+    pub params: Vec<Datatype>,
+    /// `None` if the function doesn't return a value
+    pub return_type: Option<Datatype>,
+    /// Whether more arguments than `params` can be passed
+    pub variadic: bool,
+    /// Whether the function is defined outside of the program. Those are
+    /// called with the C calling convention, which passes floats in xmm
+    /// registers rather than with the integers
+    pub external: bool,
+    /// Whether the function is called the fast way, with every argument in
+    /// one of `FASTCALL_REGISTERS` and without a frame, see `fastcall`
+    pub fastcall: bool,
+}
+
+// Where an argument is passed
+enum ArgumentLocation {
+    Register(Register),
+    /// The index of an xmm register, and whether the float is promoted to a
+    /// double like C does for variadic arguments
+    Vector(usize, bool),
+    /// The index of a stack slot, and whether the float is promoted
+    Stack(usize, bool),
+    /// A struct split over the registers, one per eight bytes
+    StructRegisters(Datatype, Vec<Register>),
+    /// A struct copied to the stack, starting at the slot with this index
+    StructStack(Datatype, usize),
+}
+
+/// What an instrumented program counts. The counts are written to `path`
+/// when the program exits, see `profile::parse`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Instrumentation {
+    /// How often every function is called
+    Profile { path: String },
+    /// How often every statement runs. `statements` is where each statement
+    /// starts in the source, in the order they were parsed
+    Coverage {
+        path: String,
+        statements: Vec<usize>,
+    },
+}
+
+impl Instrumentation {
+    pub fn path(&self) -> &str {
+        match self {
+            Instrumentation::Profile { path } | Instrumentation::Coverage { path, .. } => path,
+        }
+    }
+}
+
+/// How the program is started.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Entry {
+    /// The top-level code runs from this symbol and exits the process with
+    /// its value when it's done
+    Symbol(String),
+    /// No entry point is generated and every function is exported, for code
+    /// that's called by a kernel or another runtime with its own entry point
+    Freestanding,
+}
+
+impl Default for Entry {
+    fn default() -> Entry {
+        Entry::Symbol(crate::callgraph::ENTRY.to_owned())
+    }
+}
+
+/// Where `break` and `continue` jump to inside a loop or switch.
+#[derive(Debug, Clone)]
+pub struct LoopLabels {
+    pub break_label: String,
+    /// `None` inside a switch that isn't in a loop
+    pub continue_label: Option<String>,
 }
 
 pub struct Environment<'a> {
@@ -32,9 +229,249 @@ pub struct Environment<'a> {
     pub base_stack: usize,
     pub variables: HashMap<String, VariableData>,
     pub datatypes: HashMap<String, Datatype>,
+    /// The type and value of every enum member declared in this environment
+    pub members: HashMap<String, (Datatype, i64)>,
+    /// Number of labels handed out so far, only tracked by the root environment
+    pub label_count: Cell<usize>,
+    /// How far below rbp the deepest variable reaches, only tracked by the
+    /// outermost environment of a function
+    pub frame_size: Cell<usize>,
+    /// The read-only data the code refers to, only tracked by the root environment
+    pub constants: RefCell<Vec<asm::Constant>>,
+    /// The type of every expression worked out so far. Only tracked by the
+    /// root environment
+    pub types: RefCell<SideTable<Datatype>>,
+    /// The bits of every property default, which are known at compile
+    /// time. Only tracked by the root environment
+    pub values: RefCell<SideTable<i64>>,
+    /// The nodes whose code couldn't be generated, from the one the error
+    /// is about out to the program. Only tracked by the root environment
+    pub failed: RefCell<Vec<ast::NodeId>>,
+    /// Every function declared so far, only tracked by the root environment
+    pub functions: HashMap<String, FunctionData>,
+    /// How the program is started, only used by the root environment
+    pub entry: Entry,
+    /// What the program counts, if anything. Only used by the root environment
+    pub instrumentation: Option<Instrumentation>,
+    /// The name of every counter, in the order of their counters: the
+    /// function or the start index of the statement being counted. Only
+    /// tracked by the root environment
+    pub counters: RefCell<Vec<String>>,
+    /// How many statements have been generated so far, only tracked by the
+    /// root environment
+    pub statements: Cell<usize>,
+    /// Whether variables are guarded by canaries and the slots of ended
+    /// scopes are poisoned. Only used by the root environment
+    pub sanitize: bool,
+    /// Whether divisions check for a zero divisor, so the program aborts
+    /// with a message rather than crashing with SIGFPE. Only used by the
+    /// root environment
+    pub checked: bool,
+    /// How much the program is optimized, the level of `-O`. Only used by
+    /// the root environment
+    pub optimize: u8,
+    /// Whether the program is linked with libc, whose stdio buffers have to
+    /// be flushed before it exits. Only used by the root environment
+    pub libc: bool,
+    /// The location of the canary above each variable of this environment,
+    /// with the variable's name
+    pub canaries: Vec<(usize, String)>,
+    /// The asm label of every label in the function, only tracked by the
+    /// outermost environment of a function
+    pub labels: HashMap<String, String>,
+    /// The innermost loop or switch that's being generated in this environment, if any
+    pub loop_labels: Option<LoopLabels>,
+    /// Where `return` jumps to, only set in the outermost environment of a function
+    pub return_label: Option<String>,
+    /// What the function returns, only set in the outermost environment of a function
+    pub return_type: Option<Datatype>,
+    /// Bytes of temporaries placed after the variables of this environment
+    /// by the statement being generated
+    pub temporaries: Cell<usize>,
+    /// Where the next call puts a struct it returns through memory, instead
+    /// of a temporary. Set when the call initializes a variable, and taken
+    /// by the call before its arguments can see it
+    pub destination: Option<Address>,
+    /// Whether this is the outermost environment of a function (or of the
+    /// entry point). Variables, labels and loops of the environments around
+    /// it belong to another stack frame and can't be seen from inside
+    pub is_frame: bool,
+}
+
+impl Environment<'_> {
+    /// The top-level environment with the builtin datatypes declared.
+    pub fn new() -> Environment<'static> {
+        Environment {
+            parent: None,
+            base_stack: 0,
+            variables: HashMap::new(),
+            datatypes: HashMap::from([
+                (
+                    String::from("int"),
+                    Datatype::Single {
+                        size: 4,
+                        signed: true,
+                    },
+                ),
+                (
+                    String::from("bool"),
+                    Datatype::Single {
+                        size: 1,
+                        signed: false,
+                    },
+                ),
+                (
+                    String::from("char"),
+                    Datatype::Single {
+                        size: 1,
+                        signed: true,
+                    },
+                ),
+                (String::from("float"), Datatype::Float { size: 4 }),
+            ]),
+            members: HashMap::new(),
+            label_count: Cell::new(0),
+            frame_size: Cell::new(0),
+            constants: RefCell::new(vec![]),
+            types: RefCell::new(SideTable::new()),
+            values: RefCell::new(SideTable::new()),
+            failed: RefCell::new(vec![]),
+            functions: HashMap::new(),
+            entry: Entry::default(),
+            instrumentation: None,
+            counters: RefCell::new(vec![]),
+            statements: Cell::new(0),
+            sanitize: false,
+            checked: false,
+            optimize: 0,
+            libc: false,
+            canaries: vec![],
+            labels: HashMap::new(),
+            loop_labels: None,
+            return_label: None,
+            return_type: None,
+            temporaries: Cell::new(0),
+            destination: None,
+            is_frame: true,
+        }
+    }
 }
 
 impl<'a> Environment<'a> {
+    /// A nested environment whose variables are placed after the ones of this one.
+    pub fn child(&self) -> Environment<'_> {
+        Environment {
+            parent: Some(self),
+            variables: HashMap::new(),
+            datatypes: HashMap::new(),
+            members: HashMap::new(),
+            base_stack: self.stack_end(),
+            label_count: Cell::new(0),
+            frame_size: Cell::new(0),
+            constants: RefCell::new(vec![]),
+            types: RefCell::new(SideTable::new()),
+            values: RefCell::new(SideTable::new()),
+            failed: RefCell::new(vec![]),
+            functions: HashMap::new(),
+            entry: Entry::default(),
+            instrumentation: None,
+            counters: RefCell::new(vec![]),
+            statements: Cell::new(0),
+            sanitize: false,
+            checked: false,
+            optimize: 0,
+            libc: false,
+            canaries: vec![],
+            labels: HashMap::new(),
+            loop_labels: None,
+            return_label: None,
+            return_type: None,
+            temporaries: Cell::new(0),
+            destination: None,
+            is_frame: false,
+        }
+    }
+
+    /// The environment of a function's body, with a stack frame of its own.
+    pub fn function(&self) -> Environment<'_> {
+        Environment {
+            parent: Some(self),
+            variables: HashMap::new(),
+            datatypes: HashMap::new(),
+            members: HashMap::new(),
+            base_stack: 0,
+            label_count: Cell::new(0),
+            frame_size: Cell::new(0),
+            constants: RefCell::new(vec![]),
+            types: RefCell::new(SideTable::new()),
+            values: RefCell::new(SideTable::new()),
+            failed: RefCell::new(vec![]),
+            functions: HashMap::new(),
+            entry: Entry::default(),
+            instrumentation: None,
+            counters: RefCell::new(vec![]),
+            statements: Cell::new(0),
+            sanitize: false,
+            checked: false,
+            optimize: 0,
+            libc: false,
+            canaries: vec![],
+            labels: HashMap::new(),
+            loop_labels: None,
+            return_label: None,
+            return_type: None,
+            temporaries: Cell::new(0),
+            destination: None,
+            is_frame: true,
+        }
+    }
+
+    // How far below rbp the variables and temporaries of this environment reach
+    fn stack_end(&self) -> usize {
+        let variables = self.variables.values().map(|var| var.location);
+        let canaries = self.canaries.iter().map(|(location, _)| *location);
+        let end = variables.chain(canaries).max().unwrap_or(0);
+        end.max(self.base_stack) + self.temporaries.get()
+    }
+
+    /// Declares a variable placed right after the ones already in this
+    /// environment, padded so its address is a multiple of its alignment.
+    pub fn allocate_var(&mut self, name: &str, datatype: Datatype) -> Result<(), GeneratorError> {
+        // The canary sits right above the variable, where writing past its
+        // end ends up
+        if self.sanitizing() {
+            let location = (self.stack_end() + 8).next_multiple_of(8);
+            self.reserve_stack(location);
+            self.canaries.push((location, name.to_owned()));
+        }
+        let location = (self.stack_end() + datatype.size()).next_multiple_of(datatype.align());
+        self.declare_var(
+            name,
+            VariableData {
+                datatype,
+                location,
+                register: None,
+            },
+        )
+    }
+
+    /// Reserves frame space for a value that doesn't fit in a register, like
+    /// a struct literal. It's only valid until the end of the statement,
+    /// after which the space is reused.
+    pub fn temporary(&self, size: usize) -> Address {
+        let end = self.stack_end();
+        let location = (end + size).next_multiple_of(8);
+        self.temporaries
+            .set(self.temporaries.get() + location - end);
+        self.reserve_stack(location);
+        Address::new(Register::Rbp, -(location as isize))
+    }
+
+    // Frees the temporaries of the statement that was just generated
+    fn end_statement(&self) {
+        self.temporaries.set(0);
+    }
+
     pub fn declare_var(
         &mut self,
         name: &str,
@@ -44,27 +481,220 @@ impl<'a> Environment<'a> {
             return Err(GeneratorError::VariableAlreadyExists);
         }
 
+        self.reserve_stack(var_data.location);
         self.variables.insert(name.to_string(), var_data);
         Ok(())
     }
 
+    // Makes sure the frame reaches at least `depth` bytes below rbp
+    fn reserve_stack(&self, depth: usize) {
+        match self.parent {
+            Some(parent) if !self.is_frame => parent.reserve_stack(depth),
+            _ => self.frame_size.set(self.frame_size.get().max(depth)),
+        }
+    }
+
     pub fn lookup_var(&self, name: &str) -> Result<&VariableData, GeneratorError> {
         let env = self.resolve_var(name)?;
         let var = &env.variables[name];
         Ok(var)
     }
 
-    pub fn resolve_var(&self, name: &str) -> Result<&Environment, GeneratorError> {
+    pub fn resolve_var(&self, name: &str) -> Result<&Environment<'_>, GeneratorError> {
         if self.variables.contains_key(name) {
             return Ok(self);
         }
 
         match self.parent {
-            Some(parent) => parent.resolve_var(name),
-            None => Err(GeneratorError::VariableDoesNotExist),
+            Some(parent) if !self.is_frame => parent.resolve_var(name),
+            _ => Err(GeneratorError::VariableDoesNotExist),
+        }
+    }
+
+    /// Returns a label that's unique within the whole program.
+    pub fn new_label(&self, name: &str) -> String {
+        match self.parent {
+            Some(parent) => parent.new_label(name),
+            None => {
+                let id = self.label_count.get();
+                self.label_count.set(id + 1);
+                format!(".{name}_{id}")
+            }
+        }
+    }
+
+    pub fn sanitizing(&self) -> bool {
+        match self.parent {
+            Some(parent) => parent.sanitizing(),
+            None => self.sanitize,
+        }
+    }
+
+    pub fn checking(&self) -> bool {
+        match self.parent {
+            Some(parent) => parent.checking(),
+            None => self.checked,
+        }
+    }
+
+    /// The address of the counter of how often `function` is called, `None`
+    /// unless the program is instrumented for profiling.
+    pub fn counter(&self, function: &str) -> Option<Address> {
+        match self.parent {
+            Some(parent) => parent.counter(function),
+            None => match self.instrumentation {
+                Some(Instrumentation::Profile { .. }) => Some(self.add_counter(function)),
+                _ => None,
+            },
+        }
+    }
+
+    /// The address of the counter of how often the next statement runs,
+    /// `None` unless the program is instrumented for coverage. Statements
+    /// are matched up with where they start in the source by the order
+    /// they're generated in, so this has to be called for every statement,
+    /// even the ones that aren't counted.
+    pub fn statement_counter(&self, counted: bool) -> Option<Address> {
+        match self.parent {
+            Some(parent) => parent.statement_counter(counted),
+            None => {
+                let index = self.statements.get();
+                self.statements.set(index + 1);
+                // The prelude's statements come after the program's and
+                // aren't in its source, so they're never counted
+                match &self.instrumentation {
+                    Some(Instrumentation::Coverage { statements, .. }) if counted => statements
+                        .get(index)
+                        .map(|start| self.add_counter(&start.to_string())),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    fn add_counter(&self, name: &str) -> Address {
+        let mut counters = self.counters.borrow_mut();
+        counters.push(name.to_owned());
+        Address::label(COUNTERS).offset_by(8 * (counters.len() - 1))
+    }
+
+    /// Places `values` in the read-only data section and returns their address.
+    pub fn constant(&self, name: &str, size: asm::Size, values: Vec<u64>) -> Address {
+        match self.parent {
+            Some(parent) => parent.constant(name, size, values),
+            None => {
+                // Local labels starting with a dot belong to the function before
+                // them, `..@` labels can be referred to from anywhere
+                let id = self.label_count.get();
+                self.label_count.set(id + 1);
+                let label = format!("..@{name}_{id}");
+                self.constants.borrow_mut().push(asm::Constant {
+                    label: label.clone(),
+                    size,
+                    values,
+                });
+                Address::label(&label)
+            }
+        }
+    }
+
+    /// The type `node` was found to have, if it's an expression whose type
+    /// has been needed. Once the program is generated, that's every
+    /// expression that's converted, compared or does arithmetic.
+    pub fn type_of(&self, node: &ast::Node) -> Option<Datatype> {
+        match self.parent {
+            Some(parent) => parent.type_of(node),
+            None => self.types.borrow().get(node.id()).cloned(),
+        }
+    }
+
+    // Records that `node` is one of the nodes an error happened in
+    fn record_failure(&self, node: &ast::Node) {
+        match self.parent {
+            Some(parent) => parent.record_failure(node),
+            None => self.failed.borrow_mut().push(node.id()),
+        }
+    }
+
+    fn record_type(&self, node: &ast::Node, datatype: &Datatype) {
+        match self.parent {
+            Some(parent) => parent.record_type(node, datatype),
+            None => self.types.borrow_mut().insert(node.id(), datatype.clone()),
+        }
+    }
+
+    /// The bits `node` stands for, if it's a property default.
+    pub fn value_of(&self, node: &ast::Node) -> Option<i64> {
+        match self.parent {
+            Some(parent) => parent.value_of(node),
+            None => self.values.borrow().get(node.id()).copied(),
+        }
+    }
+
+    fn record_value(&self, node: &ast::Node, value: i64) {
+        match self.parent {
+            Some(parent) => parent.record_value(node, value),
+            None => self.values.borrow_mut().insert(node.id(), value),
+        }
+    }
+
+    pub fn lookup_function(&self, name: &str) -> Result<&FunctionData, GeneratorError> {
+        match self.parent {
+            Some(parent) => parent.lookup_function(name),
+            None => self
+                .functions
+                .get(name)
+                .ok_or(GeneratorError::FunctionDoesNotExist),
         }
     }
 
+    pub fn lookup_label(&self, name: &str) -> Result<&str, GeneratorError> {
+        match (self.labels.get(name), self.parent) {
+            (Some(label), _) => Ok(label),
+            (None, Some(parent)) if !self.is_frame => parent.lookup_label(name),
+            _ => Err(GeneratorError::LabelDoesNotExist),
+        }
+    }
+
+    /// The label of the epilogue of the function the code is generated for.
+    pub fn lookup_return(&self) -> Result<&str, GeneratorError> {
+        match (&self.return_label, self.parent) {
+            (Some(label), _) => Ok(label),
+            (None, Some(parent)) if !self.is_frame => parent.lookup_return(),
+            _ => Err(GeneratorError::ReturnOutsideFunction),
+        }
+    }
+
+    /// What the function being generated returns, `None` if it doesn't return a value.
+    pub fn lookup_return_type(&self) -> Option<&Datatype> {
+        match (&self.return_label, self.parent) {
+            (Some(_), _) => self.return_type.as_ref(),
+            (None, Some(parent)) if !self.is_frame => parent.lookup_return_type(),
+            _ => None,
+        }
+    }
+
+    /// The labels of the innermost loop or switch around the code being generated.
+    pub fn lookup_loop(&self) -> Result<&LoopLabels, GeneratorError> {
+        match (&self.loop_labels, self.parent) {
+            (Some(labels), _) => Ok(labels),
+            (None, Some(parent)) if !self.is_frame => parent.lookup_loop(),
+            _ => Err(GeneratorError::NotInLoop),
+        }
+    }
+
+    // Generates a loop body with `break` and `continue` jumping to `labels`
+    fn generate_loop_body(
+        &mut self,
+        body: &ast::Node,
+        labels: LoopLabels,
+    ) -> Result<String, GeneratorError> {
+        let outer = self.loop_labels.replace(labels);
+        let code = statement(body, self);
+        self.loop_labels = outer;
+        code
+    }
+
     pub fn declare_datatype(
         &mut self,
         name: &str,
@@ -79,12 +709,28 @@ impl<'a> Environment<'a> {
     }
 
     pub fn lookup_datatype(&self, name: &str) -> Result<Datatype, GeneratorError> {
+        // Pointer types aren't declared, `int*` exists wherever `int` does
+        if let Some(target) = name.strip_suffix('*') {
+            return Ok(Datatype::Pointer {
+                target: Box::new(self.lookup_datatype(target)?),
+            });
+        }
         let env = self.resolve_datatype(name)?;
         let datatype = env.datatypes[name].clone();
         Ok(datatype)
     }
 
-    pub fn resolve_datatype(&self, name: &str) -> Result<&Environment, GeneratorError> {
+    /// The type and value of the enum member `name`. Members are visible
+    /// wherever the enum is, functions included.
+    pub fn lookup_member(&self, name: &str) -> Result<&(Datatype, i64), GeneratorError> {
+        match (self.members.get(name), self.parent) {
+            (Some(member), _) => Ok(member),
+            (None, Some(parent)) => parent.lookup_member(name),
+            (None, None) => Err(GeneratorError::VariableDoesNotExist),
+        }
+    }
+
+    pub fn resolve_datatype(&self, name: &str) -> Result<&Environment<'_>, GeneratorError> {
         if self.datatypes.contains_key(name) {
             return Ok(self);
         }
@@ -103,154 +749,561 @@ pub enum GeneratorError {
     DatatypeAlreadyExists,
     DatatypeDoesNotExist,
     CannotAssignSingleValuetoStruct,
+    NotInLoop,
+    DuplicateCase,
+    DuplicateEnumValue,
+    EnumValueOutOfRange,
+    LabelAlreadyExists,
+    LabelDoesNotExist,
+    NotAnLvalue,
+    NotAScalar,
+    NestedFunction,
+    FunctionAlreadyExists,
+    FunctionDoesNotExist,
+    StructLiteralOutsideDeclaration,
+    ReturnOutsideFunction,
+    NotAFunction,
+    NotAnInteger,
+    NotAStruct,
+    PropertyDoesNotExist,
+    StatementOutsideFunction,
+    TooManyValues,
+    DuplicateProperty,
+    DefaultInUnion,
+    InvalidBitfield,
+    MissingProperty,
+    DefaultNotConstant,
+    WrongArgumentCount,
+    MismatchedStruct,
+    DivisionByZero,
+    EmptyArray,
+    NotAnArray,
+    IndexOutOfBounds,
+    NotAPointer,
+    MismatchedPointers,
+    MismatchedTypes,
+    InvalidCast,
 }
 
 impl ast::Node {
+    /// The code for the node. Expressions leave their value in rax and rsp
+    /// where they found it: anything they keep on the stack while evaluating
+    /// an operand is popped again before they're done, so expressions can be
+    /// nested as deep as the stack allows.
+    ///
+    /// Operands, arguments and the index of an assignment's target are
+    /// evaluated from left to right.
+    ///
+    /// When it fails, the nodes the error happened in are in `env.failed`.
     pub fn generate(&self, env: &mut Environment) -> Result<String, GeneratorError> {
+        self.code(env).inspect_err(|_| env.record_failure(self))
+    }
+
+    // The code for the node, see `generate`
+    fn code(&self, env: &mut Environment) -> Result<String, GeneratorError> {
         match self {
-            ast::Node::Program { body } => {
-                let mut code = "section .text
-    global _start
-_start:
-    push rbp
-    mov rbp, rsp
-    ".to_owned();
+            ast::Node::Program { body, .. } => {
+                let mut code = String::new();
+                let mut functions = String::new();
+                let mut externs = String::new();
+                declare_labels(self, env)?;
 
+                // Types and functions declared at the top level can be used
+                // anywhere in the file, so a function can call one defined
+                // further down. Types come first, signatures are made of them
+                for expr in body {
+                    if let ast::Node::StructDecl { .. }
+                    | ast::Node::EnumDecl { .. }
+                    | ast::Node::UnionDecl { .. }
+                    | ast::Node::TypeDef { .. } = expr
+                    {
+                        expr.generate(env)?;
+                    }
+                }
+                for expr in body {
+                    if let ast::Node::FnDecl { .. } | ast::Node::Extern { .. } = expr {
+                        declare_function(env, expr).inspect_err(|_| env.record_failure(expr))?;
+                    }
+                }
                 for expr in body {
-                    code += &expr.generate(env)?;
+                    if let ast::Node::FnDecl { name, .. } = expr {
+                        if fastcall(env, expr, self) {
+                            crate::debug!("codegen", "{name} is called the fast way");
+                            env.functions.get_mut(name).unwrap().fastcall = true;
+                        }
+                    }
                 }
+                // The entry point gets the first counter
+                let entry_counter = match &env.entry {
+                    Entry::Symbol(entry) => count_call(env, entry),
+                    Entry::Freestanding => String::new(),
+                };
 
-                code = format!(
-                    "{code}
-    mov rdi, rax
+                if let Entry::Symbol(entry) = &env.entry {
+                    if env.functions.contains_key(entry) {
+                        return Err(GeneratorError::FunctionAlreadyExists);
+                    }
+                }
+
+                for expr in body {
+                    crate::trace!("codegen", "generating {expr}");
+                    // Functions are placed after the entry point, so they're
+                    // only ever entered through a call
+                    match expr {
+                        ast::Node::StructDecl { .. }
+                        | ast::Node::EnumDecl { .. }
+                        | ast::Node::UnionDecl { .. }
+                        | ast::Node::TypeDef { .. } => {
+                            env.statement_counter(false);
+                        }
+                        ast::Node::FnDecl { .. } => functions += &statement(expr, env)?,
+                        ast::Node::Extern { .. } => externs += &statement(expr, env)?,
+                        // Without an entry point there's nothing to run it
+                        _ if env.entry == Entry::Freestanding => {
+                            return Err(GeneratorError::StatementOutsideFunction)
+                        }
+                        _ => code += &statement(expr, env)?,
+                    }
+                    env.end_statement();
+                }
+                crate::debug!("codegen", "variables: {:#?}", env.variables);
+                crate::debug!("codegen", "datatypes: {:#?}", env.datatypes);
+
+                // The frame's size is only known once all variables are declared.
+                // Pushes go below it, so they can't overwrite variables
+                let frame = allocate_frame(env);
+                let canaries = write_canaries(env);
+                let checks = check_canaries(env);
+                if env.sanitize || env.checked {
+                    functions += SANITIZER_FAIL;
+                }
+                code = match (&env.entry, &env.instrumentation) {
+                    (Entry::Freestanding, _) => format!("{externs}section .text\n{functions}"),
+                    (Entry::Symbol(entry), instrumentation) => {
+                        // The counts are written when the program exits,
+                        // keeping its result in rax
+                        let (dump_call, dump) = match instrumentation {
+                            Some(instrumentation) => (
+                                format!("push rax\n    call {DUMP}\n    pop rax\n    "),
+                                profile_dump(env, instrumentation.path()),
+                            ),
+                            None => (String::new(), String::new()),
+                        };
+                        // Exiting with the syscall skips libc's exit
+                        // handlers, so output buffered for a pipe or a file
+                        // would be lost. The result is kept on the stack
+                        // while the call is aligned like any other
+                        let flush = if env.libc {
+                            externs += "extern fflush\n";
+                            "push rax
+    mov rax, rsp
+    and rsp, -16
+    push rax
+    sub rsp, 8
+    xor edi, edi
+    call fflush
+    add rsp, 8
+    pop rsp
+    pop rax
+    "
+                        } else {
+                            ""
+                        };
+                        let entry = asm::Function {
+                            name: entry.clone(),
+                            global: true,
+                            frame: true,
+                            body: format!(
+                                "{frame}
+    {entry_counter}{canaries}{code}
+    {checks}{dump_call}{flush}mov rdi, rax
     mov rax, 60
     syscall
-    pop rbp
-    ret"
-                );
+    "
+                            ),
+                        };
+                        format!("{externs}section .text\n{entry}{functions}{dump}")
+                    }
+                };
 
-                Ok(code)
-            }
-            ast::Node::Scope { body } => {
-                let mut size = 0;
-                for var in env.variables.values() {
-                    size += var.datatype.size();
+                let constants = env.constants.borrow();
+                if !constants.is_empty() {
+                    code += "\nsection .rodata\n";
+                    for constant in constants.iter() {
+                        code += &format!("{constant}\n");
+                    }
+                }
+                let counters = env.counters.borrow();
+                if !counters.is_empty() {
+                    code += &format!("\nsection .bss\n{COUNTERS}: resq {}\n", counters.len());
                 }
 
-                let mut new_env = Environment {
-                    parent: Some(env),
-                    variables: HashMap::new(),
-                    datatypes: HashMap::new(),
-                    base_stack: env.base_stack + size,
-                };
+                Ok(code)
+            }
+            ast::Node::Scope { body, .. } => {
+                let mut new_env = env.child();
 
                 let mut code = String::new();
                 for expr in body {
-                    code += &expr.generate(&mut new_env)?;
+                    code += &statement(expr, &mut new_env)?;
+                    new_env.end_statement();
                 }
+                let canaries = write_canaries(&new_env);
+                let checks = check_canaries(&new_env);
+                let poison = poison(&new_env);
 
+                Ok(format!("{canaries}{code}{checks}{poison}"))
+            }
+            ast::Node::BinOp {
+                left,
+                right,
+                op: ast::Operator::LogicalAnd,
+                ..
+            } => {
+                // A false left operand is already the result, 0
+                let end = env.new_label("and_end");
+                let code = format!(
+                    "{}
+    test rax, rax
+    jz {end}
+    {}
+    test rax, rax
+    setnz al
+    movzx rax, al
+{end}:
+    ",
+                    left.generate(env)?,
+                    right.generate(env)?
+                );
                 Ok(code)
             }
-            ast::Node::BinOp { left, right, op: _ } => {
+            ast::Node::BinOp {
+                left,
+                right,
+                op: ast::Operator::LogicalOr,
+                ..
+            } => {
+                // The flags of the test survive setnz and movzx, so a true left
+                // operand skips the right one with rax already set to 1
+                let end = env.new_label("or_end");
                 let code = format!(
                     "{}
-    push rax
+    test rax, rax
+    setnz al
+    movzx rax, al
+    jnz {end}
     {}
-    pop rbx
-    add rax, rbx
+    test rax, rax
+    setnz al
+    movzx rax, al
+{end}:
     ",
                     left.generate(env)?,
                     right.generate(env)?
                 );
                 Ok(code)
             }
-            ast::Node::Integer(value) => Ok(format!("mov rax, {}\n\t", value)),
-            ast::Node::Float(value) => Ok(format!("mov rax, {}\n\t", value)),
+            ast::Node::BinOp {
+                left, right, op, ..
+            } => {
+                // The left operand ends up in rax and the right one in rcx, as
+                // shifts can only take their count from cl
+                let (left_float, right_float) = (is_float(env, left), is_float(env, right));
+                let integer = !left_float && !right_float;
+                let pointers = (pointer(env, left), pointer(env, right));
+                if integer
+                    && matches!(op, ast::Operator::Div | ast::Operator::Mod)
+                    && matches!(**right, ast::Node::Integer { value: 0, .. })
+                {
+                    return Err(GeneratorError::DivisionByZero);
+                }
+                // A pointer can be compared with null but not with a number
+                if let (Ok(left), Ok(right)) = (value_type(env, left), value_type(env, right)) {
+                    if op.compares() && !conversions::comparable(&left, &right) {
+                        return Err(GeneratorError::MismatchedTypes);
+                    }
+                }
+                let instruction = match op {
+                    _ if !integer => float_operation(op, left_float, right_float)?,
+                    ast::Operator::Add | ast::Operator::Sub if pointers != (None, None) => {
+                        pointer_operation(op, pointers)?
+                    }
+                    ast::Operator::Add => "add rax, rcx".to_owned(),
+                    ast::Operator::Sub => "sub rax, rcx".to_owned(),
+                    ast::Operator::Mul => "imul rax, rcx".to_owned(),
+                    // idiv divides rdx:rax, so rax is sign extended into rdx
+                    // first. The quotient is truncated toward zero and the
+                    // remainder has the sign of the dividend, like in C
+                    ast::Operator::Div => format!(
+                        "{}cqo
+    idiv rcx",
+                        check_divisor(env)
+                    ),
+                    ast::Operator::Mod => format!(
+                        "{}cqo
+    idiv rcx
+    mov rax, rdx",
+                        check_divisor(env)
+                    ),
+                    ast::Operator::BitAnd => "and rax, rcx".to_owned(),
+                    ast::Operator::BitOr => "or rax, rcx".to_owned(),
+                    ast::Operator::BitXor => "xor rax, rcx".to_owned(),
+                    ast::Operator::ShiftLeft => "shl rax, cl".to_owned(),
+                    ast::Operator::ShiftRight => "sar rax, cl".to_owned(),
+                    ast::Operator::Equal => compare("e"),
+                    ast::Operator::NotEqual => compare("ne"),
+                    ast::Operator::Less => compare("l"),
+                    ast::Operator::LessEqual => compare("le"),
+                    ast::Operator::Greater => compare("g"),
+                    ast::Operator::GreaterEqual => compare("ge"),
+                    ast::Operator::LogicalAnd | ast::Operator::LogicalOr => {
+                        unreachable!("short-circuiting operators are generated separately")
+                    }
+                };
+                let code = format!(
+                    "{}
+    push rax
+    {}
+    mov rcx, rax
+    pop rax
+    {}
+    ",
+                    left.generate(env)?,
+                    right.generate(env)?,
+                    instruction
+                );
+                Ok(code)
+            }
+            // The pointer's value is the address it's read through
+            ast::Node::UnaryOp {
+                op: ast::UnaryOperator::Deref,
+                ..
+            } => {
+                let index = address_index(env, self)?;
+                let (address, datatype) = address(env, self)?;
+                let load = datatype.load(Register::Rax, address)?;
+                Ok(format!("{index}{load}\n    "))
+            }
+            ast::Node::UnaryOp {
+                value,
+                op: ast::UnaryOperator::AddressOf,
+                ..
+            } => {
+                // Bitfields don't start at a byte of their own
+                if bitfield(env, value)?.is_some() {
+                    return Err(GeneratorError::NotAnLvalue);
+                }
+                let index = address_index(env, value)?;
+                let (address, _) = address(env, value)?;
+                let lea = Instruction::Lea {
+                    dst: Register::Rax,
+                    src: address,
+                };
+                Ok(format!("{index}{lea}\n    "))
+            }
+            ast::Node::UnaryOp { value, op, .. } => {
+                let instruction = match op {
+                    // Only the sign bit of a float differs from its negation
+                    ast::UnaryOperator::Neg if is_float(env, value) => "btc eax, 31",
+                    ast::UnaryOperator::Neg => "neg rax",
+                    ast::UnaryOperator::BitNot if is_float(env, value) => {
+                        return Err(GeneratorError::NotAnInteger)
+                    }
+                    ast::UnaryOperator::BitNot => "not rax",
+                    // Any non-zero value is true, so compare against zero
+                    ast::UnaryOperator::Not => {
+                        "test rax, rax
+    sete al
+    movzx rax, al"
+                    }
+                    ast::UnaryOperator::Deref | ast::UnaryOperator::AddressOf => {
+                        unreachable!("pointer operators are generated separately")
+                    }
+                };
+                let code = format!(
+                    "{}
+    {}
+    ",
+                    value.generate(env)?,
+                    instruction
+                );
+                Ok(code)
+            }
+            ast::Node::Integer { value, .. } => {
+                let mov = Instruction::MovImmediate {
+                    dst: Register::Rax,
+                    value: *value as i64,
+                };
+                Ok(format!("{mov}\n\t"))
+            }
+            ast::Node::Bool { value, .. } => Ok(format!("mov rax, {}\n\t", *value as i32)),
+            ast::Node::Char { value, .. } => Ok(format!("mov rax, {}\n\t", value)),
+            ast::Node::Null { .. } => Ok("mov rax, 0\n\t".to_owned()),
+            ast::Node::SizeOfType { .. } | ast::Node::SizeOfValue { .. } => {
+                let mov = Instruction::MovImmediate {
+                    dst: Register::Rax,
+                    value: size_of(env, self)? as i64,
+                };
+                Ok(format!("{mov}\n\t"))
+            }
+            ast::Node::Cast {
+                value, datatype, ..
+            } => {
+                let to = env.lookup_datatype(datatype)?;
+                let from = value_type(env, value)?;
+                let conversion =
+                    conversions::explicit(&from, &to).ok_or(GeneratorError::InvalidCast)?;
+                apply(env, value, &from, &to, conversion)
+            }
+            // There are no float immediates, so the value is loaded from memory
+            ast::Node::Float { value, .. } => {
+                let address = env.constant("float", asm::Size::Dword, vec![value.to_bits() as u64]);
+                let load = Instruction::Load {
+                    dst: Register::Rax,
+                    src: address.sized(asm::Size::Dword),
+                };
+                Ok(format!("{load}\n\t"))
+            }
             ast::Node::VarDecl {
                 datatype,
                 name,
                 value,
+                ..
             } => {
                 // Return an error if the variable already exists
                 if env.resolve_var(name).is_ok() {
                     return Err(GeneratorError::VariableAlreadyExists);
                 }
 
-                let mut size = 0;
-                for var in &env.variables {
-                    size += var.1.datatype.size();
-                }
-
                 let datatype = env.lookup_datatype(datatype)?;
+                env.allocate_var(name, datatype.clone())?;
+                let canary = write_canary(env, name);
 
-                env.declare_var(
-                    name,
-                    VariableData {
-                        datatype: datatype.clone(),
-                        location: env.base_stack + size + datatype.size(),
+                let (address, _) = address(
+                    env,
+                    &ast::Node::Identifier {
+                        value: name.clone(),
+                        id: ast::NodeId::DETACHED,
                     },
                 )?;
-
-                let location = env.variables.get(name).unwrap().location;
-                let mut code = String::new();
-                match *value.clone() {
-                    ast::Node::StructData { data } => match datatype {
-                        Datatype::Single { size: _ } => {
-                            return Err(GeneratorError::CannotAssignSingleValuetoStruct)
-                        }
-                        Datatype::Struct { size, offsets } => {
-                            for i in 0..data.len() {
-                                let expr = &data[i];
-
-                                code += &format!(
-                                    "{}
-    mov [rbp-{}], rax
-    ",
-                                    expr.generate(env)?,
-                                    location - size + offsets[i].1
-                                );
-                            }
-                        }
-                    },
+                let code = match (&**value, &datatype) {
+                    (ast::Node::StructData { data, .. }, _) => {
+                        store_struct_literal(env, data, &datatype, &address)?
+                    }
+                    // The variable is passed as the memory to return into,
+                    // rather than copying the struct out of a temporary
+                    (
+                        ast::Node::Call { name, .. },
+                        Datatype::Struct { .. } | Datatype::Union { .. },
+                    ) if returns_in_memory(&datatype)
+                        && return_type(env, name)?.as_ref() == Some(&datatype) =>
+                    {
+                        env.destination = Some(address.clone());
+                        value.generate(env)?
+                    }
+                    (_, Datatype::Struct { .. } | Datatype::Union { .. }) => {
+                        copy_struct(env, value, &datatype, &address)?
+                    }
                     _ => {
-                        code = format!(
+                        let store = Instruction::Store {
+                            dst: address.sized(datatype.operand_size()?),
+                            src: Register::Rax,
+                        };
+                        format!(
                             "{}
-    mov [rbp-{}], rax
+    {store}
     ",
-                            value.generate(env)?,
-                            location
+                            convert(env, value, &datatype)?,
                         )
                     }
-                }
-
-                Ok(code)
+                };
+                Ok(format!("{canary}{code}"))
             }
-            ast::Node::StructDecl { name, properties } => {
-                if env.lookup_datatype(name).is_ok() {
-                    return Err(GeneratorError::DatatypeAlreadyExists);
-                }
+            ast::Node::ArrayDecl {
+                datatype,
+                name,
+                lens,
+                ..
+            } => {
+                if env.resolve_var(name).is_ok() {
+                    return Err(GeneratorError::VariableAlreadyExists);
+                }
+                if lens.contains(&0) {
+                    return Err(GeneratorError::EmptyArray);
+                }
+
+                // Built from the innermost dimension out, so the elements of
+                // a row are next to each other
+                let mut datatype = env.lookup_datatype(datatype)?;
+                for len in lens.iter().rev() {
+                    datatype = Datatype::Array {
+                        element: Box::new(datatype),
+                        len: *len,
+                    };
+                }
+                env.allocate_var(name, datatype)?;
+                Ok(write_canary(env, name))
+            }
+            ast::Node::StructDecl {
+                name,
+                properties,
+                packed,
+                ..
+            } => {
+                if env.lookup_datatype(name).is_ok() {
+                    return Err(GeneratorError::DatatypeAlreadyExists);
+                }
 
-                let mut offsets = vec![];
-                let mut offset = 0;
-                for prop in properties {
-                    let datatype = env.lookup_datatype(&prop.0)?;
-                    let size = datatype.size();
-                    offsets.push((prop.1.clone(), offset + size));
-                    offset += size;
+                let datatype = struct_layout(env, properties, *packed)?;
+                env.declare_datatype(name, datatype)?;
+
+                Ok(String::new())
+            }
+            ast::Node::UnionDecl {
+                name, properties, ..
+            } => {
+                if env.lookup_datatype(name).is_ok() {
+                    return Err(GeneratorError::DatatypeAlreadyExists);
                 }
 
-                env.declare_datatype(
-                    name,
-                    Datatype::Struct {
-                        size: size(env, properties)?,
-                        offsets,
-                    },
-                )?;
+                let datatype = union_layout(env, properties)?;
+                env.declare_datatype(name, datatype)?;
+
+                Ok(String::new())
+            }
+            ast::Node::EnumDecl { name, members, .. } => {
+                if env.lookup_datatype(name).is_ok() {
+                    return Err(GeneratorError::DatatypeAlreadyExists);
+                }
+
+                let datatype = Datatype::Enum { name: name.clone() };
+                env.declare_datatype(name, datatype.clone())?;
+                let mut values = vec![];
+                let mut next = 0;
+                for (member, value) in members {
+                    if env.members.contains_key(member) || env.variables.contains_key(member) {
+                        return Err(GeneratorError::VariableAlreadyExists);
+                    }
+                    // Members are stored in a dword, which includes one
+                    // counting up past the last int
+                    let value = value.unwrap_or(next);
+                    if i32::try_from(value).is_err() {
+                        return Err(GeneratorError::EnumValueOutOfRange);
+                    }
+                    if values.contains(&value) {
+                        return Err(GeneratorError::DuplicateEnumValue);
+                    }
+                    values.push(value);
+                    next = value + 1;
+                    env.members
+                        .insert(member.clone(), (datatype.clone(), value));
+                }
 
                 Ok(String::new())
             }
-            ast::Node::StructType { properties: _ } => Ok(String::new()),
-            ast::Node::TypeDef { name, value } => {
+            ast::Node::StructType { .. } => Ok(String::new()),
+            ast::Node::FnType { .. } => Ok(String::new()),
+            ast::Node::TypeDef { name, value, .. } => {
                 if env.lookup_datatype(name).is_ok() {
                     return Err(GeneratorError::DatatypeAlreadyExists);
                 }
@@ -259,40 +1312,2345 @@ _start:
                 env.declare_datatype(
                     name,
                     match *value.clone() {
-                        ast::Node::StructType { properties } => {
-                            let mut offsets = vec![];
-                            let mut offset = 0;
-                            for prop in &properties {
-                                let datatype = env.lookup_datatype(&prop.0)?;
-                                let size = datatype.size();
-                                offsets.push((prop.1.clone(), offset + size));
-                                offset += size;
-                            }
-                            Datatype::Struct {
-                                size: size(env, &properties)?,
-                                offsets,
-                            }
-                        }
-                        ast::Node::Identifier { value } => env.lookup_datatype(&value)?,
-                        _ => Datatype::Single { size: 0 },
+                        ast::Node::StructType {
+                            properties, packed, ..
+                        } => struct_layout(env, &properties, packed)?,
+                        ast::Node::FnType {
+                            params,
+                            return_type,
+                            ..
+                        } => Datatype::Function {
+                            params: params
+                                .iter()
+                                .map(|datatype| env.lookup_datatype(datatype))
+                                .collect::<Result<_, _>>()?,
+                            return_type: return_type
+                                .map(|datatype| env.lookup_datatype(&datatype).map(Box::new))
+                                .transpose()?,
+                        },
+                        ast::Node::Identifier { value, .. } => env.lookup_datatype(&value)?,
+                        _ => Datatype::Single {
+                            size: 0,
+                            signed: false,
+                        },
                     },
                 )?;
 
                 Ok(String::new())
             }
-            ast::Node::Identifier { value } => {
-                let var_data = env.lookup_var(value)?;
-                Ok(format!("mov rax, [rbp-{}]", var_data.location))
+            ast::Node::Identifier { value, .. } => {
+                // A function's name on its own is its address
+                if env.lookup_var(value).is_err() && env.lookup_function(value).is_ok() {
+                    let lea = Instruction::Lea {
+                        dst: Register::Rax,
+                        src: Address::label(value),
+                    };
+                    return Ok(format!("{lea}\n    "));
+                }
+                if let Ok(VariableData {
+                    datatype,
+                    register: Some(register),
+                    ..
+                }) = env.lookup_var(value)
+                {
+                    let extend = datatype.extend(Register::Rax, *register)?;
+                    return Ok(format!("{extend}\n    "));
+                }
+                if let (Err(_), Ok((_, value))) = (env.lookup_var(value), env.lookup_member(value))
+                {
+                    let mov = Instruction::MovImmediate {
+                        dst: Register::Rax,
+                        value: *value,
+                    };
+                    return Ok(format!("{mov}\n    "));
+                }
+
+                let (address, datatype) = address(env, self)?;
+                let load = datatype.load(Register::Rax, address)?;
+                Ok(format!("{load}\n    "))
+            }
+            ast::Node::MemberAccess { .. } | ast::Node::Index { .. } => {
+                let index = address_index(env, self)?;
+                let (address, datatype) = address(env, self)?;
+                if let Some(bits) = bitfield(env, self)? {
+                    return Ok(format!("{index}{}", read_bits(&datatype, address, bits)?));
+                }
+                let load = datatype.load(Register::Rax, address)?;
+                Ok(format!("{index}{load}\n    "))
+            }
+            // Struct literals are only understood where a struct is expected,
+            // VarDecl and Assign store them into their struct themselves
+            ast::Node::StructData { data: _, .. } => {
+                Err(GeneratorError::StructLiteralOutsideDeclaration)
+            }
+            ast::Node::If {
+                cond, then, else_, ..
+            } => {
+                let else_label = env.new_label("else");
+                let end = env.new_label("if_end");
+                let cond = cond.generate(env)?;
+                let then = statement(then, env)?;
+                let else_ = match else_ {
+                    Some(else_) => statement(else_, env)?,
+                    None => String::new(),
+                };
+                let code = format!(
+                    "{cond}
+    test rax, rax
+    jz {else_label}
+    {then}
+    jmp {end}
+{else_label}:
+    {else_}
+{end}:
+    "
+                );
+                Ok(code)
+            }
+            ast::Node::Assign { target, value, .. } => {
+                let (address, datatype) = address(env, target)?;
+                // The target's index is computed first, and saved while the
+                // value is evaluated as that could index an array too
+                let index = address_index(env, target)?;
+                if let Datatype::Struct { .. } | Datatype::Union { .. } = datatype {
+                    // An element's whole address is saved instead, the copy
+                    // may need the index register for the value's address
+                    let (save, restore, dst) = match index.is_empty() {
+                        true => (String::new(), "", address),
+                        false => {
+                            let lea = Instruction::Lea {
+                                dst: Register::Rax,
+                                src: address,
+                            };
+                            let save = format!("{index}{lea}\n    push rax\n    ");
+                            (save, "pop rdx\n    ", Address::new(Register::Rdx, 0))
+                        }
+                    };
+                    // Literals are built in a temporary and then copied, so
+                    // they can still read the properties they overwrite
+                    let (value, src) = match &**value {
+                        ast::Node::StructData { data, .. } => {
+                            let temporary = env.temporary(datatype.size());
+                            let value = store_struct_literal(env, data, &datatype, &temporary)?;
+                            (value, temporary)
+                        }
+                        _ => struct_value(env, value, &datatype)?,
+                    };
+                    let copy = copy(&dst, &src, datatype.size());
+                    // The value of the expression is the struct's address
+                    let lea = Instruction::Lea {
+                        dst: Register::Rax,
+                        src: dst,
+                    };
+                    return Ok(format!("{save}{value}{restore}{copy}{lea}\n    "));
+                }
+
+                let store = match bitfield(env, target)? {
+                    Some(bits) => write_bits(&datatype, address, bits)?,
+                    None => Instruction::Store {
+                        dst: address.sized(datatype.operand_size()?),
+                        src: Register::Rax,
+                    }
+                    .to_string(),
+                };
+                let value = match index.is_empty() {
+                    true => convert(env, value, &datatype)?,
+                    false => format!(
+                        "{index}push {INDEX_REGISTER}\n    {}pop {INDEX_REGISTER}\n    ",
+                        convert(env, value, &datatype)?
+                    ),
+                };
+                // The assigned value stays in rax as the value of the expression
+                Ok(format!(
+                    "{value}
+    {store}
+    "
+                ))
+            }
+            ast::Node::IncDec {
+                target,
+                increment,
+                postfix,
+                ..
+            } => {
+                let index = address_index(env, target)?;
+                let (address, datatype) = address(env, target)?;
+                // A bitfield is read, changed in rax and written back. The
+                // old value is kept on the stack for postfix operators
+                if let Some(bits) = bitfield(env, target)? {
+                    let read = read_bits(&datatype, address.clone(), bits)?;
+                    let write = write_bits(&datatype, address, bits)?;
+                    let change = if *increment { "inc rax" } else { "dec rax" };
+                    return Ok(if *postfix {
+                        format!("{index}{read}push rax\n    {change}\n    {write}pop rax\n    ")
+                    } else {
+                        format!("{index}{read}{change}\n    {write}")
+                    });
+                }
+                let load = datatype.load(Register::Rax, address.clone())?;
+                // Floats add or subtract 1.0 rather than 1 from their bits
+                if let Datatype::Float { .. } = datatype {
+                    let one = env.constant("float", asm::Size::Dword, vec![1f32.to_bits() as u64]);
+                    let op = if *increment { "addss" } else { "subss" };
+                    let store = Instruction::Store {
+                        dst: address.sized(asm::Size::Dword),
+                        src: Register::Rax,
+                    };
+                    let change = format!(
+                        "movd xmm0, eax\n    {op} xmm0, {}\n    movd eax, xmm0\n    {store}",
+                        one.sized(asm::Size::Dword)
+                    );
+                    return Ok(if *postfix {
+                        format!("{index}{load}\n    push rax\n    {change}\n    pop rax\n    ")
+                    } else {
+                        format!("{index}{load}\n    {change}\n    ")
+                    });
+                }
+                let memory = address.sized(datatype.operand_size()?);
+                let change = match (&datatype, increment) {
+                    // Pointers step over a whole element
+                    (Datatype::Pointer { target }, _) if target.size() != 1 => {
+                        let size = target.size() as i64;
+                        Instruction::AddImmediate {
+                            dst: memory,
+                            value: if *increment { size } else { -size },
+                        }
+                    }
+                    (_, true) => Instruction::Inc { dst: memory },
+                    (_, false) => Instruction::Dec { dst: memory },
+                };
+                let code = if *postfix {
+                    format!("{index}{load}\n    {change}\n    ")
+                } else {
+                    format!("{index}{change}\n    {load}\n    ")
+                };
+                Ok(code)
+            }
+            ast::Node::Ternary {
+                cond, then, else_, ..
+            } => {
+                let else_label = env.new_label("ternary_else");
+                let end = env.new_label("ternary_end");
+                let cond = cond.generate(env)?;
+                // Integers of any width are already alike in rax, but when
+                // one branch is a float the other has to become one too
+                let (then, else_) = if is_float(env, self) {
+                    let float = env.lookup_datatype("float")?;
+                    (convert(env, then, &float)?, convert(env, else_, &float)?)
+                } else {
+                    (then.generate(env)?, else_.generate(env)?)
+                };
+                let code = format!(
+                    "{cond}
+    test rax, rax
+    jz {else_label}
+    {then}
+    jmp {end}
+{else_label}:
+    {else_}
+{end}:
+    "
+                );
+                Ok(code)
+            }
+            ast::Node::For {
+                init,
+                cond,
+                step,
+                body,
+                ..
+            } => {
+                // Variables declared in the initializer only live as long as the loop
+                let mut env = env.child();
+                let start = env.new_label("for");
+                let next = env.new_label("for_step");
+                let end = env.new_label("for_end");
+                let init = match init {
+                    Some(init) => statement(init, &mut env)?,
+                    None => String::new(),
+                };
+                // A missing condition loops forever
+                let cond = match cond {
+                    Some(cond) => format!(
+                        "{}
+    test rax, rax
+    jz {end}",
+                        cond.generate(&mut env)?
+                    ),
+                    None => String::new(),
+                };
+                let body = env.generate_loop_body(
+                    body,
+                    LoopLabels {
+                        break_label: end.clone(),
+                        continue_label: Some(next.clone()),
+                    },
+                )?;
+                let step = match step {
+                    Some(step) => step.generate(&mut env)?,
+                    None => String::new(),
+                };
+                let canaries = write_canaries(&env);
+                let checks = check_canaries(&env);
+                let poison = poison(&env);
+                let code = format!(
+                    "{canaries}{init}
+{start}:
+    {cond}
+    {body}
+{next}:
+    {step}
+    jmp {start}
+{end}:
+    {checks}{poison}"
+                );
+                Ok(code)
+            }
+            ast::Node::DoWhile { body, cond, .. } => {
+                // The body always runs once, the condition is tested at the bottom
+                let start = env.new_label("do");
+                let next = env.new_label("do_cond");
+                let end = env.new_label("do_end");
+                let body = env.generate_loop_body(
+                    body,
+                    LoopLabels {
+                        break_label: end.clone(),
+                        continue_label: Some(next.clone()),
+                    },
+                )?;
+                let cond = cond.generate(env)?;
+                let code = format!(
+                    "
+{start}:
+    {body}
+{next}:
+    {cond}
+    test rax, rax
+    jnz {start}
+{end}:
+    "
+                );
+                Ok(code)
+            }
+            ast::Node::While { cond, body, .. } => {
+                let start = env.new_label("while");
+                let end = env.new_label("while_end");
+                let cond = cond.generate(env)?;
+                let body = env.generate_loop_body(
+                    body,
+                    LoopLabels {
+                        break_label: end.clone(),
+                        continue_label: Some(start.clone()),
+                    },
+                )?;
+                let code = format!(
+                    "
+{start}:
+    {cond}
+    test rax, rax
+    jz {end}
+    {body}
+    jmp {start}
+{end}:
+    "
+                );
+                Ok(code)
+            }
+            ast::Node::Break { .. } => Ok(format!("jmp {}\n\t", env.lookup_loop()?.break_label)),
+            ast::Node::Continue { .. } => match &env.lookup_loop()?.continue_label {
+                Some(label) => Ok(format!("jmp {label}\n\t")),
+                None => Err(GeneratorError::NotInLoop),
+            },
+            ast::Node::Label { name, .. } => Ok(format!("\n{}:\n    ", env.lookup_label(name)?)),
+            ast::Node::Goto { name, .. } => Ok(format!("jmp {}\n\t", env.lookup_label(name)?)),
+            ast::Node::FnDecl {
+                name,
+                params,
+                return_type,
+                section,
+                body,
+                ..
+            } => {
+                if env.parent.is_some() {
+                    return Err(GeneratorError::NestedFunction);
+                }
+
+                // Without an entry point, functions are what the program exports
+                let global = env.entry == Entry::Freestanding;
+                let mut env = env.function();
+                let epilogue = env.new_label("return");
+                env.return_label = Some(epilogue.clone());
+                env.return_type = return_type
+                    .as_ref()
+                    .map(|datatype| env.lookup_datatype(datatype))
+                    .transpose()?;
+                declare_labels(body, &mut env)?;
+
+                // The arguments of a fastcall function stay in the registers
+                // they arrived in, and nothing else needs a frame
+                let code = if env.lookup_function(name)?.fastcall {
+                    for ((datatype, name), register) in params.iter().zip(FASTCALL_REGISTERS) {
+                        let var_data = VariableData {
+                            datatype: env.lookup_datatype(datatype)?,
+                            location: 0,
+                            register: Some(register),
+                        };
+                        env.declare_var(name, var_data)?;
+                    }
+                    let counter = count_call(&env, name);
+                    let body = body.generate(&mut env)?;
+                    asm::Function {
+                        name: name.clone(),
+                        global,
+                        frame: false,
+                        body: format!("{counter}{body}\n{epilogue}:\n    "),
+                    }
+                    .to_string()
+                } else {
+                    // A struct too large for rax and rdx is returned through
+                    // memory the caller passes the address of as the first
+                    // argument. It's kept in the frame until `return` needs it
+                    let mut spills = String::new();
+                    let sret = env.return_type.as_ref().is_some_and(returns_in_memory);
+                    if sret {
+                        env.allocate_var(SRET, POINTER)?;
+                        let (address, _) = address(
+                            &env,
+                            &ast::Node::Identifier {
+                                value: SRET.to_owned(),
+                                id: ast::NodeId::DETACHED,
+                            },
+                        )?;
+                        let store = Instruction::Store {
+                            dst: address.sized(asm::Size::Qword),
+                            src: ARGUMENT_REGISTERS[0],
+                        };
+                        spills += &format!("{store}\n    ");
+                    }
+
+                    // The arguments arrive in registers and, once those run out,
+                    // on the stack above the return address. They're copied into
+                    // the frame so they can be used like any other variable.
+                    // Structs are copied through rcx, which may hold an argument
+                    // itself, so that happens once all registers are saved
+                    let mut copies = String::new();
+                    let (mut registers, mut stack) = (sret as usize, 0);
+                    for (datatype, name) in params {
+                        let datatype = env.lookup_datatype(datatype)?;
+                        env.allocate_var(name, datatype.clone())?;
+                        let (address, _) = address(
+                            &env,
+                            &ast::Node::Identifier {
+                                value: name.clone(),
+                                id: ast::NodeId::DETACHED,
+                            },
+                        )?;
+                        if let Datatype::Struct { .. } | Datatype::Union { .. } = datatype {
+                            let eightbytes = datatype.size().div_ceil(8);
+                            let src = if in_registers(&datatype, registers) {
+                                let temporary = env.temporary(16);
+                                let arguments =
+                                    &ARGUMENT_REGISTERS[registers..registers + eightbytes];
+                                for (i, register) in arguments.iter().enumerate() {
+                                    let store = Instruction::Store {
+                                        dst: temporary.offset_by(8 * i).sized(asm::Size::Qword),
+                                        src: *register,
+                                    };
+                                    spills += &format!("{store}\n    ");
+                                }
+                                registers += eightbytes;
+                                temporary
+                            } else {
+                                stack += eightbytes;
+                                Address::new(Register::Rbp, 16 + 8 * (stack - eightbytes) as isize)
+                            };
+                            copies += &copy(&address, &src, datatype.size());
+                            continue;
+                        }
+
+                        let size = datatype.operand_size()?;
+                        let src = match ARGUMENT_REGISTERS.get(registers) {
+                            Some(register) => {
+                                registers += 1;
+                                *register
+                            }
+                            None => {
+                                let load = Instruction::Load {
+                                    dst: Register::Rax,
+                                    src: Address::new(Register::Rbp, 16 + 8 * stack as isize)
+                                        .sized(size),
+                                };
+                                stack += 1;
+                                spills += &format!("{load}\n    ");
+                                Register::Rax
+                            }
+                        };
+                        let store = Instruction::Store {
+                            dst: address.sized(size),
+                            src,
+                        };
+                        spills += &format!("{store}\n    ");
+                    }
+                    spills += &copies;
+                    env.end_statement();
+
+                    let counter = count_call(&env, name);
+                    let body = body.generate(&mut env)?;
+                    let frame = allocate_frame(&env);
+                    // rcx may still hold an argument until the spills are done
+                    let canaries = write_canaries(&env);
+                    let checks = check_canaries(&env);
+                    asm::Function {
+                        name: name.clone(),
+                        global,
+                        frame: true,
+                        body: format!(
+                            "{frame}
+    {counter}{spills}{canaries}{body}
+{epilogue}:
+    {checks}"
+                        ),
+                    }
+                    .to_string()
+                };
+                // nasm doesn't know sections by other names hold code, and
+                // whatever follows expects to still be in .text
+                Ok(match section {
+                    Some(section) => format!(
+                        "\nsection {section} progbits alloc exec nowrite align=16{code}section .text\n"
+                    ),
+                    None => code,
+                })
+            }
+            ast::Node::Extern { name, .. } => {
+                if env.parent.is_some() {
+                    return Err(GeneratorError::NestedFunction);
+                }
+                Ok(format!("extern {name}\n"))
+            }
+            ast::Node::Return { value, .. } => {
+                let label = env.lookup_return()?.to_owned();
+                let value = match (value, env.lookup_return_type().cloned()) {
+                    (
+                        Some(value),
+                        Some(datatype @ (Datatype::Struct { .. } | Datatype::Union { .. })),
+                    ) => return_struct(env, value, &datatype)?,
+                    (Some(value), Some(datatype)) => convert(env, value, &datatype)?,
+                    (Some(value), None) => value.generate(env)?,
+                    (None, _) => String::new(),
+                };
+                // The value is left in rax for the epilogue to return
+                Ok(format!("{value}\n    jmp {label}\n\t"))
             }
-            ast::Node::StructData { data: _ } => Ok(String::new()),
+            ast::Node::Call { name, args, .. } => {
+                let destination = env.destination.take();
+                // Functions and variables of the program shadow the intrinsics
+                if env.lookup_var(name).is_err() && env.lookup_function(name).is_err() {
+                    if let Some(code) = intrinsic(env, name, args)? {
+                        return Ok(code);
+                    }
+                }
+
+                // Calls through a variable holding a function's address are
+                // indirect, the address is loaded once the arguments are ready
+                let callee = ast::Node::Identifier {
+                    value: name.clone(),
+                    id: ast::NodeId::DETACHED,
+                };
+                let (target, function) = match address(env, &callee) {
+                    Ok((address, datatype @ Datatype::Function { .. })) => {
+                        let load = datatype.load(Register::Rax, address)?;
+                        (format!("{load}\n    call rax"), None)
+                    }
+                    Ok(_) => return Err(GeneratorError::NotAFunction),
+                    Err(_) => (
+                        format!("call {name}"),
+                        Some(env.lookup_function(name)?.clone()),
+                    ),
+                };
+                if let Some(function) = function.as_ref().filter(|function| function.fastcall) {
+                    return call_fast(env, name, args, &function.params);
+                }
+                // A struct is returned into a temporary of the caller, the
+                // value of the call is its address. One returned through
+                // memory that initializes a variable is built right in it
+                let result = match &return_type(env, name)? {
+                    Some(datatype @ (Datatype::Struct { .. } | Datatype::Union { .. })) => {
+                        let sret = returns_in_memory(datatype);
+                        let address = match destination {
+                            Some(address) if sret => address,
+                            _ => env.temporary(datatype.size().max(16)),
+                        };
+                        Some((address, sret))
+                    }
+                    _ => None,
+                };
+                let sret = result.as_ref().is_some_and(|(_, sret)| *sret);
+                let params = match &function {
+                    Some(function) => function.params.clone(),
+                    None => match address(env, &callee)? {
+                        (_, Datatype::Function { params, .. }) => params,
+                        _ => unreachable!(),
+                    },
+                };
+                let external = function.as_ref().is_some_and(|function| function.external);
+                let variadic = function.as_ref().is_some_and(|function| function.variadic);
+                let fixed = function
+                    .as_ref()
+                    .map_or(0, |function| function.params.len());
+
+                let mut locations = vec![];
+                let (mut registers, mut vectors, mut stack_args) = (sret as usize, 0, 0);
+                for (i, arg) in args.iter().enumerate() {
+                    // Arguments are converted to their parameter's type
+                    let float = external
+                        && match params.get(i) {
+                            Some(datatype) => matches!(datatype, Datatype::Float { .. }),
+                            None => is_float(env, arg),
+                        };
+                    let promote = float && variadic && i >= fixed;
+                    let location = if let Some(
+                        datatype @ (Datatype::Struct { .. } | Datatype::Union { .. }),
+                    ) = params.get(i)
+                    {
+                        let eightbytes = datatype.size().div_ceil(8);
+                        if in_registers(datatype, registers) {
+                            registers += eightbytes;
+                            let arguments = &ARGUMENT_REGISTERS[registers - eightbytes..registers];
+                            ArgumentLocation::StructRegisters(datatype.clone(), arguments.to_vec())
+                        } else {
+                            stack_args += eightbytes;
+                            ArgumentLocation::StructStack(datatype.clone(), stack_args - eightbytes)
+                        }
+                    } else if float && vectors < 8 {
+                        vectors += 1;
+                        ArgumentLocation::Vector(vectors - 1, promote)
+                    } else if !float && registers < ARGUMENT_REGISTERS.len() {
+                        registers += 1;
+                        ArgumentLocation::Register(ARGUMENT_REGISTERS[registers - 1])
+                    } else {
+                        stack_args += 1;
+                        ArgumentLocation::Stack(stack_args - 1, promote)
+                    };
+                    locations.push(location);
+                }
+
+                // The stack has to be 16 byte aligned at the call, which it
+                // isn't necessarily in the middle of an expression. rsp is
+                // rounded down and its old value saved above the arguments
+                let reserved = 8 * stack_args + if stack_args % 2 == 0 { 8 } else { 0 };
+                let mut code = format!(
+                    "mov rax, rsp
+    and rsp, -16
+    push rax
+    sub rsp, {reserved}
+    "
+                );
+
+                // Arguments are evaluated from left to right. The ones going
+                // in registers are kept on the stack until all are evaluated,
+                // as evaluating the others could clobber the registers
+                let mut pushed = 0;
+                for (i, (arg, location)) in args.iter().zip(&locations).enumerate() {
+                    match location {
+                        // Copied to a temporary first, so the registers can be
+                        // loaded a whole qword at a time without reading past
+                        // the end of the struct
+                        ArgumentLocation::StructRegisters(datatype, registers) => {
+                            let (value, src) = struct_value(env, arg, datatype)?;
+                            let temporary = env.temporary(16);
+                            code += &format!("{value}{}", copy(&temporary, &src, datatype.size()));
+                            for i in 0..registers.len() {
+                                let qword = temporary.offset_by(8 * i).sized(asm::Size::Qword);
+                                code += &format!("push {qword}\n    ");
+                            }
+                            pushed += registers.len();
+                            continue;
+                        }
+                        ArgumentLocation::StructStack(datatype, slot) => {
+                            let (value, src) = struct_value(env, arg, datatype)?;
+                            let dst = Address::new(Register::Rsp, 8 * (pushed + slot) as isize);
+                            code += &format!("{value}{}", copy(&dst, &src, datatype.size()));
+                            continue;
+                        }
+                        _ => match params.get(i) {
+                            Some(datatype) => code += &convert(env, arg, datatype)?,
+                            None => code += &arg.generate(env)?,
+                        },
+                    }
+                    match location {
+                        ArgumentLocation::Stack(slot, promote) => {
+                            if *promote {
+                                code += "\n    movd xmm0, eax\n    cvtss2sd xmm0, xmm0\n    movq rax, xmm0";
+                            }
+                            // The stack slots are above everything pushed so far
+                            let store = Instruction::Store {
+                                dst: Address::new(Register::Rsp, 8 * (pushed + slot) as isize)
+                                    .sized(asm::Size::Qword),
+                                src: Register::Rax,
+                            };
+                            code += &format!("\n    {store}\n    ");
+                        }
+                        _ => {
+                            code += "\n    push rax\n    ";
+                            pushed += 1;
+                        }
+                    }
+                }
+                for location in locations.iter().rev() {
+                    match location {
+                        ArgumentLocation::Register(register) => {
+                            code += &format!("pop {register}\n    ")
+                        }
+                        ArgumentLocation::Vector(index, promote) => {
+                            code += &format!("pop rax\n    movd xmm{index}, eax\n    ");
+                            if *promote {
+                                code += &format!("cvtss2sd xmm{index}, xmm{index}\n    ");
+                            }
+                        }
+                        ArgumentLocation::StructRegisters(_, registers) => {
+                            for register in registers.iter().rev() {
+                                code += &format!("pop {register}\n    ");
+                            }
+                        }
+                        ArgumentLocation::Stack(..) | ArgumentLocation::StructStack(..) => {}
+                    }
+                }
+                if let Some((address, true)) = &result {
+                    let lea = Instruction::Lea {
+                        dst: ARGUMENT_REGISTERS[0],
+                        src: address.clone(),
+                    };
+                    code += &format!("{lea}\n    ");
+                }
+                // Variadic functions are told how many xmm registers hold
+                // arguments, so they know which ones to save
+                if variadic {
+                    code += &format!("mov al, {vectors}\n    ");
+                }
+
+                // The return value is already in rax, unless it's a float
+                // returned the C way
+                let returns_float = matches!(
+                    function,
+                    Some(FunctionData {
+                        external: true,
+                        return_type: Some(Datatype::Float { .. }),
+                        ..
+                    })
+                );
+                code += &format!(
+                    "{target}
+    add rsp, {reserved}
+    pop rsp
+    {}",
+                    if returns_float {
+                        "movd eax, xmm0\n    "
+                    } else {
+                        ""
+                    }
+                );
+                // rax already holds the address of a struct returned in memory
+                if let Some((address, false)) = result {
+                    let stores =
+                        [Register::Rax, Register::Rdx]
+                            .into_iter()
+                            .enumerate()
+                            .map(|(i, src)| Instruction::Store {
+                                dst: address.offset_by(8 * i).sized(asm::Size::Qword),
+                                src,
+                            });
+                    for store in stores {
+                        code += &format!("{store}\n    ");
+                    }
+                    let lea = Instruction::Lea {
+                        dst: Register::Rax,
+                        src: address,
+                    };
+                    code += &format!("{lea}\n    ");
+                }
+                Ok(code)
+            }
+            ast::Node::Switch { value, cases, .. } => {
+                let value = value.generate(env)?;
+                let end = env.new_label("switch_end");
+                // break leaves the switch, continue still belongs to the loop around it
+                let continue_label = env
+                    .lookup_loop()
+                    .ok()
+                    .and_then(|labels| labels.continue_label.clone());
+                let mut env = env.child();
+                env.loop_labels = Some(LoopLabels {
+                    break_label: end.clone(),
+                    continue_label,
+                });
+
+                let mut labels: Vec<(i32, String)> = vec![];
+                let mut default = None;
+                let mut bodies = String::new();
+                for (case, body) in cases {
+                    let label = env.new_label("case");
+                    match case {
+                        Some(case) if labels.iter().any(|(value, _)| value == case) => {
+                            return Err(GeneratorError::DuplicateCase)
+                        }
+                        Some(case) => labels.push((*case, label.clone())),
+                        None if default.is_some() => return Err(GeneratorError::DuplicateCase),
+                        None => default = Some(label.clone()),
+                    }
+
+                    // Cases fall through into the next one unless they break
+                    bodies += &format!("\n{label}:\n    ");
+                    for stmt in body {
+                        bodies += &statement(stmt, &mut env)?;
+                        env.end_statement();
+                    }
+                }
+                let dispatch = dispatch(&env, &labels, default.as_ref().unwrap_or(&end));
+
+                let canaries = write_canaries(&env);
+                let checks = check_canaries(&env);
+                let poison = poison(&env);
+                let code = format!(
+                    "{value}
+    {canaries}{dispatch}
+{bodies}
+{end}:
+    {checks}{poison}"
+                );
+                Ok(code)
+            }
+        }
+    }
+}
+
+// The call counters of a profiled program, one qword per function
+const COUNTERS: &str = "..@profile_counters";
+// The routine writing the counters to the profile file
+const DUMP: &str = "..@profile_dump";
+
+// What canaries hold and the slots of ended scopes are filled with
+const CANARY: i64 = 0x2bad_c0de_2bad_c0de;
+const POISON: i64 = 0x5a5a_5a5a_5a5a_5a5a;
+// Reports the corruption or error described by the message at rsi, rdx
+// bytes long, and aborts the program
+const FAIL: &str = "..@sanitizer_fail";
+const SANITIZER_FAIL: &str = "
+..@sanitizer_fail:
+    mov rax, 1
+    mov rdi, 2
+    syscall
+    mov rax, 39
+    syscall
+    mov rdi, rax
+    mov rsi, 6
+    mov rax, 62
+    syscall
+    mov rdi, 134
+    mov rax, 60
+    syscall
+";
+
+// Writes the canaries of the variables of `env` when its scope is entered,
+// so they're in place even if a jump skips a declaration
+fn write_canaries(env: &Environment) -> String {
+    if env.canaries.is_empty() {
+        return String::new();
+    }
+    let mov = Instruction::MovImmediate {
+        dst: Register::Rcx,
+        value: CANARY,
+    };
+    let mut code = format!("{mov}\n    ");
+    for (location, _) in &env.canaries {
+        code += &store_canary(*location);
+    }
+    code
+}
+
+// Writes the canary of the variable `name` again where it's declared. Until
+// then its slot may have been used by temporaries or nested scopes
+fn write_canary(env: &Environment, name: &str) -> String {
+    match env.canaries.iter().find(|(_, var)| var == name) {
+        Some((location, _)) => {
+            let mov = Instruction::MovImmediate {
+                dst: Register::Rcx,
+                value: CANARY,
+            };
+            format!("{mov}\n    {}", store_canary(*location))
+        }
+        None => String::new(),
+    }
+}
+
+fn store_canary(location: usize) -> String {
+    let store = Instruction::Store {
+        dst: Address::new(Register::Rbp, -(location as isize)).sized(asm::Size::Qword),
+        src: Register::Rcx,
+    };
+    format!("{store}\n    ")
+}
+
+// Aborts the program if the canary of any variable of `env` was
+// overwritten. Only rcx is used, so the value in rax survives the checks.
+// Code leaving the scope early, like a return from inside a loop, skips them
+fn check_canaries(env: &Environment) -> String {
+    let mut code = String::new();
+    for (location, name) in &env.canaries {
+        let message = format!("sanitizer: the canary above {name} was overwritten\n");
+        let length = message.len();
+        let message = env.constant(
+            "sanitizer_message",
+            asm::Size::Byte,
+            message.bytes().map(u64::from).collect(),
+        );
+        let ok = env.new_label("canary_ok");
+        let mov = Instruction::MovImmediate {
+            dst: Register::Rcx,
+            value: CANARY,
+        };
+        let canary = Address::new(Register::Rbp, -(*location as isize)).sized(asm::Size::Qword);
+        code += &format!(
+            "{mov}
+    cmp {canary}, rcx
+    je {ok}
+    lea rsi, {message}
+    mov rdx, {length}
+    call {FAIL}
+{ok}:
+    "
+        );
+    }
+    code
+}
+
+// Aborts the program if the divisor in rcx is zero, when divisions are checked
+fn check_divisor(env: &Environment) -> String {
+    if !env.checking() {
+        return String::new();
+    }
+    let message = "checked: division by zero\n";
+    let length = message.len();
+    let message = env.constant(
+        "checked_message",
+        asm::Size::Byte,
+        message.bytes().map(u64::from).collect(),
+    );
+    let ok = env.new_label("divisor_ok");
+    format!(
+        "test rcx, rcx
+    jnz {ok}
+    lea rsi, {message}
+    mov rdx, {length}
+    call {FAIL}
+{ok}:
+    "
+    )
+}
+
+// Fills the slots of the variables of `env` with a pattern once its scope
+// has ended, so code that still uses them reads obvious garbage
+fn poison(env: &Environment) -> String {
+    let (start, end) = (env.base_stack, env.stack_end());
+    if !env.sanitizing() || start >= end {
+        return String::new();
+    }
+    let mov = Instruction::MovImmediate {
+        dst: Register::Rcx,
+        value: POISON,
+    };
+    let mut code = format!("{mov}\n    ");
+    let mut offset = start;
+    while offset < end {
+        let width = [
+            asm::Size::Qword,
+            asm::Size::Dword,
+            asm::Size::Word,
+            asm::Size::Byte,
+        ]
+        .into_iter()
+        .find(|width| width.bytes() <= end - offset)
+        .unwrap();
+        offset += width.bytes();
+        let store = Instruction::Store {
+            dst: Address::new(Register::Rbp, -(offset as isize)).sized(width),
+            src: Register::Rcx,
+        };
+        code += &format!("{store}\n    ");
+    }
+    code
+}
+
+// Generates a statement of a block or the body of an if or a loop, counting
+// how often it runs if the program is instrumented for coverage.
+// Declarations and labels don't run, so they aren't counted
+fn statement(stmt: &ast::Node, env: &mut Environment) -> Result<String, GeneratorError> {
+    let counted = !matches!(
+        stmt,
+        ast::Node::FnDecl { .. }
+            | ast::Node::Extern { .. }
+            | ast::Node::ArrayDecl { .. }
+            | ast::Node::StructDecl { .. }
+            | ast::Node::EnumDecl { .. }
+            | ast::Node::UnionDecl { .. }
+            | ast::Node::TypeDef { .. }
+            | ast::Node::Label { .. }
+    );
+    match env.statement_counter(counted) {
+        Some(counter) => {
+            let inc = Instruction::Inc {
+                dst: counter.sized(asm::Size::Qword),
+            };
+            Ok(format!("{inc}\n    {}", stmt.generate(env)?))
         }
+        None => stmt.generate(env),
     }
 }
 
-fn size(env: &Environment, properties: &Vec<(String, String)>) -> Result<usize, GeneratorError> {
+// Increments the call counter of `function`, if the program is profiled
+fn count_call(env: &Environment, function: &str) -> String {
+    match env.counter(function) {
+        Some(counter) => {
+            let inc = Instruction::Inc {
+                dst: counter.sized(asm::Size::Qword),
+            };
+            format!("{inc}\n    ")
+        }
+        None => String::new(),
+    }
+}
+
+// The routine writing the profile to `path`: the names of the counted
+// functions, each on its own line and followed by an empty line, then their
+// counters as little endian qwords in the same order. A file that can't be
+// opened is skipped, it shouldn't change how the program ends. The file is
+// opened with O_WRONLY | O_CREAT | O_TRUNC (577) and mode 0644 (420)
+fn profile_dump(env: &Environment, path: &str) -> String {
+    let counters = env.counters.borrow();
+    let mut names = counters.join("\n").into_bytes();
+    names.extend(b"\n\n");
+    let names_length = names.len();
+    let names = env.constant(
+        "profile_names",
+        asm::Size::Byte,
+        names.into_iter().map(u64::from).collect(),
+    );
+    let mut path = path.as_bytes().to_vec();
+    path.push(0);
+    let path = env.constant(
+        "profile_path",
+        asm::Size::Byte,
+        path.into_iter().map(u64::from).collect(),
+    );
+    let done = env.new_label("profile_done");
+    format!(
+        "
+{DUMP}:
+    mov rax, 2
+    lea rdi, {path}
+    mov rsi, 577
+    mov rdx, 420
+    syscall
+    test rax, rax
+    js {done}
+    push rax
+    mov rdi, rax
+    mov rax, 1
+    lea rsi, {names}
+    mov rdx, {names_length}
+    syscall
+    mov rdi, qword [rsp]
+    mov rax, 1
+    lea rsi, [rel {COUNTERS}]
+    mov rdx, {}
+    syscall
+    pop rdi
+    mov rax, 3
+    syscall
+{done}:
+    ret
+",
+        8 * counters.len()
+    )
+}
+
+// The variable holding where a function returns a large struct to. It can't
+// be referred to by the program, names can't start with a dot
+const SRET: &str = ".sret";
+const POINTER: Datatype = Datatype::Single {
+    size: 8,
+    signed: false,
+};
+
+// Holds the index of an array element while its address is used. Nothing
+// else touches it, so it survives the copies through rcx and the rdx of
+// divisions and returned structs
+const INDEX_REGISTER: Register = Register::R11;
+
+/// The registers the first integer arguments of a call are passed in, as
+/// specified by the System V ABI.
+pub const ARGUMENT_REGISTERS: [Register; 6] = [
+    Register::Rdi,
+    Register::Rsi,
+    Register::Rdx,
+    Register::Rcx,
+    Register::R8,
+    Register::R9,
+];
+
+/// The registers the arguments of a fastcall function are passed in. Its
+/// body never touches them, so they're read where they arrived.
+pub const FASTCALL_REGISTERS: [Register; 5] = [
+    Register::Rdi,
+    Register::Rsi,
+    Register::R8,
+    Register::R9,
+    Register::R10,
+];
+
+/// The registers the number of a Linux system call and its arguments are
+/// passed in.
+pub const SYSCALL_REGISTERS: [Register; 7] = [
+    Register::Rax,
+    Register::Rdi,
+    Register::Rsi,
+    Register::Rdx,
+    Register::R10,
+    Register::R8,
+    Register::R9,
+];
+
+// Registers the signature of a function declaration, before any code that
+// could call it is generated
+fn declare_function(env: &mut Environment, node: &ast::Node) -> Result<(), GeneratorError> {
+    let (name, params, return_type, variadic, external) = match node {
+        ast::Node::FnDecl {
+            name,
+            params,
+            return_type,
+            ..
+        } => (name, params, return_type, false, false),
+        ast::Node::Extern {
+            name,
+            params,
+            variadic,
+            return_type,
+            ..
+        } => (name, params, return_type, *variadic, true),
+        _ => return Ok(()),
+    };
+    if env.functions.contains_key(name) {
+        return Err(GeneratorError::FunctionAlreadyExists);
+    }
+
+    let function = FunctionData {
+        params: params
+            .iter()
+            .map(|(datatype, _)| env.lookup_datatype(datatype))
+            .collect::<Result<_, _>>()?,
+        return_type: return_type
+            .as_ref()
+            .map(|datatype| env.lookup_datatype(datatype))
+            .transpose()?,
+        variadic,
+        external,
+        fastcall: false,
+    };
+    env.functions.insert(name.clone(), function);
+    Ok(())
+}
+
+// Whether a function is called the fast way, which only -O2 does. That's
+// an internal function that does nothing but return an expression of its
+// scalar parameters: it never calls anything and needs no memory of its
+// own, so it does without a frame, reads its arguments where they arrive
+// and its callers don't align the stack. Exported functions and functions
+// whose address is taken can be called by code that doesn't know about it
+fn fastcall(env: &Environment, function: &ast::Node, program: &ast::Node) -> bool {
+    let ast::Node::FnDecl { name, body, .. } = function else {
+        return false;
+    };
+    if env.optimize < 2 || env.entry == Entry::Freestanding {
+        return false;
+    }
+    let Ok(data) = env.lookup_function(name) else {
+        return false;
+    };
+    let scalar = |datatype: &Datatype| datatype.operand_size().is_ok();
+    if data.params.len() > FASTCALL_REGISTERS.len()
+        || !data.params.iter().all(scalar)
+        || !data.return_type.as_ref().is_some_and(scalar)
+    {
+        return false;
+    }
+    let ast::Node::Scope { body, .. } = &**body else {
+        return false;
+    };
+    let [ast::Node::Return {
+        value: Some(value), ..
+    }] = body.as_slice()
+    else {
+        return false;
+    };
+    in_registers_only(value) && !names(program, name)
+}
+
+// Whether an expression can be evaluated without touching memory other than
+// the stack: no calls, no assignments and no addresses
+fn in_registers_only(node: &ast::Node) -> bool {
+    match node {
+        ast::Node::Integer { .. }
+        | ast::Node::Bool { .. }
+        | ast::Node::Char { .. }
+        | ast::Node::Float { .. }
+        | ast::Node::Null { .. }
+        | ast::Node::Identifier { .. }
+        | ast::Node::SizeOfType { .. }
+        | ast::Node::SizeOfValue { .. } => true,
+        ast::Node::UnaryOp {
+            op: ast::UnaryOperator::Deref | ast::UnaryOperator::AddressOf,
+            ..
+        } => false,
+        ast::Node::BinOp { .. } | ast::Node::UnaryOp { .. } | ast::Node::Ternary { .. } => {
+            node.children().into_iter().all(in_registers_only)
+        }
+        ast::Node::Cast { value, .. } => in_registers_only(value),
+        _ => false,
+    }
+}
+
+// Whether `name` is used anywhere in `node` other than to call it
+fn names(node: &ast::Node, name: &str) -> bool {
+    match node {
+        ast::Node::Identifier { value, .. } if value == name => true,
+        _ => node.children().into_iter().any(|child| names(child, name)),
+    }
+}
+
+// Whether a variable is a parameter that's kept in a register
+fn in_register(env: &Environment, name: &str) -> bool {
+    env.lookup_var(name)
+        .is_ok_and(|var_data| var_data.register.is_some())
+}
+
+// Calls a function the fast way. The arguments are evaluated from left to
+// right and moved to their registers, the last one straight from rax
+fn call_fast(
+    env: &mut Environment,
+    name: &str,
+    args: &[ast::Node],
+    params: &[Datatype],
+) -> Result<String, GeneratorError> {
+    if args.len() != params.len() {
+        return Err(GeneratorError::WrongArgumentCount);
+    }
+    let mut code = String::new();
+    for (i, (arg, datatype)) in args.iter().zip(params).enumerate() {
+        code += &convert(env, arg, datatype)?;
+        if i + 1 < args.len() {
+            code += "\n    push rax\n    ";
+        }
+    }
+    if let Some(last) = args.len().checked_sub(1) {
+        code += &format!("\n    mov {}, rax\n    ", FASTCALL_REGISTERS[last]);
+        for register in FASTCALL_REGISTERS[..last].iter().rev() {
+            code += &format!("pop {register}\n    ");
+        }
+    }
+    Ok(code + &format!("call {name}\n    "))
+}
+
+// Gives every label in `node` its asm label up front, so a goto can jump to
+// a label further down
+fn declare_labels(node: &ast::Node, env: &mut Environment) -> Result<(), GeneratorError> {
+    if let ast::Node::Label { name, .. } = node {
+        if env.labels.contains_key(name) {
+            return Err(GeneratorError::LabelAlreadyExists);
+        }
+        let label = env.new_label(name);
+        env.labels.insert(name.clone(), label);
+    }
+    // A function's labels are its own, they're declared along with its body
+    if let ast::Node::FnDecl { .. } = node {
+        return Ok(());
+    }
+
+    for child in node.children() {
+        declare_labels(child, env)?;
+    }
+    Ok(())
+}
+
+// Jumps to the label of the case matching rax, or to `default`. Cases that
+// cover at least half of their range go through a jump table, sparse ones
+// through a chain of comparisons
+fn dispatch(env: &Environment, cases: &[(i32, String)], default: &str) -> String {
+    let min = cases
+        .iter()
+        .map(|(value, _)| *value as i64)
+        .min()
+        .unwrap_or(0);
+    let max = cases
+        .iter()
+        .map(|(value, _)| *value as i64)
+        .max()
+        .unwrap_or(0);
+    let range = (max - min + 1) as usize;
+
+    if cases.len() >= 4 && range <= cases.len() * 2 {
+        let table = env.new_label("switch_table");
+        let mut targets = vec![default; range];
+        for (value, label) in cases {
+            targets[(*value as i64 - min) as usize] = label;
+        }
+        return format!(
+            "sub rax, {min}
+    cmp rax, {}
+    ja {default}
+    {}
+    jmp [rcx + rax * 8]
+{table}:
+    dq {}",
+            range - 1,
+            Instruction::Lea {
+                dst: Register::Rcx,
+                src: Address::label(&table),
+            },
+            targets.join(", ")
+        );
+    }
+
+    let mut code = String::new();
+    for (value, label) in cases {
+        code += &format!(
+            "cmp rax, {value}
+    je {label}
+    "
+        );
+    }
+    code + &format!("jmp {default}")
+}
+
+// The code of a call to an operation the compiler provides itself, `None` if
+// there's no intrinsic called `name`. Their memory accesses are always
+// emitted exactly as written, which memory mapped registers rely on
+fn intrinsic(
+    env: &mut Environment,
+    name: &str,
+    args: &[ast::Node],
+) -> Result<Option<String>, GeneratorError> {
+    let int = env.lookup_datatype("int")?;
+    let code = match (name, args) {
+        // example: volatile_load(753664)
+        ("volatile_load", [address]) => {
+            let load = int.load(Register::Rax, Address::new(Register::Rax, 0))?;
+            format!(
+                "{}
+    {load}
+    ",
+                address.generate(env)?
+            )
+        }
+        // example: volatile_store(753664, 3872)
+        ("volatile_store", [address, value]) => {
+            let store = Instruction::Store {
+                dst: Address::new(Register::Rcx, 0).sized(int.operand_size()?),
+                src: Register::Rax,
+            };
+            // The stored value is the value of the expression
+            format!(
+                "{}
+    push rax
+    {}
+    pop rcx
+    {store}
+    ",
+                address.generate(env)?,
+                value.generate(env)?
+            )
+        }
+        // example: syscall(60, 0)
+        ("syscall", [_, ..]) if args.len() <= SYSCALL_REGISTERS.len() => {
+            let mut code = String::new();
+            for arg in args {
+                code += &format!("{}\n    push rax\n    ", arg.generate(env)?);
+            }
+            for register in SYSCALL_REGISTERS[..args.len()].iter().rev() {
+                code += &format!("pop {register}\n    ");
+            }
+            // The kernel returns the result in rax
+            code + "syscall\n    "
+        }
+        ("volatile_load" | "volatile_store" | "syscall", _) => {
+            return Err(GeneratorError::WrongArgumentCount)
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(code))
+}
+
+// Whether the value of an expression is a float, which makes the operators
+// using it float operations
+fn is_float(env: &Environment, node: &ast::Node) -> bool {
+    if let Some(datatype) = env.type_of(node) {
+        return matches!(datatype, Datatype::Float { .. });
+    }
+    let datatype = match node {
+        ast::Node::Float { .. } => return true,
+        ast::Node::BinOp {
+            left, right, op, ..
+        } => {
+            return matches!(
+                op,
+                ast::Operator::Add | ast::Operator::Sub | ast::Operator::Mul | ast::Operator::Div
+            ) && (is_float(env, left) || is_float(env, right))
+        }
+        ast::Node::UnaryOp {
+            value,
+            op: ast::UnaryOperator::Neg,
+            ..
+        } => return is_float(env, value),
+        ast::Node::Ternary { then, else_, .. } => {
+            return is_float(env, then) || is_float(env, else_)
+        }
+        ast::Node::Identifier { value, .. } if in_register(env, value) => env
+            .lookup_var(value)
+            .map(|var_data| var_data.datatype.clone()),
+        ast::Node::Identifier { .. }
+        | ast::Node::MemberAccess { .. }
+        | ast::Node::Index { .. }
+        | ast::Node::UnaryOp {
+            op: ast::UnaryOperator::Deref,
+            ..
+        } => address(env, node).map(|(_, datatype)| datatype),
+        ast::Node::Assign { target, .. } | ast::Node::IncDec { target, .. } => {
+            address(env, target).map(|(_, datatype)| datatype)
+        }
+        ast::Node::Cast { datatype, .. } => env.lookup_datatype(datatype),
+        ast::Node::Call { name, .. } => {
+            let callee = ast::Node::Identifier {
+                value: name.clone(),
+                id: ast::NodeId::DETACHED,
+            };
+            match address(env, &callee) {
+                Ok((_, Datatype::Function { return_type, .. })) => return_type
+                    .map(|datatype| *datatype)
+                    .ok_or(GeneratorError::NotAScalar),
+                _ => env.lookup_function(name).and_then(|function| {
+                    function
+                        .return_type
+                        .clone()
+                        .ok_or(GeneratorError::NotAScalar)
+                }),
+            }
+        }
+        _ => return false,
+    };
+    matches!(datatype, Ok(Datatype::Float { .. }))
+}
+
+// Applies a binary operator to the float bits in eax and ecx. An int operand
+// is converted to a float first
+fn float_operation(
+    op: &ast::Operator,
+    left_float: bool,
+    right_float: bool,
+) -> Result<String, GeneratorError> {
+    let left = if left_float {
+        "movd xmm0, eax"
+    } else {
+        "cvtsi2ss xmm0, rax"
+    };
+    let right = if right_float {
+        "movd xmm1, ecx"
+    } else {
+        "cvtsi2ss xmm1, rcx"
+    };
+    // Comparisons with NaN are false, except for != which is true. ucomiss
+    // reports NaN as unordered by setting ZF, PF and CF all at once, so
+    // < and <= swap the operands and test CF like > and >= do. -0.0 and 0.0
+    // compare equal
+    let operation = match op {
+        ast::Operator::Add => "addss xmm0, xmm1\n    movd eax, xmm0",
+        ast::Operator::Sub => "subss xmm0, xmm1\n    movd eax, xmm0",
+        ast::Operator::Mul => "mulss xmm0, xmm1\n    movd eax, xmm0",
+        ast::Operator::Div => "divss xmm0, xmm1\n    movd eax, xmm0",
+        ast::Operator::Equal => {
+            "ucomiss xmm0, xmm1
+    sete al
+    setnp cl
+    and al, cl
+    movzx rax, al"
+        }
+        ast::Operator::NotEqual => {
+            "ucomiss xmm0, xmm1
+    setne al
+    setp cl
+    or al, cl
+    movzx rax, al"
+        }
+        ast::Operator::Less => "ucomiss xmm1, xmm0\n    seta al\n    movzx rax, al",
+        ast::Operator::LessEqual => "ucomiss xmm1, xmm0\n    setae al\n    movzx rax, al",
+        ast::Operator::Greater => "ucomiss xmm0, xmm1\n    seta al\n    movzx rax, al",
+        ast::Operator::GreaterEqual => "ucomiss xmm0, xmm1\n    setae al\n    movzx rax, al",
+        ast::Operator::Mod
+        | ast::Operator::BitAnd
+        | ast::Operator::BitOr
+        | ast::Operator::BitXor
+        | ast::Operator::ShiftLeft
+        | ast::Operator::ShiftRight => return Err(GeneratorError::NotAnInteger),
+        ast::Operator::LogicalAnd | ast::Operator::LogicalOr => {
+            unreachable!("short-circuiting operators are generated separately")
+        }
+    };
+    Ok(format!("{left}\n    {right}\n    {operation}"))
+}
+
+// The type a pointer operand points to, `None` if it's not a pointer
+fn pointer(env: &Environment, node: &ast::Node) -> Option<Datatype> {
+    match value_type(env, node) {
+        Ok(Datatype::Pointer { target }) => Some(*target),
+        _ => None,
+    }
+}
+
+// Adds to or subtracts from the pointer in rax or rcx, or subtracts two
+// pointers. Like in C, pointers move by whole elements and their
+// difference counts elements rather than bytes
+fn pointer_operation(
+    op: &ast::Operator,
+    pointers: (Option<Datatype>, Option<Datatype>),
+) -> Result<String, GeneratorError> {
+    let scaled = |register: Register, target: &Datatype| match target.size() {
+        1 => String::new(),
+        size => format!("imul {register}, {register}, {size}\n    "),
+    };
+    match (op, pointers) {
+        (ast::Operator::Add, (Some(target), None)) => {
+            Ok(format!("{}add rax, rcx", scaled(Register::Rcx, &target)))
+        }
+        (ast::Operator::Sub, (Some(target), None)) => {
+            Ok(format!("{}sub rax, rcx", scaled(Register::Rcx, &target)))
+        }
+        (ast::Operator::Add, (None, Some(target))) => {
+            Ok(format!("{}add rax, rcx", scaled(Register::Rax, &target)))
+        }
+        (ast::Operator::Sub, (Some(left), Some(right))) if left == right => Ok(format!(
+            "sub rax, rcx
+    mov rcx, {}
+    cqo
+    idiv rcx",
+            left.size()
+        )),
+        (ast::Operator::Sub, (Some(_), Some(_))) => Err(GeneratorError::MismatchedPointers),
+        // Neither adding two pointers nor subtracting one from an integer
+        // means anything
+        _ => Err(GeneratorError::NotAnInteger),
+    }
+}
+
+// Sets rax to 1 if the comparison of rax with rcx meets the condition code, 0 otherwise
+fn compare(condition: &str) -> String {
+    format!(
+        "cmp rax, rcx
+    set{condition} al
+    movzx rax, al"
+    )
+}
+
+// Makes room below rbp for the deepest the variables of a frame reach, in
+// nested scopes too. The size is rounded up to a multiple of 16, so a
+// function leaves rsp as aligned as it found it after pushing rbp
+fn allocate_frame(env: &Environment) -> String {
+    match env.frame_size.get().next_multiple_of(16) {
+        0 => String::new(),
+        size => format!("sub rsp, {size}"),
+    }
+}
+
+// Stores the values of a struct literal into the struct of type `datatype`
+// at `address`, one property at a time. Every property has to be given a
+// value exactly once, properties with a default can be left out
+fn store_struct_literal(
+    env: &mut Environment,
+    data: &[(Option<String>, ast::Node)],
+    datatype: &Datatype,
+    address: &Address,
+) -> Result<String, GeneratorError> {
+    let (properties, union) = match datatype {
+        Datatype::Struct { properties, .. } => (properties, false),
+        Datatype::Union { properties, .. } => (properties, true),
+        _ => return Err(GeneratorError::CannotAssignSingleValuetoStruct),
+    };
+    // A union holds one of its properties at a time, so exactly one is given
+    if union && data.len() > 1 {
+        return Err(GeneratorError::TooManyValues);
+    }
+    if union && data.is_empty() {
+        return Err(GeneratorError::MissingProperty);
+    }
+
+    let mut code = String::new();
+    let mut initialized = vec![false; properties.len()];
+    let mut next = 0;
+    for (name, expr) in data {
+        let index = match name {
+            Some(name) => properties
+                .iter()
+                .position(|property| property.name == *name)
+                .ok_or(GeneratorError::PropertyDoesNotExist)?,
+            None if next < properties.len() => next,
+            None => return Err(GeneratorError::TooManyValues),
+        };
+        if initialized[index] {
+            return Err(GeneratorError::DuplicateProperty);
+        }
+        initialized[index] = true;
+        next = index + 1;
+
+        let property = &properties[index];
+        let address = address.offset_by(property.offset);
+        if let Datatype::Struct { .. } | Datatype::Union { .. } = property.datatype {
+            code += &match expr {
+                ast::Node::StructData { data, .. } => {
+                    store_struct_literal(env, data, &property.datatype, &address)?
+                }
+                _ => copy_struct(env, expr, &property.datatype, &address)?,
+            };
+            continue;
+        }
+        let store = match property.bits {
+            Some(bits) => write_bits(&property.datatype, address, bits)?,
+            None => Instruction::Store {
+                dst: address.sized(property.datatype.operand_size()?),
+                src: Register::Rax,
+            }
+            .to_string(),
+        };
+
+        code += &format!(
+            "{}
+    {store}
+    ",
+            convert(env, expr, &property.datatype)?,
+        );
+    }
+    let uninitialized = properties
+        .iter()
+        .zip(initialized)
+        .filter(|(_, init)| !init && !union);
+    for (property, _) in uninitialized {
+        let value = property.default.ok_or(GeneratorError::MissingProperty)?;
+        let mov = Instruction::MovImmediate {
+            dst: Register::Rax,
+            value,
+        };
+        let address = address.offset_by(property.offset);
+        let store = match property.bits {
+            Some(bits) => write_bits(&property.datatype, address, bits)?,
+            None => Instruction::Store {
+                dst: address.sized(property.datatype.operand_size()?),
+                src: Register::Rax,
+            }
+            .to_string(),
+        };
+        code += &format!("{mov}\n    {store}\n    ");
+    }
+    Ok(code)
+}
+
+// Copies `size` bytes from `src` to `dst` through rcx, using the widest
+// moves that fit
+fn copy(dst: &Address, src: &Address, size: usize) -> String {
+    let mut code = String::new();
+    let mut offset = 0;
+    while offset < size {
+        let width = [
+            asm::Size::Qword,
+            asm::Size::Dword,
+            asm::Size::Word,
+            asm::Size::Byte,
+        ]
+        .into_iter()
+        .find(|width| width.bytes() <= size - offset)
+        .unwrap();
+        let load = Instruction::Load {
+            dst: Register::Rcx,
+            src: src.offset_by(offset).sized(width),
+        };
+        let store = Instruction::Store {
+            dst: dst.offset_by(offset).sized(width),
+            src: Register::Rcx,
+        };
+        code += &format!("{load}\n    {store}\n    ");
+        offset += width.bytes();
+    }
+    code
+}
+
+// Copies the struct `value` evaluates to into `dst`
+fn copy_struct(
+    env: &mut Environment,
+    value: &ast::Node,
+    datatype: &Datatype,
+    dst: &Address,
+) -> Result<String, GeneratorError> {
+    let (code, src) = struct_value(env, value, datatype)?;
+    Ok(format!("{code}{}", copy(dst, &src, datatype.size())))
+}
+
+// The code evaluating `value`, a struct of type `datatype`, and where the
+// struct is once it has run. Variables, properties and elements are used
+// where they're stored, literals are built in a temporary, and assignments
+// and calls leave the address of their struct in rax
+fn struct_value(
+    env: &mut Environment,
+    value: &ast::Node,
+    datatype: &Datatype,
+) -> Result<(String, Address), GeneratorError> {
+    let rax = Address::new(Register::Rax, 0);
+    let (code, (src, src_datatype)) = match value {
+        ast::Node::StructData { data, .. } => {
+            let temporary = env.temporary(datatype.size());
+            let code = store_struct_literal(env, data, datatype, &temporary)?;
+            (code, (temporary, datatype.clone()))
+        }
+        ast::Node::Identifier { .. }
+        | ast::Node::MemberAccess { .. }
+        | ast::Node::Index { .. }
+        | ast::Node::UnaryOp {
+            op: ast::UnaryOperator::Deref,
+            ..
+        } => (address_index(env, value)?, address(env, value)?),
+        ast::Node::Assign { target, .. } => {
+            let (_, datatype) = address(env, target)?;
+            (value.generate(env)?, (rax, datatype))
+        }
+        ast::Node::Call { name, .. } => {
+            let return_type =
+                return_type(env, name)?.ok_or(GeneratorError::CannotAssignSingleValuetoStruct)?;
+            (value.generate(env)?, (rax, return_type))
+        }
+        _ => return Err(GeneratorError::CannotAssignSingleValuetoStruct),
+    };
+    match src_datatype {
+        Datatype::Struct { .. } | Datatype::Union { .. } if src_datatype == *datatype => {
+            Ok((code, src))
+        }
+        Datatype::Struct { .. } | Datatype::Union { .. } => Err(GeneratorError::MismatchedStruct),
+        _ => Err(GeneratorError::CannotAssignSingleValuetoStruct),
+    }
+}
+
+// What calling `name` returns, whether it's a function or a variable
+// holding a function's address
+fn return_type(env: &Environment, name: &str) -> Result<Option<Datatype>, GeneratorError> {
+    let callee = ast::Node::Identifier {
+        value: name.to_owned(),
+        id: ast::NodeId::DETACHED,
+    };
+    match address(env, &callee) {
+        Ok((_, Datatype::Function { return_type, .. })) => Ok(return_type.map(|t| *t)),
+        Ok(_) => Err(GeneratorError::NotAFunction),
+        Err(_) => Ok(env.lookup_function(name)?.return_type.clone()),
+    }
+}
+
+// Whether a struct argument is passed in registers, given how many are
+// taken by the arguments before it. Larger structs, and ones that would only
+// partly fit, are passed on the stack
+fn in_registers(datatype: &Datatype, taken: usize) -> bool {
+    let size = datatype.size();
+    size <= 16 && taken + size.div_ceil(8) <= ARGUMENT_REGISTERS.len()
+}
+
+// Whether a struct is returned through memory the caller provides rather
+// than in rax and rdx, which only hold 16 bytes
+fn returns_in_memory(datatype: &Datatype) -> bool {
+    matches!(datatype, Datatype::Struct { .. } | Datatype::Union { .. }) && datatype.size() > 16
+}
+
+// Returns a struct the way the System V ABI does: up to 16 bytes in rax and
+// rdx, larger ones are copied to the memory the caller passed the address
+// of, which is returned in rax
+fn return_struct(
+    env: &mut Environment,
+    value: &ast::Node,
+    datatype: &Datatype,
+) -> Result<String, GeneratorError> {
+    let (code, src) = struct_value(env, value, datatype)?;
+    if returns_in_memory(datatype) {
+        let (sret, _) = address(
+            env,
+            &ast::Node::Identifier {
+                value: SRET.to_owned(),
+                id: ast::NodeId::DETACHED,
+            },
+        )?;
+        let load = Instruction::Load {
+            dst: Register::Rdx,
+            src: sret.sized(asm::Size::Qword),
+        };
+        let copy = copy(&Address::new(Register::Rdx, 0), &src, datatype.size());
+        return Ok(format!("{code}{load}\n    {copy}mov rax, rdx\n    "));
+    }
+
+    // Copied to a temporary first, so the registers can be loaded a whole
+    // qword at a time without reading past the end of the struct
+    let temporary = env.temporary(16);
+    let copy = copy(&temporary, &src, datatype.size());
+    let loads = [Register::Rax, Register::Rdx]
+        .into_iter()
+        .enumerate()
+        .take(datatype.size().div_ceil(8))
+        .map(|(i, dst)| Instruction::Load {
+            dst,
+            src: temporary.offset_by(8 * i).sized(asm::Size::Qword),
+        });
+    let mut code = format!("{code}{copy}");
+    for load in loads {
+        code += &format!("{load}\n    ");
+    }
+    Ok(code)
+}
+
+// The bits of a literal, possibly negated, as a value of type `datatype`.
+// Defaults are evaluated where the struct is declared, so they can't refer
+// to anything that only exists where it's used
+fn constant_value(node: &ast::Node, datatype: &Datatype) -> Result<i64, GeneratorError> {
+    let float = matches!(datatype, Datatype::Float { .. });
+    match node {
+        ast::Node::Integer { value, .. } if float => Ok((*value as f32).to_bits() as i64),
+        ast::Node::Integer { value, .. } => Ok(*value as i64),
+        ast::Node::Float { value, .. } if float => Ok(value.to_bits() as i64),
+        ast::Node::Float { value, .. } => Ok(*value as i64),
+        ast::Node::Bool { value, .. } => Ok(*value as i64),
+        ast::Node::Char { value, .. } if float => Ok((*value as f32).to_bits() as i64),
+        ast::Node::Char { value, .. } => Ok(*value as i64),
+        ast::Node::UnaryOp {
+            value,
+            op: ast::UnaryOperator::Neg,
+            ..
+        } => {
+            let value = constant_value(value, datatype)?;
+            // Only the sign bit of a float differs from its negation
+            Ok(if float { value ^ (1 << 31) } else { -value })
+        }
+        _ => Err(GeneratorError::DefaultNotConstant),
+    }
+}
+
+// Lays the properties out in declaration order, each at the next offset
+// that's a multiple of its alignment. The size is padded to a multiple of
+// the struct's alignment too, so every element of an array is aligned.
+// Packed structs have no padding and an alignment of 1.
+//
+// Bitfields are packed into units of their type's size, each one going
+// right after the bits before it unless that would cross into the next
+// unit, where it starts instead. The property after a bitfield starts at
+// the next whole byte
+fn struct_layout(
+    env: &Environment,
+    properties: &Vec<ast::Property>,
+    packed: bool,
+) -> Result<Datatype, GeneratorError> {
+    let mut layout = vec![];
+    // Counted in bits, as bitfields can end in the middle of a byte
+    let mut end: usize = 0;
+    let mut align = 1;
+    for property in properties {
+        let datatype = env.lookup_datatype(&property.datatype)?;
+        let default = default_value(env, property, &datatype)?;
+        let property_align = if packed { 1 } else { datatype.align() };
+        let (offset, bits) = match property.bits {
+            Some(width) => {
+                let unit = 8 * datatype.size();
+                check_bitfield(&datatype, width)?;
+                if end % unit + width as usize > unit {
+                    end = end.next_multiple_of(unit);
+                }
+                let offset = end / unit * datatype.size();
+                let shift = (end % unit) as u32;
+                end += width as usize;
+                (offset, Some((shift, width)))
+            }
+            None => {
+                let offset = end.div_ceil(8).next_multiple_of(property_align);
+                end = 8 * (offset + datatype.size());
+                (offset, None)
+            }
+        };
+        align = align.max(property_align);
+        layout.push(Property {
+            name: property.name.clone(),
+            offset,
+            datatype,
+            default,
+            bits,
+        });
+    }
+    Ok(Datatype::Struct {
+        size: end.div_ceil(8).next_multiple_of(align),
+        align,
+        properties: layout,
+    })
+}
+
+// The value of a property's default, recorded for its node
+fn default_value(
+    env: &Environment,
+    property: &ast::Property,
+    datatype: &Datatype,
+) -> Result<Option<i64>, GeneratorError> {
+    property
+        .default
+        .as_ref()
+        .map(|default| {
+            let value = constant_value(default, datatype)?;
+            env.record_value(default, value);
+            Ok(value)
+        })
+        .transpose()
+}
+
+// Only ints, bools and enums can be bitfields, and at most as wide as
+// their type
+fn check_bitfield(datatype: &Datatype, width: u32) -> Result<(), GeneratorError> {
+    match datatype {
+        Datatype::Single { .. } | Datatype::Enum { .. }
+            if width >= 1 && width as usize <= 8 * datatype.size() =>
+        {
+            Ok(())
+        }
+        _ => Err(GeneratorError::InvalidBitfield),
+    }
+}
+
+// The bits of the property `node` accesses, if it's a bitfield
+fn bitfield(env: &Environment, node: &ast::Node) -> Result<Option<(u32, u32)>, GeneratorError> {
+    let ast::Node::MemberAccess { target, member, .. } = node else {
+        return Ok(None);
+    };
+    let (_, datatype) = address(env, target)?;
+    let (Datatype::Struct { properties, .. } | Datatype::Union { properties, .. }) = datatype
+    else {
+        return Ok(None);
+    };
+    Ok(properties
+        .into_iter()
+        .find(|property| property.name == *member)
+        .and_then(|property| property.bits))
+}
+
+// Reads a bitfield into rax: its unit is loaded, the bits above the field
+// shifted out and the field shifted down to bit 0, extending its sign if
+// its type is signed
+fn read_bits(
+    datatype: &Datatype,
+    address: Address,
+    (shift, width): (u32, u32),
+) -> Result<String, GeneratorError> {
+    let load = datatype.load(Register::Rax, address)?;
+    let right = if datatype.is_signed() { "sar" } else { "shr" };
+    Ok(format!(
+        "{load}\n    shl rax, {}\n    {right} rax, {}\n    ",
+        64 - shift - width,
+        64 - width
+    ))
+}
+
+// Writes rax to a bitfield, keeping the other bits of its unit. Only the
+// lowest bits of the value fit, so rax is left holding the value the field
+// has now, read back like `read_bits` would. rcx and rdx are clobbered
+fn write_bits(
+    datatype: &Datatype,
+    address: Address,
+    (shift, width): (u32, u32),
+) -> Result<String, GeneratorError> {
+    let size = datatype.operand_size()?;
+    let load = Instruction::Load {
+        dst: Register::Rdx,
+        src: address.clone().sized(size),
+    };
+    let store = Instruction::Store {
+        dst: address.sized(size),
+        src: Register::Rdx,
+    };
+    // The mask is sign extended to the operand size, so it's written as a
+    // negative number rather than one too large for the immediate
+    let field = ((1u64 << width) - 1) << shift;
+    let keep = (!field << (64 - 8 * size.bytes())) as i64 >> (64 - 8 * size.bytes());
+    let right = if datatype.is_signed() { "sar" } else { "shr" };
+    let (rcx, rdx) = (Register::Rcx.name(size), Register::Rdx.name(size));
+    Ok(format!(
+        "shl rax, {left}
+    mov rcx, rax
+    shr rcx, {down}
+    {right} rax, {left}
+    {load}
+    and {rdx}, {keep}
+    or {rdx}, {rcx}
+    {store}
+    ",
+        left = 64 - width,
+        down = 64 - width - shift,
+    ))
+}
+
+// Every property of a union starts at offset 0, so it's as large as its
+// largest property, padded to a multiple of its alignment like a struct.
+// Only one property holds a value at a time, so they can't have defaults
+fn union_layout(
+    env: &Environment,
+    properties: &Vec<ast::Property>,
+) -> Result<Datatype, GeneratorError> {
+    let mut layout: Vec<Property> = vec![];
     let mut size = 0;
-    for prop in properties {
-        size += env.lookup_datatype(&prop.0)?.size();
+    let mut align = 1;
+    for property in properties {
+        if property.default.is_some() {
+            return Err(GeneratorError::DefaultInUnion);
+        }
+        let datatype = env.lookup_datatype(&property.datatype)?;
+        if let Some(width) = property.bits {
+            check_bitfield(&datatype, width)?;
+        }
+        size = size.max(datatype.size());
+        align = align.max(datatype.align());
+        layout.push(Property {
+            name: property.name.clone(),
+            offset: 0,
+            datatype,
+            default: None,
+            bits: property.bits.map(|width| (0, width)),
+        });
+    }
+    Ok(Datatype::Union {
+        size: size.next_multiple_of(align),
+        align,
+        properties: layout,
+    })
+}
+
+/// The effective address of an l-value along with its type. Every load,
+/// store and address computation goes through here, so they all agree on
+/// where things live.
+pub fn address(env: &Environment, node: &ast::Node) -> Result<(Address, Datatype), GeneratorError> {
+    match node {
+        ast::Node::Identifier { value, .. } => {
+            let var_data = env.lookup_var(value)?;
+            if var_data.register.is_some() {
+                return Err(GeneratorError::NotAnLvalue);
+            }
+            let address = Address::new(Register::Rbp, -(var_data.location as isize));
+            Ok((address, var_data.datatype.clone()))
+        }
+        ast::Node::MemberAccess { target, member, .. } => {
+            let (address, datatype) = self::address(env, target)?;
+            let (Datatype::Struct { properties, .. } | Datatype::Union { properties, .. }) =
+                datatype
+            else {
+                return Err(GeneratorError::NotAStruct);
+            };
+            let property = properties
+                .into_iter()
+                .find(|property| property.name == *member)
+                .ok_or(GeneratorError::PropertyDoesNotExist)?;
+            Ok((address.offset_by(property.offset), property.datatype))
+        }
+        ast::Node::Index { target, index, .. } => {
+            let (address, datatype) = self::address(env, target)?;
+            let Datatype::Array { element, len } = datatype else {
+                return Err(GeneratorError::NotAnArray);
+            };
+            // Constant indices are folded into the offset, others are read
+            // from the register `address_index` leaves them in
+            if let ast::Node::Integer { value: index, .. } = **index {
+                let index = index as usize;
+                if index >= len {
+                    return Err(GeneratorError::IndexOutOfBounds);
+                }
+                return Ok((address.offset_by(index * element.size()), *element));
+            }
+            let Address::Register {
+                base,
+                index: outer,
+                offset,
+            } = address
+            else {
+                return Err(GeneratorError::NotAnLvalue);
+            };
+            // Behind a pointer, the index is added to the address that's
+            // already in the register
+            if base == INDEX_REGISTER {
+                return Ok((address, *element));
+            }
+            // With the indices of outer dimensions added in, the register
+            // holds a distance in bytes
+            let scale = match outer {
+                Some(_) => Scale::One,
+                None => scale(element.size()).unwrap_or(Scale::One),
+            };
+            let address = Address::Register {
+                base,
+                index: Some((INDEX_REGISTER, scale)),
+                offset,
+            };
+            Ok((address, *element))
+        }
+        ast::Node::UnaryOp {
+            value,
+            op: ast::UnaryOperator::Deref,
+            ..
+        } => {
+            let Datatype::Pointer { target } = value_type(env, value)? else {
+                return Err(GeneratorError::NotAPointer);
+            };
+            // `address_index` leaves the pointer in the index register
+            Ok((Address::new(INDEX_REGISTER, 0), *target))
+        }
+        _ => Err(GeneratorError::NotAnLvalue),
+    }
+}
+
+// The size `sizeof` stands for. Only the type of its operand is worked out,
+// nothing is evaluated. A variable takes precedence over a type of the same
+// name, like it would in C
+fn size_of(env: &Environment, node: &ast::Node) -> Result<usize, GeneratorError> {
+    let datatype = match node {
+        ast::Node::SizeOfType { datatype, .. } => match env.lookup_var(datatype) {
+            Ok(var_data) => var_data.datatype.clone(),
+            Err(_) => env.lookup_datatype(datatype)?,
+        },
+        ast::Node::SizeOfValue { value, .. } => value_type(env, value)?,
+        _ => unreachable!("only sizeof has a size to generate"),
+    };
+    Ok(datatype.size())
+}
+
+// The type of the value an expression evaluates to. Struct literals and
+// calls of functions that don't return anything have none. A node is only
+// ever evaluated in one scope, so its type is worked out once and recorded
+fn value_type(env: &Environment, node: &ast::Node) -> Result<Datatype, GeneratorError> {
+    if let Some(datatype) = env.type_of(node) {
+        return Ok(datatype);
+    }
+    let datatype = infer_type(env, node)?;
+    env.record_type(node, &datatype);
+    Ok(datatype)
+}
+
+fn infer_type(env: &Environment, node: &ast::Node) -> Result<Datatype, GeneratorError> {
+    match node {
+        ast::Node::Integer { .. } => env.lookup_datatype("int"),
+        ast::Node::Bool { .. } => env.lookup_datatype("bool"),
+        ast::Node::Char { .. } => env.lookup_datatype("char"),
+        ast::Node::Null { .. } => Ok(Datatype::Null),
+        ast::Node::SizeOfType { .. } | ast::Node::SizeOfValue { .. } => env.lookup_datatype("int"),
+        ast::Node::Cast { datatype, .. } => env.lookup_datatype(datatype),
+        ast::Node::Float { .. } => env.lookup_datatype("float"),
+        ast::Node::UnaryOp {
+            value,
+            op: ast::UnaryOperator::AddressOf,
+            ..
+        } => {
+            let (_, target) = address(env, value)?;
+            Ok(Datatype::Pointer {
+                target: Box::new(target),
+            })
+        }
+        ast::Node::Assign { target, .. } | ast::Node::IncDec { target, .. } => {
+            address(env, target).map(|(_, datatype)| datatype)
+        }
+        ast::Node::Call { name, .. } => return_type(env, name)?.ok_or(GeneratorError::NotAScalar),
+        // A pointer moved by a number of elements is still a pointer
+        ast::Node::BinOp {
+            left,
+            right,
+            op: op @ (ast::Operator::Add | ast::Operator::Sub),
+            ..
+        } if pointer(env, left).is_some() || pointer(env, right).is_some() => {
+            match (pointer(env, left), pointer(env, right)) {
+                (Some(target), None) => Ok(Datatype::Pointer {
+                    target: Box::new(target),
+                }),
+                (None, Some(target)) if matches!(op, ast::Operator::Add) => Ok(Datatype::Pointer {
+                    target: Box::new(target),
+                }),
+                (Some(_), Some(_)) if matches!(op, ast::Operator::Sub) => {
+                    env.lookup_datatype("int")
+                }
+                _ => Err(GeneratorError::NotAnInteger),
+            }
+        }
+        ast::Node::UnaryOp {
+            op: ast::UnaryOperator::Deref,
+            ..
+        } => address(env, node).map(|(_, datatype)| datatype),
+        // Everything else computes a number, comparisons included
+        ast::Node::BinOp { .. } | ast::Node::UnaryOp { .. } | ast::Node::Ternary { .. }
+            if is_float(env, node) =>
+        {
+            env.lookup_datatype("float")
+        }
+        ast::Node::BinOp { .. } | ast::Node::UnaryOp { .. } => env.lookup_datatype("int"),
+        ast::Node::Ternary { then, .. } => value_type(env, then),
+        // Parameters kept in a register have no address
+        ast::Node::Identifier { value, .. } if in_register(env, value) => env
+            .lookup_var(value)
+            .map(|var_data| var_data.datatype.clone()),
+        ast::Node::Identifier { value, .. }
+            if env.lookup_var(value).is_err() && env.lookup_member(value).is_ok() =>
+        {
+            env.lookup_member(value)
+                .map(|(datatype, _)| datatype.clone())
+        }
+        // A function's name on its own is its address
+        ast::Node::Identifier { value, .. }
+            if env.lookup_var(value).is_err() && env.lookup_function(value).is_ok() =>
+        {
+            let function = env.lookup_function(value)?;
+            Ok(Datatype::Function {
+                params: function.params.clone(),
+                return_type: function.return_type.clone().map(Box::new),
+            })
+        }
+        _ => address(env, node).map(|(_, datatype)| datatype),
+    }
+}
+
+// The code evaluating `value` and converting it to `datatype`, the type of
+// wherever it's stored. Values whose type isn't known are used as they are
+fn convert(
+    env: &mut Environment,
+    value: &ast::Node,
+    datatype: &Datatype,
+) -> Result<String, GeneratorError> {
+    let Ok(from) = value_type(env, value) else {
+        return value.generate(env);
+    };
+    let conversion =
+        conversions::implicit(&from, datatype).ok_or(GeneratorError::MismatchedTypes)?;
+    apply(env, value, &from, datatype, conversion)
+}
+
+// The code evaluating `value`, of type `from`, and converting it to `to`
+fn apply(
+    env: &mut Environment,
+    value: &ast::Node,
+    from: &Datatype,
+    to: &Datatype,
+    conversion: Conversion,
+) -> Result<String, GeneratorError> {
+    let code = match conversion {
+        Conversion::Decay => {
+            let index = address_index(env, value)?;
+            let (address, _) = address(env, value)?;
+            let lea = Instruction::Lea {
+                dst: Register::Rax,
+                src: address,
+            };
+            return Ok(format!("{index}{lea}\n    "));
+        }
+        Conversion::Keep => return value.generate(env),
+        Conversion::ToBool => {
+            let rax = Register::Rax.name(from.operand_size()?);
+            format!("test {rax}, {rax}\n    setne al\n    movzx rax, al")
+        }
+        Conversion::IntToFloat => "cvtsi2ss xmm0, rax\n    movd eax, xmm0".to_owned(),
+        Conversion::FloatToInt => "movd xmm0, eax\n    cvttss2si rax, xmm0".to_owned(),
+        Conversion::Narrow => {
+            let Datatype::Single { signed, .. } = *to else {
+                unreachable!("only integers are narrowed to")
+            };
+            let size = to.operand_size()?;
+            let low = Register::Rax.name(size);
+            match (size, signed) {
+                (asm::Size::Qword, _) => return value.generate(env),
+                (asm::Size::Dword, true) => format!("movsxd rax, {low}"),
+                // Writing the low dword of a register clears the rest of it
+                (asm::Size::Dword, false) => format!("mov {low}, {low}"),
+                (_, true) => format!("movsx rax, {low}"),
+                (_, false) => format!("movzx rax, {low}"),
+            }
+        }
+    };
+    Ok(format!("{}\n    {code}\n    ", value.generate(env)?))
+}
+
+// The code putting the part of an l-value's address that's only known at run
+// time, the indices of array elements, in the index register. Empty when the
+// whole address is known up front
+fn address_index(env: &mut Environment, node: &ast::Node) -> Result<String, GeneratorError> {
+    match node {
+        ast::Node::MemberAccess { target, .. } => address_index(env, target),
+        ast::Node::UnaryOp {
+            value,
+            op: ast::UnaryOperator::Deref,
+            ..
+        } => Ok(format!(
+            "{}mov {INDEX_REGISTER}, rax\n    ",
+            value.generate(env)?
+        )),
+        ast::Node::Index { target, index, .. } => {
+            let outer = address_index(env, target)?;
+            if let ast::Node::Integer { .. } = **index {
+                return Ok(outer);
+            }
+            if is_float(env, index) {
+                return Err(GeneratorError::NotAnInteger);
+            }
+            let (address, Datatype::Array { element, .. }) = address(env, target)? else {
+                return Err(GeneratorError::NotAnArray);
+            };
+            let index = index.generate(env)?;
+            // An address read from a pointer is added to like a distance
+            let outer_scale = match address {
+                Address::Register {
+                    index: Some((_, scale)),
+                    ..
+                } => Some(scale),
+                Address::Register { base, .. } if base == INDEX_REGISTER => Some(Scale::One),
+                _ => None,
+            };
+            let Some(outer_scale) = outer_scale else {
+                // Elements too big to be scaled by the address are scaled here
+                let scaled = match scale(element.size()) {
+                    Some(_) => String::new(),
+                    None => format!("imul rax, rax, {}\n    ", element.size()),
+                };
+                return Ok(format!("{index}{scaled}mov {INDEX_REGISTER}, rax\n    "));
+            };
+
+            // The outer indices are kept while this one is evaluated, then
+            // both are added up into a single distance in bytes
+            let scaled = match element.size() {
+                1 => String::new(),
+                size => format!("imul rax, rax, {size}\n    "),
+            };
+            let lea = Instruction::Lea {
+                dst: INDEX_REGISTER,
+                src: Address::Register {
+                    base: Register::Rax,
+                    index: Some((INDEX_REGISTER, outer_scale)),
+                    offset: 0,
+                },
+            };
+            Ok(format!(
+                "{outer}push {INDEX_REGISTER}\n    {index}{scaled}pop {INDEX_REGISTER}\n    {lea}\n    "
+            ))
+        }
+        _ => Ok(String::new()),
+    }
+}
+
+// The scale of an index register that steps over elements of `size` bytes
+fn scale(size: usize) -> Option<Scale> {
+    match size {
+        1 => Some(Scale::One),
+        2 => Some(Scale::Two),
+        4 => Some(Scale::Four),
+        8 => Some(Scale::Eight),
+        _ => None,
     }
-    Ok(size)
 }
@@ -1,11 +1,33 @@
 extern crate phf;
+use crate::source::FileId;
 use phf::phf_map;
 use std::fmt;
 
 pub static KEYWORDS: phf::Map<&'static str, TokenType> = phf_map! {
     "fn" => TokenType::Fn,
+    "extern" => TokenType::Extern,
     "typedef" => TokenType::TypeDef,
     "struct" => TokenType::Struct,
+    "enum" => TokenType::Enum,
+    "union" => TokenType::Union,
+    "packed" => TokenType::Packed,
+    "if" => TokenType::If,
+    "else" => TokenType::Else,
+    "while" => TokenType::While,
+    "for" => TokenType::For,
+    "do" => TokenType::Do,
+    "break" => TokenType::Break,
+    "continue" => TokenType::Continue,
+    "switch" => TokenType::Switch,
+    "case" => TokenType::Case,
+    "default" => TokenType::Default,
+    "goto" => TokenType::Goto,
+    "return" => TokenType::Return,
+    "true" => TokenType::True,
+    "false" => TokenType::False,
+    "null" => TokenType::Null,
+    "as" => TokenType::As,
+    "sizeof" => TokenType::SizeOf,
 };
 
 #[derive(Debug, Clone, PartialEq)]
@@ -13,14 +35,78 @@ pub enum TokenType {
     Identifier,
     Integer,
     Float,
+    Char,
+    True,
+    False,
+    Null,
+    As,
+    SizeOf,
     Add,
+    Sub,
+    Star,
+    Slash,
+    Percent,
+    Ampersand,
+    Pipe,
+    Caret,
+    AmpersandAmpersand,
+    PipePipe,
+    ShiftLeft,
+    ShiftRight,
+    EqualsEquals,
+    BangEquals,
+    Less,
+    LessEquals,
+    Greater,
+    GreaterEquals,
+    Bang,
+    Tilde,
+    AddAdd,
+    SubSub,
+    AddEquals,
+    SubEquals,
+    StarEquals,
+    SlashEquals,
+    PercentEquals,
+    AmpersandEquals,
+    PipeEquals,
+    CaretEquals,
+    ShiftLeftEquals,
+    ShiftRightEquals,
+    Arrow,
+    Ellipsis,
+    Dot,
+    At,
+    String,
     Fn,
+    Extern,
     TypeDef,
     Struct,
+    Enum,
+    Union,
+    Packed,
+    If,
+    Else,
+    While,
+    For,
+    Do,
+    Break,
+    Continue,
+    Switch,
+    Case,
+    Default,
+    Goto,
+    Return,
     OpenBrace,
     CloseBrace,
+    OpenParen,
+    CloseParen,
+    OpenBracket,
+    CloseBracket,
     Equals,
     Semicolon,
+    Colon,
+    Question,
     Comma,
 }
 
@@ -30,6 +116,8 @@ pub struct Token {
     pub value: String,
     pub start_index: usize,
     pub end_index: usize,
+    /// The file of the indices
+    pub file: FileId,
 }
 
 impl fmt::Display for Token {
@@ -44,49 +132,512 @@ impl fmt::Display for Token {
 
 #[derive(Debug)]
 pub enum LexerError {
-    IllegalCharacter,
-    InvalidFloat,
+    IllegalCharacter {
+        character: char,
+        index: usize,
+    },
+    InvalidFloat {
+        value: String,
+        start_index: usize,
+        end_index: usize,
+    },
+    UnterminatedString {
+        start_index: usize,
+    },
+    InvalidChar {
+        value: String,
+        start_index: usize,
+        end_index: usize,
+    },
 }
 
-pub fn lex(script: String) -> Result<Vec<Token>, LexerError> {
+impl fmt::Display for LexerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LexerError::IllegalCharacter { character, .. } => {
+                write!(f, "Illegal character {:?}", character)
+            }
+            LexerError::InvalidFloat { value, .. } => write!(f, "Invalid float {}", value),
+            LexerError::UnterminatedString { .. } => write!(f, "Unterminated string"),
+            LexerError::InvalidChar { value, .. } => write!(f, "Invalid character {}", value),
+        }
+    }
+}
+
+impl LexerError {
+    /// The indices of the first and last character the error is about. An
+    /// unterminated string is about its opening quote.
+    pub fn span(&self) -> (usize, usize) {
+        match self {
+            LexerError::IllegalCharacter { index, .. } => (*index, *index),
+            LexerError::InvalidFloat {
+                start_index,
+                end_index,
+                ..
+            }
+            | LexerError::InvalidChar {
+                start_index,
+                end_index,
+                ..
+            } => (*start_index, *end_index),
+            LexerError::UnterminatedString { start_index } => (*start_index, *start_index),
+        }
+    }
+}
+
+/// The byte a character literal such as `'a'` or `'\n'` stands for, quotes
+/// included. Only ASCII characters and the escapes `\n`, `\t`, `\r`, `\0`,
+/// `\\`, `\'` and `\"` fit in a char.
+pub fn char_value(literal: &str) -> Option<u8> {
+    let inner = literal.strip_prefix('\'')?.strip_suffix('\'')?;
+    let mut chars = inner.chars();
+    let c = match (chars.next()?, chars.next()) {
+        ('\\', Some(escape)) => match escape {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            '\\' | '\'' | '"' => escape,
+            _ => return None,
+        },
+        ('\'' | '\n', _) => return None,
+        (c, None) => c,
+        _ => return None,
+    };
+    match chars.next() {
+        None if c.is_ascii() => Some(c as u8),
+        _ => None,
+    }
+}
+
+/// Splits the script into tokens. Invalid input is skipped and reported in
+/// the returned errors, so the tokens around it can still be used.
+pub fn lex(script: String) -> (Vec<Token>, Vec<LexerError>) {
+    lex_file(script, FileId::default())
+}
+
+/// Like `lex`, for the file `file` of a program made of several. `lex` lexes
+/// the first one.
+pub fn lex_file(script: String, file: FileId) -> (Vec<Token>, Vec<LexerError>) {
     let mut tokens = Vec::new();
+    let mut errors = Vec::new();
 
+    // Indexing a Vec is constant time, chars().nth() would make lexing quadratic
+    let script: Vec<char> = script.chars().collect();
     let mut i = 0;
     while i < script.len() {
-        let c = script.chars().nth(i).unwrap();
+        let c = script[i];
+        let next = script.get(i + 1).copied();
 
         if c.is_whitespace() {
             i += 1;
             continue;
         }
 
+        // Line comments run until the end of the line
+        if c == '/' && next == Some('/') {
+            while i < script.len() && script[i] != '\n' {
+                i += 1;
+            }
+            continue;
+        }
+
         if c == ',' {
             tokens.push(Token {
                 token_type: TokenType::Comma,
                 value: String::from(","),
                 start_index: i,
                 end_index: i,
+                file,
             })
+        } else if c == '.' && next == Some('.') && script.get(i + 2) == Some(&'.') {
+            tokens.push(Token {
+                token_type: TokenType::Ellipsis,
+                value: String::from("..."),
+                start_index: i,
+                end_index: i + 2,
+                file,
+            });
+            i += 2;
+        } else if c == '.' {
+            tokens.push(Token {
+                token_type: TokenType::Dot,
+                value: String::from("."),
+                start_index: i,
+                end_index: i,
+                file,
+            })
+        } else if c == '@' {
+            tokens.push(Token {
+                token_type: TokenType::At,
+                value: String::from("@"),
+                start_index: i,
+                end_index: i,
+                file,
+            })
+        } else if c == '"' {
+            // Strings can't span lines, so a missing quote only swallows the
+            // rest of the line
+            let length = script[i + 1..].iter().position(|&c| c == '"' || c == '\n');
+            match length {
+                Some(length) if script[i + 1 + length] == '"' => {
+                    tokens.push(Token {
+                        token_type: TokenType::String,
+                        value: script[i..=i + 1 + length].iter().collect(),
+                        start_index: i,
+                        end_index: i + 1 + length,
+                        file,
+                    });
+                    i += 1 + length;
+                }
+                _ => {
+                    errors.push(LexerError::UnterminatedString { start_index: i });
+                    i += length.unwrap_or(script.len() - i - 1);
+                }
+            }
+        } else if c == '\'' {
+            // Like strings, a literal ends at the next quote on the line, so
+            // a missing one only swallows the rest of the line. An escaped
+            // quote doesn't end it
+            let from = if next == Some('\\') { i + 3 } else { i + 1 };
+            let end = match (from..script.len()).find(|&j| script[j] == '\'' || script[j] == '\n') {
+                Some(j) if script[j] == '\'' => j,
+                Some(j) => j - 1,
+                None => script.len() - 1,
+            };
+            let value: String = script[i..=end].iter().collect();
+            if char_value(&value).is_some() {
+                tokens.push(Token {
+                    token_type: TokenType::Char,
+                    value,
+                    start_index: i,
+                    end_index: end,
+                    file,
+                });
+            } else {
+                errors.push(LexerError::InvalidChar {
+                    value,
+                    start_index: i,
+                    end_index: end,
+                });
+            }
+            i = end;
         } else if c == ';' {
             tokens.push(Token {
                 token_type: TokenType::Semicolon,
                 value: String::from(";"),
                 start_index: i,
                 end_index: i,
+                file,
             })
+        } else if c == '?' {
+            tokens.push(Token {
+                token_type: TokenType::Question,
+                value: String::from("?"),
+                start_index: i,
+                end_index: i,
+                file,
+            })
+        } else if c == ':' {
+            tokens.push(Token {
+                token_type: TokenType::Colon,
+                value: String::from(":"),
+                start_index: i,
+                end_index: i,
+                file,
+            })
+        } else if c == '=' && next == Some('=') {
+            tokens.push(Token {
+                token_type: TokenType::EqualsEquals,
+                value: String::from("=="),
+                start_index: i,
+                end_index: i + 1,
+                file,
+            });
+            i += 1;
         } else if c == '=' {
             tokens.push(Token {
                 token_type: TokenType::Equals,
                 value: String::from("="),
                 start_index: i,
                 end_index: i,
+                file,
             })
+        } else if c == '+' && next == Some('+') {
+            tokens.push(Token {
+                token_type: TokenType::AddAdd,
+                value: String::from("++"),
+                start_index: i,
+                end_index: i + 1,
+                file,
+            });
+            i += 1;
+        } else if c == '+' && next == Some('=') {
+            tokens.push(Token {
+                token_type: TokenType::AddEquals,
+                value: String::from("+="),
+                start_index: i,
+                end_index: i + 1,
+                file,
+            });
+            i += 1;
         } else if c == '+' {
             tokens.push(Token {
                 token_type: TokenType::Add,
                 value: String::from("+"),
                 start_index: i,
                 end_index: i,
+                file,
+            });
+        } else if c == '-' && next == Some('>') {
+            tokens.push(Token {
+                token_type: TokenType::Arrow,
+                value: String::from("->"),
+                start_index: i,
+                end_index: i + 1,
+                file,
+            });
+            i += 1;
+        } else if c == '-' && next == Some('-') {
+            tokens.push(Token {
+                token_type: TokenType::SubSub,
+                value: String::from("--"),
+                start_index: i,
+                end_index: i + 1,
+                file,
+            });
+            i += 1;
+        } else if c == '-' && next == Some('=') {
+            tokens.push(Token {
+                token_type: TokenType::SubEquals,
+                value: String::from("-="),
+                start_index: i,
+                end_index: i + 1,
+                file,
+            });
+            i += 1;
+        } else if c == '-' {
+            tokens.push(Token {
+                token_type: TokenType::Sub,
+                value: String::from("-"),
+                start_index: i,
+                end_index: i,
+                file,
+            });
+        } else if c == '*' && next == Some('=') {
+            tokens.push(Token {
+                token_type: TokenType::StarEquals,
+                value: String::from("*="),
+                start_index: i,
+                end_index: i + 1,
+                file,
+            });
+            i += 1;
+        } else if c == '*' {
+            tokens.push(Token {
+                token_type: TokenType::Star,
+                value: String::from("*"),
+                start_index: i,
+                end_index: i,
+                file,
+            });
+        } else if c == '/' && next == Some('=') {
+            tokens.push(Token {
+                token_type: TokenType::SlashEquals,
+                value: String::from("/="),
+                start_index: i,
+                end_index: i + 1,
+                file,
+            });
+            i += 1;
+        } else if c == '/' {
+            tokens.push(Token {
+                token_type: TokenType::Slash,
+                value: String::from("/"),
+                start_index: i,
+                end_index: i,
+                file,
+            });
+        } else if c == '%' && next == Some('=') {
+            tokens.push(Token {
+                token_type: TokenType::PercentEquals,
+                value: String::from("%="),
+                start_index: i,
+                end_index: i + 1,
+                file,
+            });
+            i += 1;
+        } else if c == '%' {
+            tokens.push(Token {
+                token_type: TokenType::Percent,
+                value: String::from("%"),
+                start_index: i,
+                end_index: i,
+                file,
+            });
+        } else if c == '&' && next == Some('&') {
+            tokens.push(Token {
+                token_type: TokenType::AmpersandAmpersand,
+                value: String::from("&&"),
+                start_index: i,
+                end_index: i + 1,
+                file,
+            });
+            i += 1;
+        } else if c == '&' && next == Some('=') {
+            tokens.push(Token {
+                token_type: TokenType::AmpersandEquals,
+                value: String::from("&="),
+                start_index: i,
+                end_index: i + 1,
+                file,
+            });
+            i += 1;
+        } else if c == '&' {
+            tokens.push(Token {
+                token_type: TokenType::Ampersand,
+                value: String::from("&"),
+                start_index: i,
+                end_index: i,
+                file,
+            });
+        } else if c == '|' && next == Some('|') {
+            tokens.push(Token {
+                token_type: TokenType::PipePipe,
+                value: String::from("||"),
+                start_index: i,
+                end_index: i + 1,
+                file,
+            });
+            i += 1;
+        } else if c == '|' && next == Some('=') {
+            tokens.push(Token {
+                token_type: TokenType::PipeEquals,
+                value: String::from("|="),
+                start_index: i,
+                end_index: i + 1,
+                file,
+            });
+            i += 1;
+        } else if c == '|' {
+            tokens.push(Token {
+                token_type: TokenType::Pipe,
+                value: String::from("|"),
+                start_index: i,
+                end_index: i,
+                file,
+            });
+        } else if c == '^' && next == Some('=') {
+            tokens.push(Token {
+                token_type: TokenType::CaretEquals,
+                value: String::from("^="),
+                start_index: i,
+                end_index: i + 1,
+                file,
+            });
+            i += 1;
+        } else if c == '^' {
+            tokens.push(Token {
+                token_type: TokenType::Caret,
+                value: String::from("^"),
+                start_index: i,
+                end_index: i,
+                file,
+            });
+        } else if c == '<' && next == Some('<') && script.get(i + 2) == Some(&'=') {
+            tokens.push(Token {
+                token_type: TokenType::ShiftLeftEquals,
+                value: String::from("<<="),
+                start_index: i,
+                end_index: i + 2,
+                file,
+            });
+            i += 2;
+        } else if c == '<' && next == Some('<') {
+            tokens.push(Token {
+                token_type: TokenType::ShiftLeft,
+                value: String::from("<<"),
+                start_index: i,
+                end_index: i + 1,
+                file,
+            });
+            i += 1;
+        } else if c == '>' && next == Some('>') && script.get(i + 2) == Some(&'=') {
+            tokens.push(Token {
+                token_type: TokenType::ShiftRightEquals,
+                value: String::from(">>="),
+                start_index: i,
+                end_index: i + 2,
+                file,
+            });
+            i += 2;
+        } else if c == '>' && next == Some('>') {
+            tokens.push(Token {
+                token_type: TokenType::ShiftRight,
+                value: String::from(">>"),
+                start_index: i,
+                end_index: i + 1,
+                file,
+            });
+            i += 1;
+        } else if c == '>' && next == Some('=') {
+            tokens.push(Token {
+                token_type: TokenType::GreaterEquals,
+                value: String::from(">="),
+                start_index: i,
+                end_index: i + 1,
+                file,
+            });
+            i += 1;
+        } else if c == '<' && next == Some('=') {
+            tokens.push(Token {
+                token_type: TokenType::LessEquals,
+                value: String::from("<="),
+                start_index: i,
+                end_index: i + 1,
+                file,
+            });
+            i += 1;
+        } else if c == '<' {
+            tokens.push(Token {
+                token_type: TokenType::Less,
+                value: String::from("<"),
+                start_index: i,
+                end_index: i,
+                file,
+            });
+        } else if c == '>' {
+            tokens.push(Token {
+                token_type: TokenType::Greater,
+                value: String::from(">"),
+                start_index: i,
+                end_index: i,
+                file,
+            });
+        } else if c == '~' {
+            tokens.push(Token {
+                token_type: TokenType::Tilde,
+                value: String::from("~"),
+                start_index: i,
+                end_index: i,
+                file,
+            });
+        } else if c == '!' && next == Some('=') {
+            tokens.push(Token {
+                token_type: TokenType::BangEquals,
+                value: String::from("!="),
+                start_index: i,
+                end_index: i + 1,
+                file,
+            });
+            i += 1;
+        } else if c == '!' {
+            tokens.push(Token {
+                token_type: TokenType::Bang,
+                value: String::from("!"),
+                start_index: i,
+                end_index: i,
+                file,
             });
         } else if c == '{' {
             tokens.push(Token {
@@ -94,6 +645,7 @@ pub fn lex(script: String) -> Result<Vec<Token>, LexerError> {
                 value: String::from("{"),
                 start_index: i,
                 end_index: i,
+                file,
             });
         } else if c == '}' {
             tokens.push(Token {
@@ -101,40 +653,79 @@ pub fn lex(script: String) -> Result<Vec<Token>, LexerError> {
                 value: String::from("}"),
                 start_index: i,
                 end_index: i,
+                file,
+            });
+        } else if c == '(' {
+            tokens.push(Token {
+                token_type: TokenType::OpenParen,
+                value: String::from("("),
+                start_index: i,
+                end_index: i,
+                file,
+            });
+        } else if c == ')' {
+            tokens.push(Token {
+                token_type: TokenType::CloseParen,
+                value: String::from(")"),
+                start_index: i,
+                end_index: i,
+                file,
             });
-        } else if c.is_alphabetic() {
-            match parse_word(i, &script) {
+        } else if c == '[' {
+            tokens.push(Token {
+                token_type: TokenType::OpenBracket,
+                value: String::from("["),
+                start_index: i,
+                end_index: i,
+                file,
+            });
+        } else if c == ']' {
+            tokens.push(Token {
+                token_type: TokenType::CloseBracket,
+                value: String::from("]"),
+                start_index: i,
+                end_index: i,
+                file,
+            });
+        } else if c.is_alphabetic() || c == '_' {
+            let result = parse_word(i, &script, file);
+            i = result.0;
+            tokens.push(result.1);
+        } else if c.is_ascii_digit() {
+            match parse_number(i, &script, file) {
                 Ok(result) => {
                     i = result.0;
                     tokens.push(result.1);
                 }
-                Err(err) => return Err(err),
-            }
-        } else if c.is_digit(10) {
-            match parse_number(i, &script) {
-                Ok(result) => {
-                    i = result.0;
-                    tokens.push(result.1);
+                Err(err) => {
+                    if let LexerError::InvalidFloat { end_index, .. } = err {
+                        i = end_index;
+                    }
+                    errors.push(err);
                 }
-                Err(err) => return Err(err),
             }
         } else {
-            println!("Illegal character: {}", c);
-            return Err(LexerError::IllegalCharacter);
+            errors.push(LexerError::IllegalCharacter {
+                character: c,
+                index: i,
+            });
         }
 
         i += 1;
     }
 
-    Ok(tokens)
+    for token in &tokens {
+        crate::trace!("lexer", "{token}");
+    }
+    (tokens, errors)
 }
 
-fn parse_word(index: usize, script: &str) -> Result<(usize, Token), LexerError> {
+fn parse_word(index: usize, script: &[char], file: FileId) -> (usize, Token) {
     let mut word = String::from("");
-    let mut end = 0;
+    let mut end = script.len() - 1;
 
-    for (i, c) in script.char_indices().skip(index) {
-        if c.is_alphanumeric() {
+    for (i, &c) in script.iter().enumerate().skip(index) {
+        if c.is_alphanumeric() || c == '_' {
             word.push(c);
         } else {
             end = i - 1;
@@ -142,7 +733,7 @@ fn parse_word(index: usize, script: &str) -> Result<(usize, Token), LexerError>
         }
     }
 
-    Ok((
+    (
         end,
         Token {
             token_type: if KEYWORDS.contains_key(&word) {
@@ -153,27 +744,33 @@ fn parse_word(index: usize, script: &str) -> Result<(usize, Token), LexerError>
             value: word,
             start_index: index,
             end_index: end,
+            file,
         },
-    ))
+    )
 }
 
-fn parse_number(index: usize, script: &str) -> Result<(usize, Token), LexerError> {
+fn parse_number(index: usize, script: &[char], file: FileId) -> Result<(usize, Token), LexerError> {
     let mut number = String::from("");
-    let mut end = 0;
+    let mut end = script.len() - 1;
     let mut dot_count = 0;
-    for (i, c) in script.char_indices().skip(index) {
+    for (i, &c) in script.iter().enumerate().skip(index) {
         if c == '.' {
-            if dot_count == 0 {
-                dot_count += 1;
-            } else {
-                return Err(LexerError::InvalidFloat);
-            }
-        } else if !c.is_digit(10) {
+            dot_count += 1;
+        } else if !c.is_ascii_digit() {
             end = i - 1;
             break;
         }
         number.push(c);
     }
+
+    // The whole malformed number is reported so lexing can resume after it
+    if dot_count > 1 {
+        return Err(LexerError::InvalidFloat {
+            value: number,
+            start_index: index,
+            end_index: end,
+        });
+    }
     Ok((
         end,
         Token {
@@ -185,6 +782,7 @@ fn parse_number(index: usize, script: &str) -> Result<(usize, Token), LexerError
             value: number,
             start_index: index,
             end_index: end,
+            file,
         },
     ))
 }
@@ -0,0 +1,260 @@
+pub mod asm;
+pub mod ast;
+pub mod callgraph;
+pub mod calls;
+pub mod conversions;
+pub mod coverage;
+pub mod cst;
+pub mod features;
+pub mod filecheck;
+pub mod generator;
+pub mod lexer;
+pub mod log;
+pub mod parser;
+pub mod passes;
+pub mod prelude;
+pub mod profile;
+pub mod resolver;
+pub mod source;
+pub mod stress;
+pub mod tables;
+pub mod toolchain;
+pub mod warnings;
+
+use features::Features;
+use passes::{PassManager, PassPoint};
+use source::{FileId, SourceMap};
+
+/// How to compile a program besides its source. The defaults are how
+/// `compile` compiles.
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// The name diagnostics give the source
+    pub filename: String,
+    pub features: Features,
+    /// Make the prelude's functions, like exit and assert, available to
+    /// the program
+    pub prelude: bool,
+    /// Generate code for every function, even the ones that can never be
+    /// called
+    pub keep_all_functions: bool,
+    /// How many columns apart tab stops are in diagnostics
+    pub tab_width: usize,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            filename: "<source>".to_owned(),
+            features: Features::default(),
+            prelude: true,
+            keep_all_functions: false,
+            tab_width: source::DEFAULT_TAB_WIDTH,
+        }
+    }
+}
+
+/// What compiling a program gives.
+#[derive(Debug)]
+pub struct Compiled {
+    pub asm: String,
+    /// Warnings about the program, formatted for reporting like errors are
+    pub warnings: Vec<String>,
+    /// Every function of the program and what it calls, before the ones
+    /// that can never be called were dropped
+    pub graph: callgraph::CallGraph,
+    /// The counter of every instrumented statement, see `coverage::map`
+    pub counters: Vec<String>,
+}
+
+/// Compiles `source` to assembly, running the passes registered in `passes`
+/// at their points in the pipeline.
+pub fn compile(
+    source: &str,
+    features: &Features,
+    passes: &mut PassManager,
+) -> Result<String, String> {
+    compile_with(source, features, passes, |_| {})
+}
+
+/// Like `compile`, letting `configure` set up the generator first, e.g. to
+/// sanitize the program.
+pub fn compile_with(
+    source: &str,
+    features: &Features,
+    passes: &mut PassManager,
+    configure: impl FnOnce(&mut generator::Environment),
+) -> Result<String, String> {
+    let options = Options {
+        features: features.clone(),
+        ..Options::default()
+    };
+    Ok(compile_program(source, &options, passes, configure)?.asm)
+}
+
+/// The whole pipeline, which `rlang build` runs too: lexing, parsing,
+/// checking calls and features, warnings, the registered passes, dropping
+/// functions that are never called and generating code with the generator
+/// `configure` sets up. Errors are formatted for reporting, pointing at
+/// the source where they can.
+pub fn compile_program(
+    source: &str,
+    options: &Options,
+    passes: &mut PassManager,
+    configure: impl FnOnce(&mut generator::Environment),
+) -> Result<Compiled, String> {
+    let mut map = SourceMap::with_tab_width(options.tab_width);
+    let file = map.add(&options.filename, source);
+    let (tokens, errors) = lexer::lex_file(source.to_owned(), file);
+    if !errors.is_empty() {
+        return Err(lexer_errors(&map, file, &errors));
+    }
+    let parsed = parser::parse_program(source, tokens, ast::NodeId(0))
+        .map_err(|err| syntax_error(&map, file, &err))?;
+    let (mut ast, cst, statements) = (parsed.ast, parsed.cst, parsed.statements);
+    if let Err(err) = features::check(&cst, &options.features) {
+        return Err(diagnostic(
+            &map,
+            file,
+            &err.to_string(),
+            err.start_index,
+            err.start_index,
+        ));
+    }
+    if options.prelude {
+        prelude::inject(&mut ast, &mut map);
+    }
+
+    let resolution = resolver::resolve(&cst);
+    let warnings = warnings::check(&cst, &resolution)
+        .into_iter()
+        .map(|warning| {
+            let mut report = diagnostic(
+                &map,
+                file,
+                &warning.message,
+                warning.start_index,
+                warning.end_index,
+            );
+            if let Some(fix) = warning.fix {
+                let (end_line, end_col) = map.position(file, fix.end_index);
+                report += &format!(
+                    "\n  help: remove {}-{end_line}:{end_col}",
+                    map.location(file, fix.start_index)
+                );
+            }
+            report
+        })
+        .collect();
+
+    // Every mismatched call is reported at once, each pointing at what it
+    // doesn't match
+    let errors: Vec<String> = calls::check(&cst, &resolution)
+        .into_iter()
+        .map(|err| {
+            let label = diagnostic(
+                &map,
+                file,
+                &err.label.message,
+                err.label.start_index,
+                err.label.end_index,
+            );
+            diagnostic(&map, file, &err.message, err.start_index, err.end_index)
+                + "\nnote: "
+                + &label
+        })
+        .collect();
+    if !errors.is_empty() {
+        return Err(errors.join("\nerror: "));
+    }
+
+    let mut env = generator::Environment::new();
+    configure(&mut env);
+    // Coverage counts the statements that were just parsed
+    if let Some(generator::Instrumentation::Coverage {
+        statements: counted,
+        ..
+    }) = &mut env.instrumentation
+    {
+        *counted = statements;
+    }
+
+    passes
+        .run(PassPoint::AfterParse, &mut ast)
+        .map_err(|err| format!("{err:?}"))?;
+    // A freestanding program's top-level code still needs a node, though
+    // nothing runs it
+    let entry = match &env.entry {
+        generator::Entry::Symbol(entry) => entry.as_str(),
+        generator::Entry::Freestanding => callgraph::ENTRY,
+    };
+    let graph = callgraph::build(&ast, entry);
+    // Any function of a freestanding program can be called from outside, and
+    // functions that are never called are what coverage is looking for
+    let coverage = matches!(
+        env.instrumentation,
+        Some(generator::Instrumentation::Coverage { .. })
+    );
+    if !options.keep_all_functions && env.entry != generator::Entry::Freestanding && !coverage {
+        callgraph::prune(&mut ast, &graph);
+    }
+    passes
+        .run(PassPoint::BeforeCodegen, &mut ast)
+        .map_err(|err| format!("{err:?}"))?;
+
+    // Errors are about the innermost node they happened in that's from the
+    // file, not the prelude or a pass
+    let asm = ast.generate(&mut env).map_err(|err| {
+        let message = format!("{err:?}");
+        let failed = env.failed.borrow();
+        match failed
+            .iter()
+            .find_map(|id| parsed.spans.get(*id).filter(|span| span.file == file))
+        {
+            Some(span) => diagnostic(&map, file, &message, span.start, span.end),
+            None => message,
+        }
+    })?;
+    let counters = env.counters.borrow().clone();
+    Ok(Compiled {
+        asm,
+        warnings,
+        graph,
+        counters,
+    })
+}
+
+// A message about the source from `start` to `end`, with where that is and
+// the line it's on
+fn diagnostic(map: &SourceMap, file: FileId, message: &str, start: usize, end: usize) -> String {
+    format!(
+        "{message}\n  --> {}\n{}",
+        map.location(file, start),
+        map.snippet(file, start, end)
+    )
+}
+
+/// Formats the lexer errors of `file` for reporting, each pointing at the
+/// source it's about.
+pub fn lexer_errors(map: &SourceMap, file: FileId, errors: &[lexer::LexerError]) -> String {
+    errors
+        .iter()
+        .map(|err| {
+            let (start, end) = err.span();
+            diagnostic(map, file, &err.to_string(), start, end)
+        })
+        .collect::<Vec<_>>()
+        .join("\nerror: ")
+}
+
+/// Formats a syntax error of `file` for reporting, pointing at the source
+/// it's about.
+pub fn syntax_error(map: &SourceMap, file: FileId, err: &parser::SyntaxError) -> String {
+    diagnostic(
+        map,
+        file,
+        &format!("{:?}", err.error),
+        err.start_index,
+        err.end_index,
+    )
+}
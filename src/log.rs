@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+/// How much detail a message carries, from the most to the least important.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "error" => Ok(Level::Error),
+            "warn" => Ok(Level::Warn),
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            "trace" => Ok(Level::Trace),
+            _ => Err(format!("Unknown log level {s}")),
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Level::Error => write!(f, "ERROR"),
+            Level::Warn => write!(f, "WARN"),
+            Level::Info => write!(f, "INFO"),
+            Level::Debug => write!(f, "DEBUG"),
+            Level::Trace => write!(f, "TRACE"),
+        }
+    }
+}
+
+/// Which messages get logged, parsed from a list like `parser=debug,codegen=trace`.
+/// An entry without a target, like `info`, applies to every other target.
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    default: Option<Level>,
+    targets: HashMap<String, Level>,
+}
+
+impl FromStr for Filter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut filter = Filter::default();
+        for directive in s.split(',').filter(|d| !d.is_empty()) {
+            match directive.split_once('=') {
+                Some((target, level)) => {
+                    filter.targets.insert(target.to_owned(), level.parse()?);
+                }
+                None => filter.default = Some(directive.parse()?),
+            }
+        }
+        Ok(filter)
+    }
+}
+
+impl Filter {
+    pub fn enabled(&self, target: &str, level: Level) -> bool {
+        match self.targets.get(target).or(self.default.as_ref()) {
+            Some(max) => level <= *max,
+            None => false,
+        }
+    }
+}
+
+static FILTER: OnceLock<Filter> = OnceLock::new();
+
+/// Installs the filter for the rest of the process, only the first call has an effect.
+pub fn init(filter: Filter) {
+    let _ = FILTER.set(filter);
+}
+
+/// Whether a message for `target` at `level` would be logged, nothing is
+/// logged before `init`.
+pub fn enabled(target: &str, level: Level) -> bool {
+    FILTER
+        .get()
+        .is_some_and(|filter| filter.enabled(target, level))
+}
+
+pub fn write(target: &str, level: Level, args: fmt::Arguments) {
+    eprintln!("[{level} {target}] {args}");
+}
+
+// The phases of the pipeline log under these targets:
+// lexer, parser, resolver, callgraph, passes and codegen
+#[macro_export]
+macro_rules! log {
+    ($level:expr, $target:expr, $($arg:tt)+) => {
+        if $crate::log::enabled($target, $level) {
+            $crate::log::write($target, $level, format_args!($($arg)+));
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! info {
+    ($target:expr, $($arg:tt)+) => { $crate::log!($crate::log::Level::Info, $target, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($target:expr, $($arg:tt)+) => { $crate::log!($crate::log::Level::Debug, $target, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($target:expr, $($arg:tt)+) => { $crate::log!($crate::log::Level::Trace, $target, $($arg)+) };
+}
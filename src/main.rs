@@ -1,109 +1,729 @@
-use clap::{Parser, Subcommand};
-use std::collections::HashMap;
+use clap::{Parser, Subcommand, ValueEnum};
+use rlang::{
+    callgraph, coverage, features, generator, lexer, log, parser,
+    passes::PassManager,
+    profile, resolver,
+    source::{self, FileId, SourceMap},
+    stress, toolchain,
+};
+use std::collections::{HashMap, HashSet};
+use std::ffi::{OsStr, OsString};
 use std::fs;
-use std::io::Write;
-use std::process;
-
-mod ast;
-mod generator;
-mod lexer;
-mod parser;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::process::{self, ExitCode};
 
 #[derive(Debug, Parser)]
 #[command(version, about, long_about = None)]
 struct Args {
     #[command(subcommand)]
     command: Commands,
+
+    /// Log what the compiler is doing, e.g. `parser=debug,codegen=trace` or just `debug`
+    #[arg(long, global = true)]
+    log: Option<log::Filter>,
+
+    /// How many columns apart tab stops are, for the columns of positions
+    /// and for lining up the source shown in diagnostics and coverage
+    #[arg(long, global = true, default_value_t = source::DEFAULT_TAB_WIDTH as u8, value_parser = clap::value_parser!(u8).range(1..))]
+    tab_width: u8,
+}
+
+#[derive(Debug, clap::Args)]
+struct BuildOptions {
+    filename: String,
+
+    #[arg(short, long)]
+    output: Option<String>,
+
+    /// Extra artifacts to write next to the output
+    #[arg(long, value_enum)]
+    emit: Vec<Emit>,
+
+    /// Generate code for every function, even the ones that can never be called
+    #[arg(long)]
+    keep_all_functions: bool,
+
+    /// The edition of the language the program is written in
+    #[arg(long, default_value_t)]
+    edition: features::Edition,
+
+    /// Enable a feature that isn't part of the edition yet
+    #[arg(long = "feature")]
+    features: Vec<features::Feature>,
+
+    /// Link with a shared library, e.g. `-l c` to call libc's functions
+    #[arg(short = 'l', long = "library")]
+    libraries: Vec<String>,
+
+    /// The symbol the program starts at
+    #[arg(long, default_value = callgraph::ENTRY)]
+    entry: String,
+
+    /// Don't generate an entry point and export every function, for code
+    /// that's linked into a kernel or another runtime. Only the object file
+    /// is written
+    #[arg(long, conflicts_with = "entry")]
+    freestanding: bool,
+
+    /// Link with this linker script, which also links freestanding programs
+    #[arg(long)]
+    linker_script: Option<PathBuf>,
+
+    /// Count how often every function is called. The counts are written to
+    /// a .profile file next to the output when the program exits, read it
+    /// with report-profile
+    #[arg(long, conflicts_with = "freestanding")]
+    instrument_profile: bool,
+
+    /// Count how often every statement runs, for finding code the tests
+    /// don't reach. The counts are written to a .coverage file next to the
+    /// output when the program exits, show them with cov
+    #[arg(long, conflicts_with_all = ["freestanding", "instrument_profile"])]
+    instrument_coverage: bool,
+
+    /// Guard every variable with a canary that's checked when its scope
+    /// ends, and fill the slots of ended scopes with a pattern. Programs
+    /// that overwrite the stack abort instead of going on with bad values
+    #[arg(long)]
+    sanitize: bool,
+
+    /// Check divisions for a zero divisor, so the program aborts with a
+    /// message instead of crashing
+    #[arg(long)]
+    checked: bool,
+
+    /// How much to optimize. From -O2 on, small functions that are only
+    /// called from within the program take their arguments in registers
+    /// and don't set up a stack frame
+    #[arg(short = 'O', default_value_t = 0, value_parser = clap::value_parser!(u8).range(0..=2))]
+    optimize: u8,
+
+    /// Don't make the prelude's functions, like exit and assert, available
+    /// to the program. Useful for freestanding programs, where every
+    /// function ends up exported
+    #[arg(long)]
+    no_prelude: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum Emit {
+    /// The call graph in DOT format
+    Callgraph,
+    /// The call graph in JSON format
+    CallgraphJson,
 }
 
 #[derive(Debug, Subcommand)]
 enum Commands {
     #[command()]
     Run {
+        #[command(flatten)]
+        options: BuildOptions,
+    },
+
+    #[command()]
+    Build {
+        #[command(flatten)]
+        options: BuildOptions,
+    },
+
+    /// Rename the variable or type at a position along with all its references
+    #[command()]
+    Rename {
         filename: String,
 
-        #[arg(short, long)]
-        output: Option<String>,
+        #[arg(long)]
+        line: usize,
+
+        #[arg(long)]
+        col: usize,
+
+        #[arg(long)]
+        to: String,
     },
 
+    /// Print every reference to the variable or type at a position
     #[command()]
-    Build {
+    Refs {
+        filename: String,
+
+        #[arg(long)]
+        line: usize,
+
+        #[arg(long)]
+        col: usize,
+    },
+
+    /// Print where the variable or type at a position is declared
+    #[command()]
+    Def {
         filename: String,
 
-        #[arg(short, long)]
-        output: Option<String>,
+        #[arg(long)]
+        line: usize,
+
+        #[arg(long)]
+        col: usize,
+    },
+
+    /// Check that programs can be built and run on this machine
+    #[command()]
+    Doctor,
+
+    /// Print the call counts of a program built with --instrument-profile
+    #[command()]
+    ReportProfile { filename: PathBuf },
+
+    /// Print the source of a program built with --instrument-coverage with
+    /// how often each line ran
+    #[command()]
+    Cov {
+        /// The program, its .covmap and .coverage files are read
+        program: PathBuf,
     },
+
+    /// Tools for working on the compiler itself
+    #[command(subcommand, hide = true)]
+    Devtool(Devtool),
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
+#[derive(Debug, Subcommand)]
+enum Devtool {
+    /// Print a large random program that compiles, for benchmarking the compiler
+    GenStress {
+        /// Roughly how many statements to generate
+        #[arg(long, default_value_t = 1000)]
+        stmts: usize,
+
+        /// How deep blocks may be nested
+        #[arg(long, default_value_t = 8)]
+        depth: usize,
+
+        #[arg(long, default_value_t = 1)]
+        seed: u64,
+    },
+}
+
+fn main() -> ExitCode {
     let args = Args::parse();
-    match args.command {
-        Commands::Run { filename, output } => {
-            let outputfile = build(filename, output)?;
-            process::Command::new(outputfile)
-                .spawn()
-                .expect("Failed to run output");
+    if let Some(filter) = args.log {
+        log::init(filter);
+    }
+    match execute(args.command, usize::from(args.tab_width)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
         }
-        Commands::Build { filename, output } => {
-            build(filename, output)?;
+    }
+}
+
+fn execute(command: Commands, tab_width: usize) -> Result<(), String> {
+    match command {
+        Commands::Run { options } => {
+            if options.freestanding {
+                return Err("Freestanding programs have no entry point to run".to_owned());
+            }
+            let outputfile = build(options, tab_width)?;
+            if !cfg!(target_os = "linux") {
+                return Err(format!(
+                    "Built {}, but Linux executables can't be run on this host",
+                    outputfile.display()
+                ));
+            }
+
+            // A bare file name would be looked up in PATH instead of the current directory
+            let program = Path::new(".").join(&outputfile);
+            process::Command::new(&program)
+                .status()
+                .map_err(|err| io_error("run", &program, err))?;
+        }
+        Commands::Build { options } => {
+            build(options, tab_width)?;
+        }
+        Commands::Rename {
+            filename,
+            line,
+            col,
+            to,
+        } => {
+            rename(filename, line, col, to, tab_width)?;
+        }
+        Commands::Refs {
+            filename,
+            line,
+            col,
+        } => {
+            refs(filename, line, col, tab_width)?;
+        }
+        Commands::Def {
+            filename,
+            line,
+            col,
+        } => {
+            def(filename, line, col, tab_width)?;
+        }
+        Commands::Doctor => doctor()?,
+        Commands::ReportProfile { filename } => {
+            let data = fs::read(&filename).map_err(|err| io_error("read", &filename, err))?;
+            let counts = profile::parse(&data)?;
+            print!("{}", profile::report(counts));
+        }
+        Commands::Cov { program } => cov(&program, tab_width)?,
+        Commands::Devtool(Devtool::GenStress { stmts, depth, seed }) => {
+            print!("{}", stress::generate(stmts, depth, seed));
         }
     }
 
     Ok(())
 }
 
-fn build(filename: String, output: Option<String>) -> Result<String, String> {
-    let mut outputfile = match output {
-        Some(_) => output.unwrap(),
-        None => {
-            let path = std::path::Path::new(&filename);
-            match path.file_stem() {
-                Some(stem) => stem.to_str().unwrap_or_default().to_owned(),
-                None => return Err(format!("Couldn't get file stem from {}", filename)),
+fn build(options: BuildOptions, tab_width: usize) -> Result<PathBuf, String> {
+    let filename = options.filename;
+    let input = Path::new(&filename);
+    let data = fs::read_to_string(input).map_err(|err| io_error("read", input, err))?;
+
+    let mut outputfile = match options.output {
+        Some(output) => {
+            let output = PathBuf::from(output);
+            if same_file(&output, input) {
+                return Err(format!("Refusing to overwrite the input file {filename}"));
             }
+            output
         }
+        None => match input.file_stem() {
+            Some(stem) => PathBuf::from(stem),
+            None => return Err(format!("Couldn't get file stem from {}", filename)),
+        },
     };
 
-    if outputfile == filename {
-        outputfile = format!("_{}", outputfile);
+    // An input without an extension would be overwritten by its own binary
+    if same_file(&outputfile, input) {
+        let mut name = OsString::from("_");
+        name.push(outputfile.as_os_str());
+        outputfile = PathBuf::from(name);
     }
 
-    let data = fs::read_to_string(&filename).map_err(|err| err.to_string())?;
-    let tokens = lexer::lex(data).map_err(|err| format!("{err:?}"))?;
-    for token in &tokens {
-        println!("{}", token)
+    let asm_output = toolchain::artifact(&outputfile, "asm");
+    let ld_output = toolchain::artifact(&outputfile, "o");
+    for artifact in [&asm_output, &ld_output] {
+        if same_file(artifact, input) {
+            return Err(format!(
+                "Building would overwrite the input file {filename} with {}, pick another output with -o",
+                artifact.display()
+            ));
+        }
+    }
+    if let Some(parent) = outputfile.parent() {
+        if !parent.as_os_str().is_empty() && !parent.is_dir() {
+            return Err(format!(
+                "Output directory {} does not exist",
+                parent.display()
+            ));
+        }
     }
 
-    let ast = parser::parse(tokens).map_err(|err| format!("{err:?}"))?;
-    println!("{}", ast);
+    let compile_options = rlang::Options {
+        filename: filename.clone(),
+        features: features::Features::new(options.edition, options.features),
+        prelude: !options.no_prelude,
+        keep_all_functions: options.keep_all_functions,
+        tab_width,
+    };
+    // The program can be run from any directory, so the counts are written
+    // to an absolute path
+    let instrumentation = if options.instrument_profile {
+        let path = absolute(&toolchain::artifact(&outputfile, "profile"))?;
+        Some(generator::Instrumentation::Profile { path })
+    } else if options.instrument_coverage {
+        let path = absolute(&toolchain::artifact(&outputfile, "coverage"))?;
+        Some(generator::Instrumentation::Coverage {
+            path,
+            statements: vec![],
+        })
+    } else {
+        None
+    };
+    let compiled =
+        rlang::compile_program(&data, &compile_options, &mut PassManager::new(), |env| {
+            env.entry = if options.freestanding {
+                generator::Entry::Freestanding
+            } else {
+                generator::Entry::Symbol(options.entry.clone())
+            };
+            env.instrumentation = instrumentation;
+            env.sanitize = options.sanitize;
+            env.checked = options.checked;
+            env.optimize = options.optimize;
+            env.libc = options.libraries.iter().any(|lib| lib == "c");
+        })?;
+    for warning in &compiled.warnings {
+        eprintln!("warning: {warning}");
+    }
+
+    if options.emit.contains(&Emit::Callgraph) {
+        let path = toolchain::artifact(&outputfile, "dot");
+        fs::write(&path, compiled.graph.to_dot()).map_err(|err| io_error("write", &path, err))?;
+    }
+    if options.emit.contains(&Emit::CallgraphJson) {
+        let path = toolchain::artifact(&outputfile, "json");
+        fs::write(&path, compiled.graph.to_json()).map_err(|err| io_error("write", &path, err))?;
+    }
+    if options.instrument_coverage {
+        let path = toolchain::artifact(&outputfile, "covmap");
+        let source = absolute(input)?;
+        let map = coverage::map(&source, &compiled.counters);
+        fs::write(&path, map).map_err(|err| io_error("write", &path, err))?;
+    }
+
+    fs::write(&asm_output, compiled.asm).map_err(|err| io_error("write", &asm_output, err))?;
+
+    let nasm = toolchain::assembler().ok_or("Couldn't find nasm, is it installed?")?;
+    run_tool(
+        &nasm,
+        &[
+            "-felf64".as_ref(),
+            asm_output.as_os_str(),
+            "-o".as_ref(),
+            ld_output.as_os_str(),
+        ],
+    )?;
+
+    if options.freestanding && options.linker_script.is_none() {
+        return Ok(ld_output);
+    }
+    match toolchain::linker() {
+        Some(ld) => {
+            let mut args = vec![
+                ld_output.clone().into_os_string(),
+                "-o".into(),
+                outputfile.clone().into_os_string(),
+            ];
+            // A freestanding program's entry point is up to the linker script
+            if !options.freestanding {
+                args.push(format!("--entry={}", options.entry).into());
+            }
+            if let Some(script) = &options.linker_script {
+                args.push("-T".into());
+                args.push(script.clone().into_os_string());
+            }
+            if !options.libraries.is_empty() {
+                args.push("-dynamic-linker".into());
+                args.push(toolchain::DYNAMIC_LINKER.into());
+            }
+            args.extend(
+                options
+                    .libraries
+                    .iter()
+                    .map(|lib| format!("-l{lib}").into()),
+            );
+            let args: Vec<&OsStr> = args.iter().map(|arg| arg.as_os_str()).collect();
+            run_tool(&ld, &args)?
+        }
+        // Hosts like Windows rarely have an ELF linker, the object can still
+        // be linked elsewhere
+        None if !cfg!(target_os = "linux") => {
+            eprintln!(
+                "warning: no ELF linker found, skipped linking {}",
+                ld_output.display()
+            );
+            return Ok(ld_output);
+        }
+        None => return Err("Couldn't find ld, is it installed?".to_owned()),
+    }
+
+    Ok(outputfile)
+}
 
-    let mut env = generator::Environment {
-        parent: None,
-        base_stack: 0,
-        variables: HashMap::new(),
-        datatypes: HashMap::from([(String::from("int"), generator::Datatype::Single { size: 4 })]),
+// The program built by doctor, which exits with SMOKE_TEST_EXIT when the
+// generated code works
+const SMOKE_TEST: &str = "fn double(int x) -> int {
+    return x * 2;
+}
+int total = 0;
+for (int i = 0; i < 3; i++) {
+    total += double(i);
+}
+total + 36;
+";
+const SMOKE_TEST_EXIT: i32 = 42;
+
+fn doctor() -> Result<(), String> {
+    let mut failed = 0;
+    let mut report = |ok: bool, message: String| {
+        println!("{} {message}", if ok { "ok:     " } else { "problem:" });
+        if !ok {
+            failed += 1;
+        }
     };
 
-    let code = ast.generate(&mut env).map_err(|err| format!("{err:?}"))?;
-    println!("Variables: {:#?}", env.variables);
-    println!("Datatypes: {:#?}", env.datatypes);
+    report(
+        cfg!(all(target_os = "linux", target_arch = "x86_64")),
+        format!(
+            "host is {}-{}, programs are built for x86_64-linux",
+            std::env::consts::ARCH,
+            std::env::consts::OS
+        ),
+    );
+
+    let nasm = toolchain::assembler();
+    report(
+        nasm.is_some(),
+        match &nasm {
+            Some(nasm) => format!(
+                "nasm at {} ({})",
+                nasm.display(),
+                toolchain::version(nasm, "-v").unwrap_or("unknown version".to_owned())
+            ),
+            None => "nasm not found, install it or add it to PATH".to_owned(),
+        },
+    );
+    let ld = toolchain::linker();
+    report(
+        ld.is_some(),
+        match &ld {
+            Some(ld) => format!(
+                "linker at {} ({})",
+                ld.display(),
+                toolchain::version(ld, "--version").unwrap_or("unknown version".to_owned())
+            ),
+            None => "ld not found, install binutils or lld".to_owned(),
+        },
+    );
+
+    let dir = std::env::temp_dir().join(format!("rlang-doctor-{}", process::id()));
+    let source = dir.join("smoke.rl");
+    let written = fs::create_dir_all(&dir).and_then(|()| fs::write(&source, SMOKE_TEST));
+    let writable = written.is_ok();
+    report(
+        writable,
+        match written {
+            Ok(()) => format!("temp dir {} is writable", dir.display()),
+            Err(err) => io_error("write", &source, err),
+        },
+    );
+
+    if writable {
+        let smoke_test = build(
+            BuildOptions {
+                filename: source.display().to_string(),
+                output: Some(dir.join("smoke").display().to_string()),
+                emit: vec![],
+                keep_all_functions: false,
+                edition: features::Edition::default(),
+                features: vec![],
+                libraries: vec![],
+                entry: callgraph::ENTRY.to_owned(),
+                freestanding: false,
+                linker_script: None,
+                instrument_profile: false,
+                instrument_coverage: false,
+                sanitize: false,
+                checked: false,
+                optimize: 0,
+                no_prelude: false,
+            },
+            source::DEFAULT_TAB_WIDTH,
+        )
+        .and_then(|program| {
+            process::Command::new(&program)
+                .status()
+                .map_err(|err| io_error("run", &program, err))
+        });
+        report(
+            matches!(&smoke_test, Ok(status) if status.code() == Some(SMOKE_TEST_EXIT)),
+            match smoke_test {
+                Ok(status) if status.code() == Some(SMOKE_TEST_EXIT) => {
+                    "smoke test built and ran".to_owned()
+                }
+                Ok(status) => {
+                    format!("smoke test exited with {status}, expected {SMOKE_TEST_EXIT}")
+                }
+                Err(err) => format!("smoke test failed: {err}"),
+            },
+        );
+    }
+    let _ = fs::remove_dir_all(&dir);
+
+    match failed {
+        0 => Ok(()),
+        1 => Err("1 check failed".to_owned()),
+        _ => Err(format!("{failed} checks failed")),
+    }
+}
 
-    let asm_output = format!("{outputfile}.asm");
-    let ld_output = format!("{outputfile}.o");
+fn cov(program: &Path, tab_width: usize) -> Result<(), String> {
+    let path = toolchain::artifact(program, "covmap");
+    let map = fs::read_to_string(&path).map_err(|err| io_error("read", &path, err))?;
+    let (source, statements) = coverage::parse_map(&map)?;
+    let path = toolchain::artifact(program, "coverage");
+    let data = fs::read(&path).map_err(|err| io_error("read", &path, err))?;
+    let (names, counts): (Vec<String>, Vec<u64>) = profile::parse(&data)?.into_iter().unzip();
+    if names != statements.iter().map(usize::to_string).collect::<Vec<_>>() {
+        return Err(format!(
+            "{} is from another build of {}, run the program again",
+            path.display(),
+            program.display()
+        ));
+    }
 
-    let mut file = fs::File::create(&asm_output).expect("Unable to create file");
-    file.write_all(code.as_bytes())
-        .expect("Unable to write to file");
+    let source_path = Path::new(&source);
+    let source =
+        fs::read_to_string(source_path).map_err(|err| io_error("read", source_path, err))?;
+    print!(
+        "{}",
+        coverage::render(&source, &statements, &counts, tab_width)
+    );
+    Ok(())
+}
+
+fn absolute(path: &Path) -> Result<String, String> {
+    let absolute = std::path::absolute(path).map_err(|err| io_error("find", path, err))?;
+    Ok(absolute.display().to_string())
+}
 
-    process::Command::new("nasm")
-        .args(["-felf64", &asm_output])
+// Whether both paths exist and refer to the same file, however they're spelled
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+fn io_error(action: &str, path: &Path, err: std::io::Error) -> String {
+    let path = path.display();
+    match err.kind() {
+        ErrorKind::NotFound => format!("Couldn't {action} {path}: no such file or directory"),
+        ErrorKind::PermissionDenied => format!("Couldn't {action} {path}: permission denied"),
+        _ => format!("Couldn't {action} {path}: {err}"),
+    }
+}
+
+fn run_tool(program: &Path, args: &[&OsStr]) -> Result<(), String> {
+    let status = process::Command::new(program)
+        .args(args)
         .status()
-        .expect("Failed to compile");
+        .map_err(|err| io_error("run", program, err))?;
+    if !status.success() {
+        return Err(format!("{} failed with {status}", program.display()));
+    }
+    Ok(())
+}
+
+fn resolve(source: &str, map: &SourceMap, file: FileId) -> Result<resolver::Resolution, String> {
+    // Lexer errors are ignored, the rest of the file is still worth resolving
+    let (tokens, _) = lexer::lex_file(source.to_owned(), file);
+    let (_, cst) =
+        parser::parse(source, tokens).map_err(|err| rlang::syntax_error(map, file, &err))?;
+    Ok(resolver::resolve(&cst))
+}
 
-    process::Command::new("ld")
-        .args([&ld_output, "-o", &outputfile])
-        .spawn()
-        .expect("Failed to link");
+fn symbol_at(
+    map: &SourceMap,
+    file: FileId,
+    resolution: &resolver::Resolution,
+    line: usize,
+    col: usize,
+) -> Result<usize, String> {
+    let filename = map.name(file);
+    let index = map
+        .index(file, line, col)
+        .ok_or(format!("{filename}:{line}:{col} is outside of the file"))?;
+    resolution
+        .reference_at(index)
+        .and_then(|reference| reference.symbol)
+        .ok_or(format!("No variable or type at {filename}:{line}:{col}"))
+}
 
-    Ok(outputfile)
+fn refs(filename: String, line: usize, col: usize, tab_width: usize) -> Result<(), String> {
+    let data = fs::read_to_string(&filename).map_err(|err| err.to_string())?;
+    let mut map = SourceMap::with_tab_width(tab_width);
+    let file = map.add(&filename, &data);
+    let resolution = resolve(&data, &map, file)?;
+    let symbol = symbol_at(&map, file, &resolution, line, col)?;
+
+    for reference in resolution.references_to(symbol) {
+        println!("{}", map.location(file, reference.start_index));
+    }
+
+    Ok(())
+}
+
+fn def(filename: String, line: usize, col: usize, tab_width: usize) -> Result<(), String> {
+    let data = fs::read_to_string(&filename).map_err(|err| err.to_string())?;
+    let mut map = SourceMap::with_tab_width(tab_width);
+    let file = map.add(&filename, &data);
+    let resolution = resolve(&data, &map, file)?;
+    let symbol = symbol_at(&map, file, &resolution, line, col)?;
+
+    println!(
+        "{}",
+        map.location(file, resolution.symbols[symbol].definition)
+    );
+
+    Ok(())
+}
+
+fn rename(
+    filename: String,
+    line: usize,
+    col: usize,
+    to: String,
+    tab_width: usize,
+) -> Result<(), String> {
+    let (tokens, errors) = lexer::lex(to.clone());
+    if !errors.is_empty()
+        || tokens.len() != 1
+        || tokens[0].token_type != lexer::TokenType::Identifier
+    {
+        return Err(format!("{to} is not a valid identifier"));
+    }
+
+    let data = fs::read_to_string(&filename).map_err(|err| err.to_string())?;
+    let mut map = SourceMap::with_tab_width(tab_width);
+    let file = map.add(&filename, &data);
+    let resolution = resolve(&data, &map, file)?;
+    let symbol = symbol_at(&map, file, &resolution, line, col)?;
+
+    // Edit back to front so the earlier indices stay valid. References are
+    // at character indices, which are further apart in bytes
+    let mut renamed = data.clone();
+    let bytes: Vec<usize> = data
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain([data.len()])
+        .collect();
+    let references: Vec<_> = resolution.references_to(symbol).collect();
+    for reference in references.iter().rev() {
+        renamed.replace_range(
+            bytes[reference.start_index]..bytes[reference.end_index + 1],
+            &to,
+        );
+    }
+
+    // The rename must not change what any name refers to, e.g. by shadowing
+    // another variable or by being shadowed itself
+    let mut renamed_map = SourceMap::with_tab_width(tab_width);
+    let renamed_file = renamed_map.add(&filename, &renamed);
+    let new_resolution = resolve(&renamed, &renamed_map, renamed_file)?;
+    let mut mapping = HashMap::new();
+    let unchanged = resolution.references.len() == new_resolution.references.len()
+        && resolution
+            .references
+            .iter()
+            .zip(&new_resolution.references)
+            .all(|(old, new)| *mapping.entry(old.symbol).or_insert(new.symbol) == new.symbol)
+        && mapping.len() == mapping.values().collect::<HashSet<_>>().len();
+    if !unchanged {
+        return Err(format!(
+            "Renaming {} to {to} would change the meaning of the program",
+            resolution.symbols[symbol].name
+        ));
+    }
+
+    fs::write(&filename, renamed).map_err(|err| err.to_string())?;
+    println!("Renamed {} references", references.len());
+
+    Ok(())
 }
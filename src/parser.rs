@@ -1,17 +1,146 @@
 use crate::ast;
+use crate::cst;
 use crate::lexer;
+use crate::source::FileId;
+use crate::tables::{SideTable, Span};
 
 #[derive(Debug)]
 pub enum ParseError {
     InvalidToken,
+    /// The file ended in the middle of a statement
+    UnexpectedEof,
     ExpectedToken(lexer::TokenType),
+    UnknownAttribute(String),
 }
 
-fn expect(
-    tokens: &mut Vec<lexer::Token>,
-    token_type: lexer::TokenType,
-) -> Result<lexer::Token, ParseError> {
-    let token = tokens.pop().unwrap();
+/// A `ParseError` with where it is: the indices of the first and last
+/// character of the token the parser gave up at. When the file ends too
+/// early, that's the character right after the last token.
+#[derive(Debug)]
+pub struct SyntaxError {
+    pub error: ParseError,
+    pub start_index: usize,
+    pub end_index: usize,
+}
+
+/// The remaining tokens, reversed so we can pop from the end instead of the
+/// beginning which is faster. Every token popped is also recorded in the CST.
+struct Tokens {
+    tokens: Vec<lexer::Token>,
+    cst: cst::Builder,
+    /// The start index of every statement parsed so far
+    statements: Vec<usize>,
+    /// The id the next node gets
+    next_id: usize,
+    /// Where the last token popped ends
+    end: usize,
+    /// The first and last index of the last token popped, if any
+    popped: Option<(usize, usize)>,
+    /// The file the tokens are from
+    file: FileId,
+    spans: SideTable<Span>,
+}
+
+impl Tokens {
+    fn pop(&mut self) -> Option<lexer::Token> {
+        let token = self.tokens.pop()?;
+        self.cst.token(&token);
+        self.end = token.end_index;
+        self.popped = Some((token.start_index, token.end_index));
+        Some(token)
+    }
+
+    // Where the next node starts
+    fn start(&self) -> usize {
+        self.tokens
+            .last()
+            .map_or(self.end, |token| token.start_index)
+    }
+
+    // The id of a node that started at `start` and ends with the last token
+    // popped. Nodes are only given one once all their tokens are popped
+    fn id(&mut self, start: usize) -> ast::NodeId {
+        let id = ast::NodeId(self.next_id);
+        self.next_id += 1;
+        let span = Span {
+            file: self.file,
+            start,
+            end: self.end,
+        };
+        self.spans.insert(id, span);
+        id
+    }
+
+    fn last(&self) -> Option<&lexer::Token> {
+        self.tokens.last()
+    }
+
+    // Like `pop`, for when the statement isn't over yet
+    fn next(&mut self) -> Result<lexer::Token, ParseError> {
+        self.pop().ok_or(ParseError::UnexpectedEof)
+    }
+
+    // Like `last`, for when the statement isn't over yet
+    fn current(&self) -> Result<&lexer::Token, ParseError> {
+        self.last().ok_or(ParseError::UnexpectedEof)
+    }
+
+    // The token `n` tokens after the next one
+    fn peek(&self, n: usize) -> Option<&lexer::Token> {
+        self.tokens.iter().rev().nth(n)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    // Leading whitespace belongs to the parent, not to the node about to start
+    fn skip_trivia(&mut self) {
+        if let Some(token) = self.tokens.last() {
+            let start = token.start_index;
+            self.cst.trivia(start);
+        }
+    }
+
+    fn start_node(&mut self, kind: cst::NodeKind) {
+        self.skip_trivia();
+        self.cst.start_node(kind);
+    }
+
+    fn checkpoint(&mut self) -> cst::Checkpoint {
+        self.skip_trivia();
+        self.cst.checkpoint()
+    }
+
+    fn start_node_at(&mut self, checkpoint: cst::Checkpoint, kind: cst::NodeKind) {
+        self.cst.start_node_at(checkpoint, kind);
+    }
+
+    fn finish_node(&mut self) {
+        self.cst.finish_node();
+    }
+
+    // Where `error` is. Most errors are about the token that was just
+    // popped, the end of the file is right after it
+    fn error(&self, error: ParseError) -> SyntaxError {
+        let (start_index, end_index) = match (&error, self.popped) {
+            (ParseError::UnexpectedEof, Some((_, end))) => (end + 1, end + 1),
+            (_, Some(popped)) => popped,
+            (_, None) => self
+                .tokens
+                .last()
+                .map_or((0, 0), |token| (token.start_index, token.end_index)),
+        };
+        SyntaxError {
+            error,
+            start_index,
+            end_index,
+        }
+    }
+}
+
+fn expect(tokens: &mut Tokens, token_type: lexer::TokenType) -> Result<lexer::Token, ParseError> {
+    let token = tokens.next()?;
     if token.token_type != token_type {
         Err(ParseError::ExpectedToken(token_type))
     } else {
@@ -19,28 +148,108 @@ fn expect(
     }
 }
 
-pub fn parse(mut tokens: Vec<lexer::Token>) -> Result<ast::Node, ParseError> {
-    // Reversing so we can pop from the end instead of the beginning which is faster
+/// Everything the parser finds out about a source file.
+pub struct Parsed {
+    pub ast: ast::Node,
+    /// The lossless syntax tree of the source
+    pub cst: cst::SyntaxNode,
+    /// Where every statement starts in the order they were parsed, which is
+    /// the order the generator visits them in
+    pub statements: Vec<usize>,
+    /// The source of every node
+    pub spans: SideTable<Span>,
+}
+
+/// Parses the tokens into an AST, building the lossless CST of `source` alongside it.
+pub fn parse(
+    source: &str,
+    tokens: Vec<lexer::Token>,
+) -> Result<(ast::Node, cst::SyntaxNode), SyntaxError> {
+    let parsed = parse_program(source, tokens, ast::NodeId(0))?;
+    Ok((parsed.ast, parsed.cst))
+}
+
+/// Like `parse`, but also returns where every statement starts in the order
+/// they were parsed, which is the order the generator visits them in.
+pub fn parse_statements(
+    source: &str,
+    tokens: Vec<lexer::Token>,
+) -> Result<(ast::Node, cst::SyntaxNode, Vec<usize>), SyntaxError> {
+    let parsed = parse_program(source, tokens, ast::NodeId(0))?;
+    Ok((parsed.ast, parsed.cst, parsed.statements))
+}
+
+/// Parses a whole file, numbering its nodes from `first` so they don't
+/// share ids with the nodes of a program they're added to.
+pub fn parse_program(
+    source: &str,
+    mut tokens: Vec<lexer::Token>,
+    first: ast::NodeId,
+) -> Result<Parsed, SyntaxError> {
     tokens.reverse();
+    let file = tokens.first().map_or(FileId::default(), |token| token.file);
+    let mut tokens = Tokens {
+        tokens,
+        cst: cst::Builder::new(source),
+        statements: vec![],
+        next_id: first.0,
+        end: 0,
+        popped: None,
+        file,
+        spans: SideTable::new(),
+    };
 
     let mut body = vec![];
     loop {
-        let ast = parse_stmt(&mut tokens)?;
+        let ast = parse_stmt(&mut tokens).map_err(|error| tokens.error(error))?;
+        crate::trace!("parser", "statement: {ast}");
         body.push(ast);
-        if tokens.len() == 0 {
+        if tokens.is_empty() {
             break;
         }
     }
 
-    Ok(ast::Node::Program { body })
+    let id = tokens.id(0);
+    let cst = tokens.cst.finish();
+    crate::debug!("parser", "syntax tree:\n{cst:?}");
+    Ok(Parsed {
+        ast: ast::Node::Program { body, id },
+        cst,
+        statements: tokens.statements,
+        spans: tokens.spans,
+    })
 }
 
-fn parse_stmt(tokens: &mut Vec<lexer::Token>) -> Result<ast::Node, ParseError> {
-    let ast: ast::Node = match tokens.last().unwrap().token_type {
-        lexer::TokenType::Identifier => parse_var_decl(tokens)?,
+fn parse_stmt(tokens: &mut Tokens) -> Result<ast::Node, ParseError> {
+    let start = tokens.current()?.start_index;
+    tokens.statements.push(start);
+    let ast: ast::Node = match tokens.current()?.token_type {
+        lexer::TokenType::Identifier
+            if tokens.peek(1).map(|token| &token.token_type) == Some(&lexer::TokenType::Colon) =>
+        {
+            return parse_label(tokens)
+        }
+        lexer::TokenType::Identifier if is_declaration(tokens) => parse_var_decl(tokens)?,
         lexer::TokenType::OpenBrace => return parse_scope(tokens),
+        lexer::TokenType::If => return parse_if(tokens),
+        lexer::TokenType::While => return parse_while(tokens),
+        lexer::TokenType::For => return parse_for(tokens),
+        lexer::TokenType::Switch => return parse_switch(tokens),
+        lexer::TokenType::Fn | lexer::TokenType::At => return parse_fn(tokens),
+        lexer::TokenType::Do => parse_do_while(tokens)?,
+        lexer::TokenType::Goto => parse_goto(tokens)?,
+        lexer::TokenType::Return => parse_return(tokens)?,
+        lexer::TokenType::Extern => parse_extern(tokens)?,
+        lexer::TokenType::Break => {
+            parse_jump(tokens, cst::NodeKind::Break, |id| ast::Node::Break { id })?
+        }
+        lexer::TokenType::Continue => parse_jump(tokens, cst::NodeKind::Continue, |id| {
+            ast::Node::Continue { id }
+        })?,
         lexer::TokenType::TypeDef => parse_typedef(tokens)?,
         lexer::TokenType::Struct => parse_type(tokens)?,
+        lexer::TokenType::Enum => parse_enum(tokens)?,
+        lexer::TokenType::Union => parse_union(tokens)?,
         _ => parse_expr(tokens)?,
     };
 
@@ -49,143 +258,986 @@ fn parse_stmt(tokens: &mut Vec<lexer::Token>) -> Result<ast::Node, ParseError> {
     Ok(ast)
 }
 
-fn parse_expr(tokens: &mut Vec<lexer::Token>) -> Result<ast::Node, ParseError> {
+// Declarations start with a type name followed by the variable's name. With
+// a pointer type, `int* p = &x` could also be a multiplication, but that
+// isn't an l-value so it can't be followed by `=`
+fn is_declaration(tokens: &Tokens) -> bool {
+    let mut n = 1;
+    while tokens.peek(n).map(|token| &token.token_type) == Some(&lexer::TokenType::Star) {
+        n += 1;
+    }
+    if tokens.peek(n).map(|token| &token.token_type) != Some(&lexer::TokenType::Identifier) {
+        return false;
+    }
+    n == 1
+        || matches!(
+            tokens.peek(n + 1).map(|token| &token.token_type),
+            Some(lexer::TokenType::Equals | lexer::TokenType::OpenBracket)
+        )
+}
+
+fn parse_expr(tokens: &mut Tokens) -> Result<ast::Node, ParseError> {
+    // example: x = y += 3
+    let start = tokens.start();
+    let checkpoint = tokens.checkpoint();
+    let target = parse_ternary(tokens)?;
+    let op = match tokens.last().map(|token| &token.token_type) {
+        Some(lexer::TokenType::Equals) => None,
+        Some(token_type) => match compound_operator(token_type) {
+            Some(op) => Some(op),
+            None => return Ok(target),
+        },
+        None => return Ok(target),
+    };
+
+    // Assignments group to the right and evaluate to the assigned value
+    tokens.start_node_at(checkpoint, cst::NodeKind::Assign);
+    tokens.pop();
+    let mut value = parse_expr(tokens)?;
+    tokens.finish_node();
+
+    // `x += 1` is sugar for `x = x + 1`. Both copies of `x` are the same
+    // source, so they keep the same ids
+    if let Some(op) = op {
+        value = ast::Node::BinOp {
+            left: Box::new(target.clone()),
+            right: Box::new(value),
+            op,
+            id: tokens.id(start),
+        };
+    }
+
+    Ok(ast::Node::Assign {
+        target: Box::new(target),
+        value: Box::new(value),
+        id: tokens.id(start),
+    })
+}
+
+// The operator a compound assignment like `+=` applies before assigning
+fn compound_operator(token_type: &lexer::TokenType) -> Option<ast::Operator> {
+    match token_type {
+        lexer::TokenType::AddEquals => Some(ast::Operator::Add),
+        lexer::TokenType::SubEquals => Some(ast::Operator::Sub),
+        lexer::TokenType::StarEquals => Some(ast::Operator::Mul),
+        lexer::TokenType::SlashEquals => Some(ast::Operator::Div),
+        lexer::TokenType::PercentEquals => Some(ast::Operator::Mod),
+        lexer::TokenType::AmpersandEquals => Some(ast::Operator::BitAnd),
+        lexer::TokenType::PipeEquals => Some(ast::Operator::BitOr),
+        lexer::TokenType::CaretEquals => Some(ast::Operator::BitXor),
+        lexer::TokenType::ShiftLeftEquals => Some(ast::Operator::ShiftLeft),
+        lexer::TokenType::ShiftRightEquals => Some(ast::Operator::ShiftRight),
+        _ => None,
+    }
+}
+
+fn parse_ternary(tokens: &mut Tokens) -> Result<ast::Node, ParseError> {
+    // example: x > 0 ? x : -x
+    let start = tokens.start();
+    let checkpoint = tokens.checkpoint();
+    let cond = parse_binary(tokens, 0)?;
+    if tokens.last().map(|token| &token.token_type) != Some(&lexer::TokenType::Question) {
+        return Ok(cond);
+    }
+
+    // Binds looser than every binary operator and groups to the right, so
+    // `a ? b : c ? d : e` is `a ? b : (c ? d : e)`
+    tokens.start_node_at(checkpoint, cst::NodeKind::Ternary);
+    tokens.pop();
+    let then = parse_expr(tokens)?;
+    expect(tokens, lexer::TokenType::Colon)?;
+    let else_ = parse_ternary(tokens)?;
+    tokens.finish_node();
+
+    Ok(ast::Node::Ternary {
+        cond: Box::new(cond),
+        then: Box::new(then),
+        else_: Box::new(else_),
+        id: tokens.id(start),
+    })
+}
+
+fn binary_operator(token_type: &lexer::TokenType) -> Option<ast::Operator> {
+    match token_type {
+        lexer::TokenType::Add => Some(ast::Operator::Add),
+        lexer::TokenType::Sub => Some(ast::Operator::Sub),
+        lexer::TokenType::Star => Some(ast::Operator::Mul),
+        lexer::TokenType::Slash => Some(ast::Operator::Div),
+        lexer::TokenType::Percent => Some(ast::Operator::Mod),
+        lexer::TokenType::Ampersand => Some(ast::Operator::BitAnd),
+        lexer::TokenType::Pipe => Some(ast::Operator::BitOr),
+        lexer::TokenType::Caret => Some(ast::Operator::BitXor),
+        lexer::TokenType::ShiftLeft => Some(ast::Operator::ShiftLeft),
+        lexer::TokenType::ShiftRight => Some(ast::Operator::ShiftRight),
+        lexer::TokenType::EqualsEquals => Some(ast::Operator::Equal),
+        lexer::TokenType::BangEquals => Some(ast::Operator::NotEqual),
+        lexer::TokenType::Less => Some(ast::Operator::Less),
+        lexer::TokenType::LessEquals => Some(ast::Operator::LessEqual),
+        lexer::TokenType::Greater => Some(ast::Operator::Greater),
+        lexer::TokenType::GreaterEquals => Some(ast::Operator::GreaterEqual),
+        lexer::TokenType::AmpersandAmpersand => Some(ast::Operator::LogicalAnd),
+        lexer::TokenType::PipePipe => Some(ast::Operator::LogicalOr),
+        _ => None,
+    }
+}
+
+// Precedence climbing: only operators binding at least as tight as
+// `min_precedence` are consumed, which keeps every operator left associative
+fn parse_binary(tokens: &mut Tokens, min_precedence: u8) -> Result<ast::Node, ParseError> {
+    let start = tokens.start();
+    let checkpoint = tokens.checkpoint();
     let mut left = parse_primary(tokens)?;
-    while tokens.len() > 0 && tokens.last().unwrap().token_type == lexer::TokenType::Add {
-        tokens.pop().unwrap();
-        let right = parse_primary(tokens)?;
+    while let Some(op) = tokens
+        .last()
+        .and_then(|token| binary_operator(&token.token_type))
+    {
+        let precedence = op.precedence();
+        if precedence < min_precedence {
+            break;
+        }
+
+        tokens.start_node_at(checkpoint, cst::NodeKind::BinOp);
+        tokens.next()?;
+        let right = parse_binary(tokens, precedence + 1)?;
+        tokens.finish_node();
         left = ast::Node::BinOp {
             left: Box::new(left),
             right: Box::new(right),
-            op: ast::Operator::Add,
+            op,
+            id: tokens.id(start),
         };
     }
 
     Ok(left)
 }
 
-fn parse_var_decl(tokens: &mut Vec<lexer::Token>) -> Result<ast::Node, ParseError> {
-    let var_type = &tokens.pop().unwrap().value;
+fn parse_var_decl(tokens: &mut Tokens) -> Result<ast::Node, ParseError> {
+    let start = tokens.start();
+    tokens.start_node(cst::NodeKind::VarDecl);
+    let var_type = parse_datatype(tokens)?;
 
     let var_name = expect(tokens, lexer::TokenType::Identifier)?.value;
 
+    // example: int grid[8][4]
+    let mut lens = vec![];
+    while tokens.current()?.token_type == lexer::TokenType::OpenBracket {
+        tokens.pop();
+        let len = expect(tokens, lexer::TokenType::Integer)?.value;
+        expect(tokens, lexer::TokenType::CloseBracket)?;
+        lens.push(len.parse().map_err(|_| ParseError::InvalidToken)?);
+    }
+    if !lens.is_empty() {
+        tokens.finish_node();
+        return Ok(ast::Node::ArrayDecl {
+            datatype: var_type.clone(),
+            name: var_name,
+            lens,
+            id: tokens.id(start),
+        });
+    }
+
     expect(tokens, lexer::TokenType::Equals)?;
 
     let ast = parse_expr(tokens)?;
+    tokens.finish_node();
 
     Ok(ast::Node::VarDecl {
-        datatype: var_type.to_string(),
+        datatype: var_type,
         name: var_name,
         value: Box::new(ast),
+        id: tokens.id(start),
     })
 }
 
-fn parse_scope(tokens: &mut Vec<lexer::Token>) -> Result<ast::Node, ParseError> {
-    if tokens.last().unwrap().token_type != lexer::TokenType::OpenBrace {
+fn parse_scope(tokens: &mut Tokens) -> Result<ast::Node, ParseError> {
+    let start = tokens.start();
+    if tokens.current()?.token_type != lexer::TokenType::OpenBrace {
         return Err(ParseError::InvalidToken);
     }
-    tokens.pop().unwrap();
+    tokens.start_node(cst::NodeKind::Scope);
+    tokens.next()?;
 
     let mut body = vec![];
-    loop {
-        let ast = parse_stmt(tokens).unwrap();
-        body.push(ast);
-        if tokens.last().unwrap().token_type == lexer::TokenType::CloseBrace {
-            tokens.pop().unwrap();
-            break;
+    while tokens.current()?.token_type != lexer::TokenType::CloseBrace {
+        body.push(parse_stmt(tokens)?);
+    }
+    tokens.next()?;
+    tokens.finish_node();
+
+    Ok(ast::Node::Scope {
+        body,
+        id: tokens.id(start),
+    })
+}
+
+fn parse_if(tokens: &mut Tokens) -> Result<ast::Node, ParseError> {
+    // example: if (x == 1) { ... } else if (x == 2) { ... } else { ... }
+    let start = tokens.start();
+    tokens.start_node(cst::NodeKind::If);
+    expect(tokens, lexer::TokenType::If)?;
+    expect(tokens, lexer::TokenType::OpenParen)?;
+    let cond = parse_expr(tokens)?;
+    expect(tokens, lexer::TokenType::CloseParen)?;
+
+    let then = parse_stmt(tokens)?;
+    let else_ = match tokens.last() {
+        Some(token) if token.token_type == lexer::TokenType::Else => {
+            tokens.next()?;
+            Some(Box::new(parse_stmt(tokens)?))
         }
+        _ => None,
+    };
+    tokens.finish_node();
+
+    Ok(ast::Node::If {
+        cond: Box::new(cond),
+        then: Box::new(then),
+        else_,
+        id: tokens.id(start),
+    })
+}
+
+fn parse_while(tokens: &mut Tokens) -> Result<ast::Node, ParseError> {
+    // example: while (x < 10) { ... }
+    let start = tokens.start();
+    tokens.start_node(cst::NodeKind::While);
+    expect(tokens, lexer::TokenType::While)?;
+    expect(tokens, lexer::TokenType::OpenParen)?;
+    let cond = parse_expr(tokens)?;
+    expect(tokens, lexer::TokenType::CloseParen)?;
+    let body = parse_stmt(tokens)?;
+    tokens.finish_node();
+
+    Ok(ast::Node::While {
+        cond: Box::new(cond),
+        body: Box::new(body),
+        id: tokens.id(start),
+    })
+}
+
+fn parse_for(tokens: &mut Tokens) -> Result<ast::Node, ParseError> {
+    // example: for (int i = 0; i < 10; i + 1) { ... }
+    let start = tokens.start();
+    tokens.start_node(cst::NodeKind::For);
+    expect(tokens, lexer::TokenType::For)?;
+    expect(tokens, lexer::TokenType::OpenParen)?;
+
+    // Every part is optional, for (;;) loops forever
+    let init = if tokens.current()?.token_type == lexer::TokenType::Semicolon {
+        tokens.next()?;
+        None
+    } else {
+        Some(Box::new(parse_stmt(tokens)?))
+    };
+    let cond = if tokens.current()?.token_type == lexer::TokenType::Semicolon {
+        None
+    } else {
+        Some(Box::new(parse_expr(tokens)?))
+    };
+    expect(tokens, lexer::TokenType::Semicolon)?;
+    let step = if tokens.current()?.token_type == lexer::TokenType::CloseParen {
+        None
+    } else {
+        Some(Box::new(parse_expr(tokens)?))
+    };
+    expect(tokens, lexer::TokenType::CloseParen)?;
+
+    let body = parse_stmt(tokens)?;
+    tokens.finish_node();
+
+    Ok(ast::Node::For {
+        init,
+        cond,
+        step,
+        body: Box::new(body),
+        id: tokens.id(start),
+    })
+}
+
+fn parse_do_while(tokens: &mut Tokens) -> Result<ast::Node, ParseError> {
+    // example: do { ... } while (x < 10);
+    let start = tokens.start();
+    tokens.start_node(cst::NodeKind::DoWhile);
+    expect(tokens, lexer::TokenType::Do)?;
+    let body = parse_stmt(tokens)?;
+    expect(tokens, lexer::TokenType::While)?;
+    expect(tokens, lexer::TokenType::OpenParen)?;
+    let cond = parse_expr(tokens)?;
+    expect(tokens, lexer::TokenType::CloseParen)?;
+    tokens.finish_node();
+
+    Ok(ast::Node::DoWhile {
+        body: Box::new(body),
+        cond: Box::new(cond),
+        id: tokens.id(start),
+    })
+}
+
+fn parse_switch(tokens: &mut Tokens) -> Result<ast::Node, ParseError> {
+    // example: switch (x) { case 1: ... break; default: ... }
+    let start = tokens.start();
+    tokens.start_node(cst::NodeKind::Switch);
+    expect(tokens, lexer::TokenType::Switch)?;
+    expect(tokens, lexer::TokenType::OpenParen)?;
+    let value = parse_expr(tokens)?;
+    expect(tokens, lexer::TokenType::CloseParen)?;
+    expect(tokens, lexer::TokenType::OpenBrace)?;
+
+    let mut cases = vec![];
+    while tokens.current()?.token_type != lexer::TokenType::CloseBrace {
+        tokens.start_node(cst::NodeKind::Case);
+        let case = match tokens.next()?.token_type {
+            lexer::TokenType::Case => Some(parse_case_value(tokens)?),
+            lexer::TokenType::Default => None,
+            _ => return Err(ParseError::InvalidToken),
+        };
+        expect(tokens, lexer::TokenType::Colon)?;
+
+        let mut body = vec![];
+        while !matches!(
+            tokens.current()?.token_type,
+            lexer::TokenType::Case | lexer::TokenType::Default | lexer::TokenType::CloseBrace
+        ) {
+            body.push(parse_stmt(tokens)?);
+        }
+        tokens.finish_node();
+        cases.push((case, body));
+    }
+    expect(tokens, lexer::TokenType::CloseBrace)?;
+    tokens.finish_node();
+
+    Ok(ast::Node::Switch {
+        value: Box::new(value),
+        cases,
+        id: tokens.id(start),
+    })
+}
+
+// Case labels have to be known at compile time, so only (negated) integer
+// literals are allowed
+fn parse_case_value(tokens: &mut Tokens) -> Result<i32, ParseError> {
+    tokens.start_node(cst::NodeKind::Literal);
+    let negative = tokens.current()?.token_type == lexer::TokenType::Sub;
+    if negative {
+        tokens.pop();
     }
+    let token = expect(tokens, lexer::TokenType::Integer)?;
+    tokens.finish_node();
+
+    let value = token
+        .value
+        .parse::<i32>()
+        .map_err(|_| ParseError::InvalidToken)?;
+    Ok(if negative { -value } else { value })
+}
+
+fn parse_label(tokens: &mut Tokens) -> Result<ast::Node, ParseError> {
+    // example: retry:
+    let start = tokens.start();
+    tokens.start_node(cst::NodeKind::Label);
+    let name = expect(tokens, lexer::TokenType::Identifier)?.value;
+    expect(tokens, lexer::TokenType::Colon)?;
+    tokens.finish_node();
 
-    Ok(ast::Node::Scope { body })
+    Ok(ast::Node::Label {
+        name,
+        id: tokens.id(start),
+    })
 }
 
-fn parse_typedef(tokens: &mut Vec<lexer::Token>) -> Result<ast::Node, ParseError> {
+fn parse_goto(tokens: &mut Tokens) -> Result<ast::Node, ParseError> {
+    // example: goto retry
+    let start = tokens.start();
+    tokens.start_node(cst::NodeKind::Goto);
+    expect(tokens, lexer::TokenType::Goto)?;
+    let name = expect(tokens, lexer::TokenType::Identifier)?.value;
+    tokens.finish_node();
+
+    Ok(ast::Node::Goto {
+        name,
+        id: tokens.id(start),
+    })
+}
+
+fn parse_extern(tokens: &mut Tokens) -> Result<ast::Node, ParseError> {
+    // example: extern fn printf(int format, ...) -> int
+    let start = tokens.start();
+    tokens.start_node(cst::NodeKind::Extern);
+    expect(tokens, lexer::TokenType::Extern)?;
+    expect(tokens, lexer::TokenType::Fn)?;
+    let name = expect(tokens, lexer::TokenType::Identifier)?.value;
+    let (params, variadic) = parse_params(tokens)?;
+    let return_type = parse_return_type(tokens)?;
+    tokens.finish_node();
+
+    Ok(ast::Node::Extern {
+        name,
+        params,
+        variadic,
+        return_type,
+        id: tokens.id(start),
+    })
+}
+
+// The parameter list of a function, and whether it ends in `...`
+fn parse_params(tokens: &mut Tokens) -> Result<(Vec<(String, String)>, bool), ParseError> {
+    expect(tokens, lexer::TokenType::OpenParen)?;
+    let mut params = vec![];
+    let mut variadic = false;
+    if tokens.current()?.token_type != lexer::TokenType::CloseParen {
+        loop {
+            if tokens.current()?.token_type == lexer::TokenType::Ellipsis {
+                tokens.pop();
+                variadic = true;
+                break;
+            }
+            tokens.start_node(cst::NodeKind::Param);
+            let datatype = parse_datatype(tokens)?;
+            let name = expect(tokens, lexer::TokenType::Identifier)?.value;
+            tokens.finish_node();
+            params.push((datatype, name));
+            if tokens.current()?.token_type != lexer::TokenType::Comma {
+                break;
+            }
+            tokens.pop();
+        }
+    }
+    expect(tokens, lexer::TokenType::CloseParen)?;
+    Ok((params, variadic))
+}
+
+fn parse_return_type(tokens: &mut Tokens) -> Result<Option<String>, ParseError> {
+    if tokens.current()?.token_type != lexer::TokenType::Arrow {
+        return Ok(None);
+    }
+    tokens.pop();
+    Ok(Some(parse_datatype(tokens)?))
+}
+
+// A type name with a `*` for every level of pointer, e.g. `int**`
+fn parse_datatype(tokens: &mut Tokens) -> Result<String, ParseError> {
+    let mut datatype = expect(tokens, lexer::TokenType::Identifier)?.value;
+    while tokens.current()?.token_type == lexer::TokenType::Star {
+        tokens.pop();
+        datatype.push('*');
+    }
+    Ok(datatype)
+}
+
+fn parse_return(tokens: &mut Tokens) -> Result<ast::Node, ParseError> {
+    // example: return a + b
+    let start = tokens.start();
+    tokens.start_node(cst::NodeKind::Return);
+    expect(tokens, lexer::TokenType::Return)?;
+    let value = if tokens.current()?.token_type == lexer::TokenType::Semicolon {
+        None
+    } else {
+        Some(Box::new(parse_expr(tokens)?))
+    };
+    tokens.finish_node();
+
+    Ok(ast::Node::Return {
+        value,
+        id: tokens.id(start),
+    })
+}
+
+// A statement that's nothing but its keyword, like `break`
+fn parse_jump(
+    tokens: &mut Tokens,
+    kind: cst::NodeKind,
+    node: fn(ast::NodeId) -> ast::Node,
+) -> Result<ast::Node, ParseError> {
+    let start = tokens.start();
+    tokens.start_node(kind);
+    tokens.pop();
+    tokens.finish_node();
+    Ok(node(tokens.id(start)))
+}
+
+fn parse_fn(tokens: &mut Tokens) -> Result<ast::Node, ParseError> {
+    // example: fn add(int a, int b) -> int { a + b; }
+    let start = tokens.start();
+    tokens.start_node(cst::NodeKind::FnDecl);
+    let mut section = None;
+    while tokens.current()?.token_type == lexer::TokenType::At {
+        // example: @section(".text.boot")
+        tokens.start_node(cst::NodeKind::Attribute);
+        tokens.pop();
+        let attribute = expect(tokens, lexer::TokenType::Identifier)?.value;
+        if attribute != "section" {
+            return Err(ParseError::UnknownAttribute(attribute));
+        }
+        expect(tokens, lexer::TokenType::OpenParen)?;
+        let name = expect(tokens, lexer::TokenType::String)?.value;
+        section = Some(name.trim_matches('"').to_owned());
+        expect(tokens, lexer::TokenType::CloseParen)?;
+        tokens.finish_node();
+    }
+    expect(tokens, lexer::TokenType::Fn)?;
+    let name = expect(tokens, lexer::TokenType::Identifier)?.value;
+    let (params, variadic) = parse_params(tokens)?;
+    if variadic {
+        return Err(ParseError::InvalidToken);
+    }
+    let return_type = parse_return_type(tokens)?;
+
+    let body = parse_scope(tokens)?;
+    tokens.finish_node();
+
+    Ok(ast::Node::FnDecl {
+        name,
+        params,
+        return_type,
+        section,
+        body: Box::new(body),
+        id: tokens.id(start),
+    })
+}
+
+fn parse_typedef(tokens: &mut Tokens) -> Result<ast::Node, ParseError> {
     // example: typedef struct { int x; int y; } vec2_t
+    let start = tokens.start();
+    tokens.start_node(cst::NodeKind::TypeDef);
     expect(tokens, lexer::TokenType::TypeDef)?;
 
     let ast = parse_type(tokens)?;
     let name = expect(tokens, lexer::TokenType::Identifier)?.value;
+    tokens.finish_node();
 
     Ok(ast::Node::TypeDef {
         name,
         value: Box::new(ast),
+        id: tokens.id(start),
+    })
+}
+
+fn parse_enum(tokens: &mut Tokens) -> Result<ast::Node, ParseError> {
+    // example: enum color { red, green = 4, blue }
+    let start = tokens.start();
+    tokens.start_node(cst::NodeKind::EnumDecl);
+    expect(tokens, lexer::TokenType::Enum)?;
+    let name = expect(tokens, lexer::TokenType::Identifier)?.value;
+    expect(tokens, lexer::TokenType::OpenBrace)?;
+
+    let mut members = vec![];
+    // The last member can be followed by a comma too
+    while tokens.current()?.token_type != lexer::TokenType::CloseBrace {
+        let member = expect(tokens, lexer::TokenType::Identifier)?.value;
+        let value = if tokens.current()?.token_type == lexer::TokenType::Equals {
+            tokens.pop();
+            Some(parse_enum_value(tokens)?)
+        } else {
+            None
+        };
+        members.push((member, value));
+        if tokens.current()?.token_type != lexer::TokenType::Comma {
+            break;
+        }
+        tokens.pop();
+    }
+    expect(tokens, lexer::TokenType::CloseBrace)?;
+    tokens.finish_node();
+
+    Ok(ast::Node::EnumDecl {
+        name,
+        members,
+        id: tokens.id(start),
+    })
+}
+
+fn parse_union(tokens: &mut Tokens) -> Result<ast::Node, ParseError> {
+    // example: union number { int i; float f; }
+    let start = tokens.start();
+    tokens.start_node(cst::NodeKind::UnionDecl);
+    expect(tokens, lexer::TokenType::Union)?;
+    let name = expect(tokens, lexer::TokenType::Identifier)?.value;
+    expect(tokens, lexer::TokenType::OpenBrace)?;
+    let properties = parse_properties(tokens)?;
+    expect(tokens, lexer::TokenType::CloseBrace)?;
+    tokens.finish_node();
+
+    Ok(ast::Node::UnionDecl {
+        name,
+        properties,
+        id: tokens.id(start),
     })
 }
 
-fn parse_type(tokens: &mut Vec<lexer::Token>) -> Result<ast::Node, ParseError> {
-    let ast = match tokens.pop().unwrap().token_type {
+// Like case labels, member values are (negated) integer literals. They're
+// kept wide so the generator can tell the user when one doesn't fit an int
+fn parse_enum_value(tokens: &mut Tokens) -> Result<i64, ParseError> {
+    tokens.start_node(cst::NodeKind::Literal);
+    let negative = tokens.current()?.token_type == lexer::TokenType::Sub;
+    if negative {
+        tokens.pop();
+    }
+    let token = expect(tokens, lexer::TokenType::Integer)?;
+    tokens.finish_node();
+
+    let value = token
+        .value
+        .parse::<i64>()
+        .map_err(|_| ParseError::InvalidToken)?;
+    Ok(if negative { -value } else { value })
+}
+
+fn parse_properties(tokens: &mut Tokens) -> Result<Vec<ast::Property>, ParseError> {
+    let mut properties = vec![];
+    loop {
+        // example: int flags : 3 = 0;
+        tokens.start_node(cst::NodeKind::Property);
+        let datatype = parse_datatype(tokens)?;
+        let name = expect(tokens, lexer::TokenType::Identifier)?.value;
+        let bits = if tokens.current()?.token_type == lexer::TokenType::Colon {
+            tokens.pop();
+            let bits = expect(tokens, lexer::TokenType::Integer)?.value;
+            Some(bits.parse().map_err(|_| ParseError::InvalidToken)?)
+        } else {
+            None
+        };
+        let default = if tokens.current()?.token_type == lexer::TokenType::Equals {
+            tokens.pop();
+            Some(parse_ternary(tokens)?)
+        } else {
+            None
+        };
+        expect(tokens, lexer::TokenType::Semicolon)?;
+        tokens.finish_node();
+        properties.push(ast::Property {
+            datatype,
+            name,
+            bits,
+            default,
+        });
+        if tokens.current()?.token_type == lexer::TokenType::CloseBrace {
+            break;
+        }
+    }
+
+    Ok(properties)
+}
+
+fn parse_type(tokens: &mut Tokens) -> Result<ast::Node, ParseError> {
+    let start = tokens.start();
+    let checkpoint = tokens.checkpoint();
+    let ast = match tokens.next()?.token_type {
         lexer::TokenType::Struct => {
-            let ast: ast::Node = match tokens.last().unwrap().token_type {
+            // example: struct packed header { int magic; bool flag; }
+            let packed = tokens.current()?.token_type == lexer::TokenType::Packed;
+            if packed {
+                tokens.pop();
+            }
+            let ast: ast::Node = match tokens.current()?.token_type {
                 lexer::TokenType::OpenBrace => {
                     // example: struct { int x; int y; }
+                    tokens.start_node_at(checkpoint, cst::NodeKind::StructType);
                     expect(tokens, lexer::TokenType::OpenBrace)?;
 
-                    let mut properties = vec![];
-                    loop {
-                        let datatype = expect(tokens, lexer::TokenType::Identifier)?.value;
-                        let name = expect(tokens, lexer::TokenType::Identifier)?.value;
-                        expect(tokens, lexer::TokenType::Semicolon)?;
-                        properties.push((datatype, name));
-                        if tokens.last().unwrap().token_type == lexer::TokenType::CloseBrace {
-                            break;
-                        }
-                    }
+                    let properties = parse_properties(tokens)?;
+                    expect(tokens, lexer::TokenType::CloseBrace)?;
 
-                    ast::Node::StructType { properties }
+                    ast::Node::StructType {
+                        properties,
+                        packed,
+                        id: tokens.id(start),
+                    }
                 }
                 lexer::TokenType::Identifier => {
                     // example: struct vec2 { int x; int y; }
+                    tokens.start_node_at(checkpoint, cst::NodeKind::StructDecl);
                     let name = expect(tokens, lexer::TokenType::Identifier)?.value;
                     expect(tokens, lexer::TokenType::OpenBrace)?;
 
-                    let mut properties = vec![];
-                    loop {
-                        let datatype = expect(tokens, lexer::TokenType::Identifier)?.value;
-                        let name = expect(tokens, lexer::TokenType::Identifier)?.value;
-                        expect(tokens, lexer::TokenType::Semicolon)?;
-                        properties.push((datatype, name));
-                        if tokens.last().unwrap().token_type == lexer::TokenType::CloseBrace {
-                            break;
-                        }
-                    }
+                    let properties = parse_properties(tokens)?;
+                    expect(tokens, lexer::TokenType::CloseBrace)?;
 
-                    ast::Node::StructDecl { name, properties }
+                    ast::Node::StructDecl {
+                        name,
+                        properties,
+                        packed,
+                        id: tokens.id(start),
+                    }
                 }
                 _ => return Err(ParseError::InvalidToken),
             };
-
-            expect(tokens, lexer::TokenType::CloseBrace)?;
+            tokens.finish_node();
             ast
         }
+        lexer::TokenType::Fn => {
+            // example: fn(int, int) -> int
+            tokens.start_node_at(checkpoint, cst::NodeKind::FnType);
+            expect(tokens, lexer::TokenType::OpenParen)?;
+            let mut params = vec![];
+            if tokens.current()?.token_type != lexer::TokenType::CloseParen {
+                loop {
+                    params.push(parse_datatype(tokens)?);
+                    if tokens.current()?.token_type != lexer::TokenType::Comma {
+                        break;
+                    }
+                    tokens.pop();
+                }
+            }
+            expect(tokens, lexer::TokenType::CloseParen)?;
+            let return_type = parse_return_type(tokens)?;
+            tokens.finish_node();
+
+            ast::Node::FnType {
+                params,
+                return_type,
+                id: tokens.id(start),
+            }
+        }
         _ => return Err(ParseError::InvalidToken),
     };
     Ok(ast)
 }
 
-fn parse_primary(tokens: &mut Vec<lexer::Token>) -> Result<ast::Node, ParseError> {
-    let token = tokens.pop().unwrap();
-    let ast = match token.token_type {
-        lexer::TokenType::Integer => ast::Node::Integer(token.value.parse().unwrap()),
-        lexer::TokenType::Float => ast::Node::Float(token.value.parse().unwrap()),
-        lexer::TokenType::Identifier => ast::Node::Identifier { value: token.value },
+fn parse_primary(tokens: &mut Tokens) -> Result<ast::Node, ParseError> {
+    let start = tokens.start();
+    let checkpoint = tokens.checkpoint();
+    let token = tokens.next()?;
+    let mut ast = match token.token_type {
+        lexer::TokenType::Integer => {
+            tokens.start_node_at(checkpoint, cst::NodeKind::Literal);
+            // Literals that don't fit the type have no value
+            ast::Node::Integer {
+                value: token.value.parse().map_err(|_| ParseError::InvalidToken)?,
+                id: tokens.id(start),
+            }
+        }
+        lexer::TokenType::True | lexer::TokenType::False => {
+            tokens.start_node_at(checkpoint, cst::NodeKind::Literal);
+            ast::Node::Bool {
+                value: token.token_type == lexer::TokenType::True,
+                id: tokens.id(start),
+            }
+        }
+        lexer::TokenType::Char => {
+            tokens.start_node_at(checkpoint, cst::NodeKind::Literal);
+            ast::Node::Char {
+                value: lexer::char_value(&token.value).unwrap(),
+                id: tokens.id(start),
+            }
+        }
+        lexer::TokenType::Null => {
+            tokens.start_node_at(checkpoint, cst::NodeKind::Literal);
+            ast::Node::Null {
+                id: tokens.id(start),
+            }
+        }
+        lexer::TokenType::Float => {
+            tokens.start_node_at(checkpoint, cst::NodeKind::Literal);
+            ast::Node::Float {
+                value: token.value.parse().unwrap(),
+                id: tokens.id(start),
+            }
+        }
+        lexer::TokenType::Identifier
+            if tokens.last().map(|token| &token.token_type)
+                == Some(&lexer::TokenType::OpenParen) =>
+        {
+            // example: add(1, 2)
+            tokens.start_node_at(checkpoint, cst::NodeKind::Call);
+            tokens.pop();
+            let mut args = vec![];
+            if tokens.current()?.token_type != lexer::TokenType::CloseParen {
+                loop {
+                    args.push(parse_expr(tokens)?);
+                    if tokens.current()?.token_type != lexer::TokenType::Comma {
+                        break;
+                    }
+                    tokens.pop();
+                }
+            }
+            expect(tokens, lexer::TokenType::CloseParen)?;
+            ast::Node::Call {
+                name: token.value,
+                args,
+                id: tokens.id(start),
+            }
+        }
+        lexer::TokenType::Identifier => {
+            tokens.start_node_at(checkpoint, cst::NodeKind::Identifier);
+            ast::Node::Identifier {
+                value: token.value,
+                id: tokens.id(start),
+            }
+        }
+        lexer::TokenType::Sub => {
+            // example: -(a + b)
+            tokens.start_node_at(checkpoint, cst::NodeKind::UnaryOp);
+            ast::Node::UnaryOp {
+                value: Box::new(parse_primary(tokens)?),
+                op: ast::UnaryOperator::Neg,
+                id: tokens.id(start),
+            }
+        }
+        lexer::TokenType::Bang => {
+            tokens.start_node_at(checkpoint, cst::NodeKind::UnaryOp);
+            ast::Node::UnaryOp {
+                value: Box::new(parse_primary(tokens)?),
+                op: ast::UnaryOperator::Not,
+                id: tokens.id(start),
+            }
+        }
+        lexer::TokenType::Tilde => {
+            tokens.start_node_at(checkpoint, cst::NodeKind::UnaryOp);
+            ast::Node::UnaryOp {
+                value: Box::new(parse_primary(tokens)?),
+                op: ast::UnaryOperator::BitNot,
+                id: tokens.id(start),
+            }
+        }
+        lexer::TokenType::Star => {
+            // example: *p = 5
+            tokens.start_node_at(checkpoint, cst::NodeKind::UnaryOp);
+            ast::Node::UnaryOp {
+                value: Box::new(parse_primary(tokens)?),
+                op: ast::UnaryOperator::Deref,
+                id: tokens.id(start),
+            }
+        }
+        lexer::TokenType::Ampersand => {
+            // example: &x
+            tokens.start_node_at(checkpoint, cst::NodeKind::UnaryOp);
+            ast::Node::UnaryOp {
+                value: Box::new(parse_primary(tokens)?),
+                op: ast::UnaryOperator::AddressOf,
+                id: tokens.id(start),
+            }
+        }
+        lexer::TokenType::AddAdd | lexer::TokenType::SubSub => {
+            // example: ++i
+            tokens.start_node_at(checkpoint, cst::NodeKind::IncDec);
+            ast::Node::IncDec {
+                target: Box::new(parse_primary(tokens)?),
+                increment: token.token_type == lexer::TokenType::AddAdd,
+                postfix: false,
+                id: tokens.id(start),
+            }
+        }
+        lexer::TokenType::SizeOf => {
+            // example: sizeof(int*)
+            tokens.start_node_at(checkpoint, cst::NodeKind::SizeOf);
+            expect(tokens, lexer::TokenType::OpenParen)?;
+            // A name followed by nothing but stars is a type, anything else an
+            // expression
+            let mut ahead = 1;
+            while tokens.peek(ahead).map(|token| &token.token_type) == Some(&lexer::TokenType::Star)
+            {
+                ahead += 1;
+            }
+            let is_type = tokens.current()?.token_type == lexer::TokenType::Identifier
+                && tokens.peek(ahead).map(|token| &token.token_type)
+                    == Some(&lexer::TokenType::CloseParen);
+            if is_type {
+                let datatype = parse_datatype(tokens)?;
+                expect(tokens, lexer::TokenType::CloseParen)?;
+                ast::Node::SizeOfType {
+                    datatype,
+                    id: tokens.id(start),
+                }
+            } else {
+                let value = parse_expr(tokens)?;
+                expect(tokens, lexer::TokenType::CloseParen)?;
+                ast::Node::SizeOfValue {
+                    value: Box::new(value),
+                    id: tokens.id(start),
+                }
+            }
+        }
+        lexer::TokenType::OpenParen => {
+            tokens.start_node_at(checkpoint, cst::NodeKind::Paren);
+            let ast = parse_expr(tokens)?;
+            expect(tokens, lexer::TokenType::CloseParen)?;
+            ast
+        }
         lexer::TokenType::OpenBrace => {
+            tokens.start_node_at(checkpoint, cst::NodeKind::StructData);
             let mut data = vec![];
-            loop {
-                let node = parse_expr(tokens)?;
-                data.push(node);
-                let token_type = &tokens.last().unwrap().token_type;
-                if *token_type == lexer::TokenType::CloseBrace {
-                    tokens.pop().unwrap();
-                    break;
-                }
-                if *token_type == lexer::TokenType::Comma {
-                    tokens.pop().unwrap();
+            // Can be empty when every property has a default
+            while tokens.current()?.token_type != lexer::TokenType::CloseBrace {
+                // example: { y: 2, x: 1 }
+                let name = if tokens.current()?.token_type == lexer::TokenType::Identifier
+                    && tokens.peek(1).map(|token| &token.token_type)
+                        == Some(&lexer::TokenType::Colon)
+                {
+                    let name = tokens.next()?.value;
+                    tokens.pop();
+                    Some(name)
+                } else {
+                    None
+                };
+                data.push((name, parse_expr(tokens)?));
+                if tokens.current()?.token_type == lexer::TokenType::Comma {
+                    tokens.next()?;
                 }
             }
+            tokens.pop();
 
-            ast::Node::StructData { data }
+            ast::Node::StructData {
+                data,
+                id: tokens.id(start),
+            }
         }
         _ => return Err(ParseError::InvalidToken),
     };
+    tokens.finish_node();
+
+    // Postfix operators bind tighter than prefix ones, so `-i++` is `-(i++)`
+    while let Some(token_type) = tokens.last().map(|token| token.token_type.clone()) {
+        match token_type {
+            lexer::TokenType::Dot => {
+                // example: v.x
+                tokens.start_node_at(checkpoint, cst::NodeKind::MemberAccess);
+                tokens.pop();
+                let member = expect(tokens, lexer::TokenType::Identifier)?;
+                tokens.finish_node();
+                ast = ast::Node::MemberAccess {
+                    target: Box::new(ast),
+                    member: member.value,
+                    id: tokens.id(start),
+                };
+            }
+            lexer::TokenType::OpenBracket => {
+                // example: a[i + 1]
+                tokens.start_node_at(checkpoint, cst::NodeKind::Index);
+                tokens.pop();
+                let index = parse_expr(tokens)?;
+                expect(tokens, lexer::TokenType::CloseBracket)?;
+                tokens.finish_node();
+                ast = ast::Node::Index {
+                    target: Box::new(ast),
+                    index: Box::new(index),
+                    id: tokens.id(start),
+                };
+            }
+            lexer::TokenType::AddAdd | lexer::TokenType::SubSub => {
+                tokens.start_node_at(checkpoint, cst::NodeKind::IncDec);
+                tokens.pop();
+                tokens.finish_node();
+                ast = ast::Node::IncDec {
+                    target: Box::new(ast),
+                    increment: token_type == lexer::TokenType::AddAdd,
+                    postfix: true,
+                    id: tokens.id(start),
+                };
+            }
+            lexer::TokenType::As => {
+                // example: p as int
+                tokens.start_node_at(checkpoint, cst::NodeKind::Cast);
+                tokens.pop();
+                let datatype = parse_datatype(tokens)?;
+                tokens.finish_node();
+                ast = ast::Node::Cast {
+                    value: Box::new(ast),
+                    datatype,
+                    id: tokens.id(start),
+                };
+            }
+            _ => break,
+        }
+    }
     Ok(ast)
 }
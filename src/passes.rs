@@ -0,0 +1,50 @@
+use crate::ast;
+
+/// The places in the pipeline where registered passes are run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PassPoint {
+    /// Right after parsing, before any of the built-in passes
+    AfterParse,
+    /// After the built-in passes, right before the program is generated
+    BeforeCodegen,
+}
+
+/// An analysis or transformation over the whole program.
+pub trait Pass {
+    fn name(&self) -> &str;
+
+    fn run(&mut self, program: &mut ast::Node) -> Result<(), String>;
+}
+
+#[derive(Debug)]
+pub struct PassError {
+    pub pass: String,
+    pub message: String,
+}
+
+/// Runs the passes registered for each point in the order they were registered.
+#[derive(Default)]
+pub struct PassManager {
+    passes: Vec<(PassPoint, Box<dyn Pass>)>,
+}
+
+impl PassManager {
+    pub fn new() -> PassManager {
+        PassManager::default()
+    }
+
+    pub fn register(&mut self, point: PassPoint, pass: impl Pass + 'static) {
+        self.passes.push((point, Box::new(pass)));
+    }
+
+    pub fn run(&mut self, point: PassPoint, program: &mut ast::Node) -> Result<(), PassError> {
+        for (_, pass) in self.passes.iter_mut().filter(|(p, _)| *p == point) {
+            crate::info!("passes", "running {} at {point:?}", pass.name());
+            pass.run(program).map_err(|message| PassError {
+                pass: pass.name().to_owned(),
+                message,
+            })?;
+        }
+        Ok(())
+    }
+}
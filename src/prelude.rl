@@ -0,0 +1,19 @@
+// Functions every program can call without defining them. Only the ones a
+// program calls end up in its output
+
+// Ends the program right away with `status` as its exit code
+fn exit(int status) {
+    syscall(60, status);
+}
+
+// Ends the program with SIGABRT, like C's abort
+fn abort() {
+    syscall(62, syscall(39), 6);
+}
+
+// Aborts the program if `cond` is false
+fn assert(bool cond) {
+    if (!cond) {
+        abort();
+    }
+}
@@ -0,0 +1,62 @@
+use crate::source::SourceMap;
+use crate::{ast, callgraph, lexer, parser};
+use std::collections::HashSet;
+
+/// The prelude, written in the language itself.
+pub const SOURCE: &str = include_str!("prelude.rl");
+
+/// Adds the prelude functions `program` calls after its own statements, so
+/// they keep their order. Functions the program defines itself take the
+/// place of the prelude's. The prelude is added to `map` as a file of its
+/// own.
+pub fn inject(program: &mut ast::Node, map: &mut SourceMap) {
+    // The prelude's nodes are numbered after the program's
+    let first = program.next_id();
+    let ast::Node::Program { body, .. } = program else {
+        return;
+    };
+    let defined: HashSet<String> = body
+        .iter()
+        .filter_map(|expr| match expr {
+            ast::Node::FnDecl { name, .. } | ast::Node::Extern { name, .. } => Some(name.clone()),
+            _ => None,
+        })
+        .collect();
+
+    let file = map.add("prelude.rl", SOURCE);
+    let (tokens, _) = lexer::lex_file(SOURCE.to_owned(), file);
+    let parsed = parser::parse_program(SOURCE, tokens, first).expect("the prelude parses");
+    let ast::Node::Program { body: prelude, .. } = parsed.ast else {
+        unreachable!("the parser always returns a program");
+    };
+    let mut injected = HashSet::new();
+    for expr in prelude {
+        if let ast::Node::FnDecl { name, .. } = &expr {
+            if defined.contains(name) {
+                continue;
+            }
+            injected.insert(name.clone());
+        }
+        body.push(expr);
+    }
+
+    // Functions of a freestanding program are called from outside, so every
+    // one of them counts as used, not just what the entry point reaches
+    let graph = callgraph::build(program, callgraph::ENTRY);
+    let roots: Vec<&str> = defined
+        .iter()
+        .map(String::as_str)
+        .chain([callgraph::ENTRY])
+        .collect();
+    let used: HashSet<String> = graph
+        .reachable_from(&roots)
+        .into_iter()
+        .map(str::to_owned)
+        .collect();
+    if let ast::Node::Program { body, .. } = program {
+        body.retain(|expr| match expr {
+            ast::Node::FnDecl { name, .. } => !injected.contains(name) || used.contains(name),
+            _ => true,
+        });
+    }
+}
@@ -0,0 +1,45 @@
+/// Reads a profile written by a program built with `--instrument-profile`:
+/// the names of the counted functions, one per line and followed by an empty
+/// line, then a little endian qword counter for each of them.
+pub fn parse(data: &[u8]) -> Result<Vec<(String, u64)>, String> {
+    let end = data
+        .windows(2)
+        .position(|window| window == b"\n\n")
+        .ok_or("The profile has no list of functions")?;
+    let names = std::str::from_utf8(&data[..end])
+        .map_err(|_| "The profile's function names aren't valid UTF-8")?;
+    let names: Vec<&str> = names.split('\n').collect();
+
+    let counters = &data[end + 2..];
+    if counters.len() != 8 * names.len() {
+        return Err(format!(
+            "The profile has {} functions but {} bytes of counters, the program may have been stopped while writing it",
+            names.len(),
+            counters.len()
+        ));
+    }
+    Ok(names
+        .into_iter()
+        .zip(counters.chunks_exact(8))
+        .map(|(name, counter)| {
+            let counter = u64::from_le_bytes(counter.try_into().unwrap());
+            (name.to_owned(), counter)
+        })
+        .collect())
+}
+
+/// Formats the counts as a table, the most called functions first.
+pub fn report(mut counts: Vec<(String, u64)>) -> String {
+    counts.sort_by(|(a_name, a), (b_name, b)| b.cmp(a).then(a_name.cmp(b_name)));
+    let width = counts
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0)
+        .max("function".len());
+    let mut report = format!("{:<width$}  calls\n", "function");
+    for (name, count) in counts {
+        report += &format!("{name:<width$}  {count}\n");
+    }
+    report
+}
@@ -0,0 +1,275 @@
+use crate::cst;
+use crate::lexer;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SymbolKind {
+    Variable,
+    Datatype,
+    Function,
+}
+
+#[derive(Debug)]
+pub struct Symbol {
+    pub name: String,
+    /// Start index of the token that declares the symbol
+    pub definition: usize,
+}
+
+#[derive(Debug)]
+pub struct Reference {
+    pub start_index: usize,
+    pub end_index: usize,
+    /// Index into `Resolution::symbols`, `None` if the name couldn't be resolved
+    pub symbol: Option<usize>,
+}
+
+/// Every symbol declared in a file and every token that refers to one,
+/// including the declaring tokens themselves.
+#[derive(Debug, Default)]
+pub struct Resolution {
+    pub symbols: Vec<Symbol>,
+    pub references: Vec<Reference>,
+}
+
+#[derive(Default)]
+struct Scope {
+    variables: HashMap<String, usize>,
+    datatypes: HashMap<String, usize>,
+    functions: HashMap<String, usize>,
+}
+
+struct Resolver {
+    resolution: Resolution,
+    scopes: Vec<Scope>,
+}
+
+impl Resolution {
+    pub fn reference_at(&self, index: usize) -> Option<&Reference> {
+        self.references
+            .iter()
+            .find(|r| r.start_index <= index && index <= r.end_index)
+    }
+
+    pub fn references_to(&self, symbol: usize) -> impl Iterator<Item = &Reference> {
+        self.references
+            .iter()
+            .filter(move |r| r.symbol == Some(symbol))
+    }
+}
+
+pub fn resolve(cst: &cst::SyntaxNode) -> Resolution {
+    let mut resolver = Resolver {
+        resolution: Resolution::default(),
+        scopes: vec![Scope::default()],
+    };
+    resolver.node(cst);
+    resolver
+        .resolution
+        .references
+        .sort_by_key(|r| r.start_index);
+    crate::debug!(
+        "resolver",
+        "{} symbols, {} references",
+        resolver.resolution.symbols.len(),
+        resolver.resolution.references.len()
+    );
+    resolver.resolution
+}
+
+impl Resolver {
+    fn declare(&mut self, token: &cst::SyntaxToken, kind: SymbolKind) {
+        let symbol = self.resolution.symbols.len();
+        self.resolution.symbols.push(Symbol {
+            name: token.text.clone(),
+            definition: token.start_index,
+        });
+
+        let scope = self.scopes.last_mut().unwrap();
+        match kind {
+            SymbolKind::Variable => scope.variables.insert(token.text.clone(), symbol),
+            SymbolKind::Datatype => scope.datatypes.insert(token.text.clone(), symbol),
+            SymbolKind::Function => scope.functions.insert(token.text.clone(), symbol),
+        };
+        self.reference(token, Some(symbol));
+    }
+
+    fn lookup(&mut self, token: &cst::SyntaxToken, kind: SymbolKind) {
+        let symbol = self.scopes.iter().rev().find_map(|scope| match kind {
+            SymbolKind::Variable => scope.variables.get(&token.text).copied(),
+            SymbolKind::Datatype => scope.datatypes.get(&token.text).copied(),
+            SymbolKind::Function => scope.functions.get(&token.text).copied(),
+        });
+
+        // Builtin datatypes like int have no declaration to point at
+        if symbol.is_none() && kind == SymbolKind::Datatype {
+            return;
+        }
+        self.reference(token, symbol);
+    }
+
+    // Variables shadow functions, a call can go through a variable holding
+    // a function's address
+    fn lookup_value(&mut self, token: &cst::SyntaxToken) {
+        let is_variable = self
+            .scopes
+            .iter()
+            .any(|scope| scope.variables.contains_key(&token.text));
+        if is_variable {
+            self.lookup(token, SymbolKind::Variable);
+        } else {
+            self.lookup(token, SymbolKind::Function);
+        }
+    }
+
+    fn reference(&mut self, token: &cst::SyntaxToken, symbol: Option<usize>) {
+        self.resolution.references.push(Reference {
+            start_index: token.start_index,
+            end_index: token.end_index(),
+            symbol,
+        });
+    }
+
+    fn node(&mut self, node: &cst::SyntaxNode) {
+        let identifiers: Vec<&cst::SyntaxToken> = node
+            .child_tokens()
+            .filter(|token| token.token_type == lexer::TokenType::Identifier)
+            .collect();
+
+        match node.kind {
+            cst::NodeKind::Scope => {
+                self.scopes.push(Scope::default());
+                self.children(node);
+                self.scopes.pop();
+            }
+            cst::NodeKind::VarDecl => {
+                // example: vec2 position = { 1, 2 }
+                self.lookup(identifiers[0], SymbolKind::Datatype);
+                self.declare(identifiers[1], SymbolKind::Variable);
+                self.children(node);
+            }
+            cst::NodeKind::StructDecl | cst::NodeKind::UnionDecl => {
+                // The name is visible to the properties so they can refer to it
+                self.declare(identifiers[0], SymbolKind::Datatype);
+                self.children(node);
+            }
+            cst::NodeKind::EnumDecl => {
+                // Members are used like variables holding their value
+                self.declare(identifiers[0], SymbolKind::Datatype);
+                for member in &identifiers[1..] {
+                    self.declare(member, SymbolKind::Variable);
+                }
+            }
+            cst::NodeKind::Property | cst::NodeKind::Cast => {
+                self.lookup(identifiers[0], SymbolKind::Datatype);
+                self.children(node);
+            }
+            cst::NodeKind::TypeDef => {
+                self.children(node);
+                self.declare(identifiers[0], SymbolKind::Datatype);
+            }
+            cst::NodeKind::SizeOf => {
+                // example: sizeof(vec2*), where a name on its own can just as
+                // well be a variable, which comes first
+                if let Some(name) = identifiers.first() {
+                    let is_pointer = node
+                        .child_tokens()
+                        .any(|token| token.token_type == lexer::TokenType::Star);
+                    let is_variable = self
+                        .scopes
+                        .iter()
+                        .any(|scope| scope.variables.contains_key(&name.text));
+                    if is_variable && !is_pointer {
+                        self.lookup(name, SymbolKind::Variable);
+                    } else {
+                        self.lookup(name, SymbolKind::Datatype);
+                    }
+                }
+                self.children(node);
+            }
+            // A function's name on its own is its address
+            cst::NodeKind::Identifier | cst::NodeKind::Call => {
+                self.lookup_value(identifiers[0]);
+                self.children(node);
+            }
+            cst::NodeKind::FnType => {
+                for datatype in identifiers {
+                    self.lookup(datatype, SymbolKind::Datatype);
+                }
+            }
+            cst::NodeKind::FnDecl | cst::NodeKind::Extern => {
+                // example: fn add(int a, int b) -> int { ... }
+                // Top-level functions are already declared by the program,
+                // others before their body so it can call the function
+                if self
+                    .resolution
+                    .reference_at(identifiers[0].start_index)
+                    .is_none()
+                {
+                    self.declare(identifiers[0], SymbolKind::Function);
+                }
+                if let Some(return_type) = identifiers.get(1) {
+                    self.lookup(return_type, SymbolKind::Datatype);
+                }
+                // The parameters are visible in the body only
+                self.scopes.push(Scope::default());
+                self.children(node);
+                self.scopes.pop();
+            }
+            cst::NodeKind::Param => {
+                self.lookup(identifiers[0], SymbolKind::Datatype);
+                self.declare(identifiers[1], SymbolKind::Variable);
+            }
+            // Labels have a namespace of their own and can be jumped to before
+            // they're declared, the generator resolves them instead
+            cst::NodeKind::Label | cst::NodeKind::Goto => {}
+            cst::NodeKind::Attribute => {}
+            cst::NodeKind::Program => {
+                // Functions can be called before they're defined
+                for function in node.child_nodes().filter(|child| {
+                    matches!(child.kind, cst::NodeKind::FnDecl | cst::NodeKind::Extern)
+                }) {
+                    let name = function
+                        .child_tokens()
+                        .find(|token| token.token_type == lexer::TokenType::Identifier);
+                    if let Some(name) = name {
+                        self.declare(name, SymbolKind::Function);
+                    }
+                }
+                self.children(node);
+            }
+            cst::NodeKind::BinOp
+            | cst::NodeKind::UnaryOp
+            | cst::NodeKind::Paren
+            | cst::NodeKind::Literal
+            | cst::NodeKind::StructType
+            | cst::NodeKind::StructData
+            | cst::NodeKind::If
+            | cst::NodeKind::While
+            | cst::NodeKind::DoWhile
+            | cst::NodeKind::Break
+            | cst::NodeKind::Continue
+            | cst::NodeKind::Case
+            | cst::NodeKind::Ternary
+            | cst::NodeKind::Assign
+            | cst::NodeKind::IncDec
+            // Properties aren't symbols, only the struct they're read from is
+            | cst::NodeKind::MemberAccess
+            | cst::NodeKind::Index
+            | cst::NodeKind::Return => self.children(node),
+            // The initializer's variables are scoped to the loop, and the
+            // cases of a switch all share one scope
+            cst::NodeKind::For | cst::NodeKind::Switch => {
+                self.scopes.push(Scope::default());
+                self.children(node);
+                self.scopes.pop();
+            }
+        }
+    }
+
+    fn children(&mut self, node: &cst::SyntaxNode) {
+        for child in node.child_nodes() {
+            self.node(child);
+        }
+    }
+}
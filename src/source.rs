@@ -0,0 +1,204 @@
+/// How many columns apart tab stops are unless `--tab-width` says otherwise,
+/// the same as in terminals.
+pub const DEFAULT_TAB_WIDTH: usize = 8;
+
+/// A file of a `SourceMap`, numbered in the order they were added. Tokens
+/// and spans carry the id of the file they're from, so indices of
+/// different files can't be mixed up.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct FileId(pub usize);
+
+/// Every source file of a program, with where their lines start worked out
+/// once when they're added. Diagnostics turn indices into lines and
+/// columns through it, which takes a binary search rather than a scan of
+/// everything before them, so reporting many of them on a large file stays
+/// cheap.
+///
+/// Indices count characters, like the lexer's. Columns are where a
+/// character shows up on screen: a tab moves to the next tab stop and wide
+/// characters take up two columns, so a column points at the same place
+/// in an editor as in the diagnostics' snippets.
+#[derive(Debug, Clone)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+    tab_width: usize,
+}
+
+#[derive(Debug, Clone)]
+struct SourceFile {
+    name: String,
+    chars: Vec<char>,
+    /// The index of the first character of every line, the first line
+    /// starting at 0
+    line_starts: Vec<usize>,
+}
+
+impl Default for SourceMap {
+    fn default() -> SourceMap {
+        SourceMap::with_tab_width(DEFAULT_TAB_WIDTH)
+    }
+}
+
+impl SourceMap {
+    pub fn new() -> SourceMap {
+        SourceMap::default()
+    }
+
+    /// A map whose columns have tab stops every `tab_width` columns.
+    pub fn with_tab_width(tab_width: usize) -> SourceMap {
+        SourceMap {
+            files: vec![],
+            tab_width: tab_width.max(1),
+        }
+    }
+
+    /// Adds a file, returning the id its tokens are lexed with.
+    pub fn add(&mut self, name: &str, source: &str) -> FileId {
+        let chars: Vec<char> = source.chars().collect();
+        let breaks = chars
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| **c == '\n')
+            .map(|(i, _)| i + 1);
+        self.files.push(SourceFile {
+            name: name.to_owned(),
+            line_starts: std::iter::once(0).chain(breaks).collect(),
+            chars,
+        });
+        FileId(self.files.len() - 1)
+    }
+
+    /// The name of the file, as diagnostics refer to it.
+    pub fn name(&self, file: FileId) -> &str {
+        &self.files[file.0].name
+    }
+
+    /// The 1-based line and column of the character at `index` of `file`.
+    pub fn position(&self, file: FileId, index: usize) -> (usize, usize) {
+        let line = self.line(file, index);
+        let start = self.files[file.0].line_starts[line - 1];
+        (line, self.column(file, start, index) + 1)
+    }
+
+    /// The index of the character at a 1-based line and column of `file`,
+    /// if there is one. A column within a tab or a wide character is the
+    /// index of that character. The line break at the end of a line isn't
+    /// part of it.
+    pub fn index(&self, file: FileId, line: usize, col: usize) -> Option<usize> {
+        let source = &self.files[file.0];
+        let start = *source.line_starts.get(line.checked_sub(1)?)?;
+        let end = source
+            .line_starts
+            .get(line)
+            .map_or(source.chars.len(), |next| next - 1);
+        let target = col.checked_sub(1)?;
+        let mut column = 0;
+        for index in start..end {
+            column = self.advance(column, source.chars[index]);
+            if target < column {
+                return Some(index);
+            }
+        }
+        None
+    }
+
+    /// Where the character at `index` of `file` is, as `name:line:col`.
+    pub fn location(&self, file: FileId, index: usize) -> String {
+        let (line, col) = self.position(file, index);
+        format!("{}:{line}:{col}", self.name(file))
+    }
+
+    /// The line of `file` with the character at `start` on it, with the
+    /// characters from `start` to `end` underlined. A span that goes on
+    /// past the line is underlined to its end. Tabs are expanded, so the
+    /// underline lines up with them whatever the terminal's tab width.
+    pub fn snippet(&self, file: FileId, start: usize, end: usize) -> String {
+        let source = &self.files[file.0];
+        let line = self.line(file, start);
+        let line_start = source.line_starts[line - 1];
+        let line_end = source
+            .line_starts
+            .get(line)
+            .map_or(source.chars.len(), |next| next - 1);
+        let text: String = source.chars[line_start..line_end].iter().collect();
+
+        let from = self.column(file, line_start, start);
+        let last = end.clamp(start, line_end.max(start + 1) - 1);
+        let to = self.column(file, line_start, last + 1);
+        let gutter = " ".repeat(line.to_string().len());
+        format!(
+            "{gutter} |\n{line} | {}\n{gutter} | {}{}",
+            expand_tabs(text.trim_end_matches('\r'), self.tab_width),
+            " ".repeat(from),
+            "^".repeat((to - from).max(1))
+        )
+    }
+
+    // The 1-based line of the character at `index`
+    fn line(&self, file: FileId, index: usize) -> usize {
+        self.files[file.0]
+            .line_starts
+            .partition_point(|start| *start <= index)
+    }
+
+    // The 0-based column `index` starts at, counting from the start of its
+    // line `line_start`. The characters past the end of the file take up a
+    // column each
+    fn column(&self, file: FileId, line_start: usize, index: usize) -> usize {
+        let chars = &self.files[file.0].chars;
+        (line_start..index).fold(0, |column, i| {
+            self.advance(column, chars.get(i).copied().unwrap_or(' '))
+        })
+    }
+
+    fn advance(&self, column: usize, c: char) -> usize {
+        advance(column, c, self.tab_width)
+    }
+}
+
+/// `line` with its tabs replaced by the spaces up to the next tab stop.
+pub fn expand_tabs(line: &str, tab_width: usize) -> String {
+    let mut expanded = String::new();
+    let mut column = 0;
+    for c in line.chars() {
+        let next = advance(column, c, tab_width.max(1));
+        match c {
+            '\t' => expanded.push_str(&" ".repeat(next - column)),
+            _ => expanded.push(c),
+        }
+        column = next;
+    }
+    expanded
+}
+
+// The 0-based column after `c` when it starts at `column`
+fn advance(column: usize, c: char, tab_width: usize) -> usize {
+    match c {
+        '\t' => (column / tab_width + 1) * tab_width,
+        _ => column + width(c),
+    }
+}
+
+/// How many columns `c` takes up in a terminal: two for the wide characters
+/// of East Asian scripts and for most emoji, none for combining marks and
+/// one for everything else.
+pub fn width(c: char) -> usize {
+    match c as u32 {
+        0x0300..=0x036F | 0x200B..=0x200F | 0x20D0..=0x20FF | 0xFE00..=0xFE0F => 0,
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1F64F
+        | 0x1F900..=0x1F9FF
+        | 0x20000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
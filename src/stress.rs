@@ -0,0 +1,158 @@
+/// Generates a random program of about `stmts` statements that compiles,
+/// for benchmarking the compiler and for finding inputs that make it slow or
+/// overflow its stack. Blocks are nested at most `max_depth` deep, and the
+/// same `seed` always gives the same program.
+pub fn generate(stmts: usize, max_depth: usize, seed: u64) -> String {
+    let mut generator = Generator {
+        // xorshift gets stuck at 0
+        state: seed.max(1),
+        remaining: stmts.max(1),
+        max_depth,
+        next_var: 0,
+        scopes: vec![vec![]],
+        code: String::new(),
+    };
+    while generator.remaining > 0 {
+        generator.stmt(0);
+    }
+    generator.code
+}
+
+struct Generator {
+    state: u64,
+    remaining: usize,
+    max_depth: usize,
+    next_var: usize,
+    /// The variables visible in each enclosing block
+    scopes: Vec<Vec<String>>,
+    code: String,
+}
+
+const OPERATORS: [&str; 12] = [
+    "+", "-", "*", "&", "|", "^", "<", "<=", ">", ">=", "==", "!=",
+];
+
+impl Generator {
+    // xorshift64, good enough to pick between a few options
+    fn random(&mut self, below: usize) -> usize {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        (self.state % below as u64) as usize
+    }
+
+    fn line(&mut self, depth: usize, text: &str) {
+        self.code += &"    ".repeat(depth);
+        self.code += text;
+        self.code += "\n";
+    }
+
+    // Variable names must be unique across the whole program, the generator
+    // doesn't allow shadowing
+    fn declare(&mut self) -> String {
+        let name = format!("v{}", self.next_var);
+        self.next_var += 1;
+        self.scopes.last_mut().unwrap().push(name.clone());
+        name
+    }
+
+    fn variable(&mut self) -> Option<String> {
+        let count = self.scopes.iter().map(|scope| scope.len()).sum();
+        if count == 0 {
+            return None;
+        }
+        let index = self.random(count);
+        self.scopes.iter().flatten().nth(index).cloned()
+    }
+
+    fn stmt(&mut self, depth: usize) {
+        self.remaining = self.remaining.saturating_sub(1);
+        let nested = depth < self.max_depth && self.remaining > 0;
+        match self.random(if nested { 8 } else { 4 }) {
+            0 | 1 => {
+                let value = self.expr(3);
+                let name = self.declare();
+                self.line(depth, &format!("int {name} = {value};"));
+            }
+            2 | 3 => match self.variable() {
+                Some(name) => {
+                    let op = ["=", "+=", "-=", "*=", "&=", "|=", "^="][self.random(7)];
+                    let value = self.expr(3);
+                    self.line(depth, &format!("{name} {op} {value};"));
+                }
+                None => {
+                    let value = self.expr(3);
+                    self.line(depth, &format!("{value};"));
+                }
+            },
+            4 => {
+                let cond = self.expr(2);
+                self.line(depth, &format!("if ({cond}) {{"));
+                self.block(depth);
+                self.line(depth, "} else {");
+                self.block(depth);
+                self.line(depth, "}");
+            }
+            5 => {
+                // The loop variable is only visible inside the loop
+                self.scopes.push(vec![]);
+                let name = self.declare();
+                self.line(
+                    depth,
+                    &format!("for (int {name} = 0; {name} < 3; {name} += 1) {{"),
+                );
+                self.block(depth);
+                self.line(depth, "}");
+                self.scopes.pop();
+            }
+            6 => {
+                let cond = self.expr(2);
+                self.line(depth, &format!("while (({cond}) && false) {{"));
+                self.block(depth);
+                self.line(depth, "}");
+            }
+            _ => {
+                self.line(depth, "{");
+                self.block(depth);
+                self.line(depth, "}");
+            }
+        }
+    }
+
+    fn block(&mut self, depth: usize) {
+        self.scopes.push(vec![]);
+        // Blocks can't be empty
+        self.stmt(depth + 1);
+        for _ in 0..self.random(4) {
+            if self.remaining == 0 {
+                break;
+            }
+            self.stmt(depth + 1);
+        }
+        self.scopes.pop();
+    }
+
+    fn expr(&mut self, depth: usize) -> String {
+        let leaf = depth == 0;
+        match self.random(if leaf { 2 } else { 6 }) {
+            0 => self.random(1000).to_string(),
+            1 => self.variable().unwrap_or_else(|| "1".to_owned()),
+            2 | 3 => {
+                let op = OPERATORS[self.random(OPERATORS.len())];
+                format!("{} {op} {}", self.expr(depth - 1), self.operand(depth - 1))
+            }
+            4 => format!("-{}", self.operand(depth - 1)),
+            _ => format!(
+                "{} ? {} : {}",
+                self.operand(depth - 1),
+                self.operand(depth - 1),
+                self.operand(depth - 1)
+            ),
+        }
+    }
+
+    // An expression that can be used as an operand without changing its meaning
+    fn operand(&mut self, depth: usize) -> String {
+        format!("({})", self.expr(depth))
+    }
+}
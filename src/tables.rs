@@ -0,0 +1,97 @@
+use crate::ast::{self, NodeId};
+use crate::resolver;
+use crate::source::FileId;
+use std::collections::HashMap;
+
+/// Where a node's source is: the indices of its first and last character
+/// in `file`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub file: FileId,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Something found out about some of the nodes of a program, like their
+/// types, looked up by node id. Passes can fill and read tables of their
+/// own without the tree having a place for what they compute.
+#[derive(Debug, Clone)]
+pub struct SideTable<T> {
+    values: HashMap<NodeId, T>,
+}
+
+impl<T> Default for SideTable<T> {
+    fn default() -> SideTable<T> {
+        SideTable {
+            values: HashMap::new(),
+        }
+    }
+}
+
+impl<T> SideTable<T> {
+    pub fn new() -> SideTable<T> {
+        SideTable::default()
+    }
+
+    /// Records `value` for the node, replacing what was recorded before.
+    /// Nothing is recorded for detached nodes, which aren't in the program.
+    pub fn insert(&mut self, id: NodeId, value: T) {
+        if id != NodeId::DETACHED {
+            self.values.insert(id, value);
+        }
+    }
+
+    pub fn get(&self, id: NodeId) -> Option<&T> {
+        self.values.get(&id)
+    }
+
+    pub fn contains(&self, id: NodeId) -> bool {
+        self.values.contains_key(&id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Every node with a value, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (NodeId, &T)> {
+        self.values.iter().map(|(id, value)| (*id, value))
+    }
+}
+
+/// The symbol every identifier and call of `program` refers to, by joining
+/// the spans the parser recorded with the resolver's references.
+pub fn symbols(
+    program: &ast::Node,
+    spans: &SideTable<Span>,
+    resolution: &resolver::Resolution,
+) -> SideTable<usize> {
+    let mut symbols = SideTable::new();
+    add_symbols(program, spans, resolution, &mut symbols);
+    symbols
+}
+
+fn add_symbols(
+    node: &ast::Node,
+    spans: &SideTable<Span>,
+    resolution: &resolver::Resolution,
+    symbols: &mut SideTable<usize>,
+) {
+    // Both start with the name they refer by
+    if let ast::Node::Identifier { id, .. } | ast::Node::Call { id, .. } = node {
+        let symbol = spans
+            .get(*id)
+            .and_then(|span| resolution.reference_at(span.start))
+            .and_then(|reference| reference.symbol);
+        if let Some(symbol) = symbol {
+            symbols.insert(*id, symbol);
+        }
+    }
+    for child in node.children() {
+        add_symbols(child, spans, resolution, symbols);
+    }
+}
@@ -0,0 +1,91 @@
+use std::env;
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+use std::process;
+
+/// Looks for the first of `names` that's an executable in PATH, adding the
+/// platform's executable suffix (.exe on Windows).
+pub fn find(names: &[&str]) -> Option<PathBuf> {
+    find_in(&env::var_os("PATH")?, names)
+}
+
+/// Like `find`, looking in the directories of `paths` instead of PATH.
+pub fn find_in(paths: &OsStr, names: &[&str]) -> Option<PathBuf> {
+    for name in names {
+        let file = format!("{}{}", name, env::consts::EXE_SUFFIX);
+        for dir in env::split_paths(paths) {
+            let candidate = dir.join(&file);
+            if candidate.is_file() {
+                return Some(candidate);
+            }
+        }
+    }
+    None
+}
+
+/// The nasm executable. The Windows installer doesn't add itself to PATH,
+/// so its default install location is checked as well.
+pub fn assembler() -> Option<PathBuf> {
+    if let Some(nasm) = find(&["nasm"]) {
+        return Some(nasm);
+    }
+
+    if cfg!(windows) {
+        for var in ["ProgramFiles", "ProgramFiles(x86)", "LOCALAPPDATA"] {
+            if let Some(dir) = env::var_os(var) {
+                let candidate = Path::new(&dir).join("NASM").join("nasm.exe");
+                if candidate.is_file() {
+                    return Some(candidate);
+                }
+            }
+        }
+    }
+    None
+}
+
+/// A linker able to produce Linux ELF executables. Hosts other than Linux
+/// usually only have one through lld or a cross toolchain.
+pub fn linker() -> Option<PathBuf> {
+    linker_in(&env::var_os("PATH")?, cfg!(target_os = "linux"))
+}
+
+/// Like `linker`, looking in the directories of `paths` on a Linux host or
+/// another one. Off Linux a plain `ld` is the host's own, like macOS's ld64
+/// or MinGW's, which can't link ELF. It's only used if it lists
+/// elf_x86_64 among its emulations, after the linkers that always can.
+pub fn linker_in(paths: &OsStr, linux: bool) -> Option<PathBuf> {
+    if linux {
+        return find_in(paths, &["ld", "ld.lld", "x86_64-linux-gnu-ld"]);
+    }
+    find_in(paths, &["x86_64-linux-gnu-ld", "ld.lld"])
+        .or_else(|| find_in(paths, &["ld"]).filter(|ld| links_elf(ld)))
+}
+
+// Whether a GNU-style ld can link x86-64 ELF, going by the emulations
+// `ld -V` lists
+fn links_elf(ld: &Path) -> bool {
+    process::Command::new(ld)
+        .arg("-V")
+        .output()
+        .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).contains("elf_x86_64"))
+}
+
+/// The first line a tool prints about its version, e.g. `nasm -v`.
+pub fn version(tool: &Path, flag: &str) -> Option<String> {
+    let output = process::Command::new(tool).arg(flag).output().ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout.lines().next().map(|line| line.trim().to_owned())
+}
+
+/// The loader of dynamically linked x86-64 Linux executables, which programs
+/// using shared libraries have to name.
+pub const DYNAMIC_LINKER: &str = "/lib64/ld-linux-x86-64.so.2";
+
+/// The path of an intermediate file, `output` with `extension` appended.
+/// Appending rather than replacing keeps `prog.v2` from becoming `prog.asm`.
+pub fn artifact(output: &Path, extension: &str) -> PathBuf {
+    let mut name = OsString::from(output.as_os_str());
+    name.push(".");
+    name.push(extension);
+    PathBuf::from(name)
+}
@@ -0,0 +1,115 @@
+use crate::cst;
+use crate::lexer;
+use crate::resolver;
+
+/// A suggested edit that resolves a warning: replace the characters from
+/// `start_index` to `end_index` (inclusive) with `replacement`.
+#[derive(Debug)]
+pub struct Fix {
+    pub start_index: usize,
+    pub end_index: usize,
+    pub replacement: String,
+}
+
+#[derive(Debug)]
+pub struct Warning {
+    pub message: String,
+    pub start_index: usize,
+    pub end_index: usize,
+    pub fix: Option<Fix>,
+}
+
+/// Runs every lint over the file.
+pub fn check(cst: &cst::SyntaxNode, resolution: &resolver::Resolution) -> Vec<Warning> {
+    let mut warnings = vec![];
+    unused_types(cst, resolution, &mut warnings);
+    warnings
+}
+
+// Warns about structs, unions, enums and typedefs that are declared but never referred to
+fn unused_types(
+    node: &cst::SyntaxNode,
+    resolution: &resolver::Resolution,
+    warnings: &mut Vec<Warning>,
+) {
+    let mut children = node.children.iter();
+    while let Some(child) = children.next() {
+        let cst::SyntaxElement::Node(child) = child else {
+            continue;
+        };
+
+        // typedef struct vec2 { ... } vec2_t declares two names
+        let names: Vec<&cst::SyntaxToken> = match child.kind {
+            cst::NodeKind::StructDecl | cst::NodeKind::UnionDecl => {
+                child.child_tokens().filter(is_identifier).take(1).collect()
+            }
+            cst::NodeKind::EnumDecl => {
+                // An enum whose members are used is used too, even if its
+                // name never comes up
+                let mut identifiers = child.child_tokens().filter(is_identifier);
+                let name = identifiers.next();
+                if identifiers.any(|member| !is_unused(member, resolution)) {
+                    continue;
+                }
+                name.into_iter().collect()
+            }
+            cst::NodeKind::TypeDef => child
+                .child_nodes()
+                .filter(|node| node.kind == cst::NodeKind::StructDecl)
+                .flat_map(|node| node.child_tokens().filter(is_identifier).take(1))
+                .chain(child.child_tokens().filter(is_identifier))
+                .collect(),
+            _ => {
+                unused_types(child, resolution, warnings);
+                continue;
+            }
+        };
+
+        let declared = names.len();
+        let unused: Vec<&cst::SyntaxToken> = names
+            .into_iter()
+            .filter(|name| is_unused(name, resolution))
+            .collect();
+        let Some((start_index, mut end_index)) = child.range() else {
+            continue;
+        };
+
+        // The declaration can only be removed if none of its names are used,
+        // in which case its semicolon goes with it
+        let removable = unused.len() == declared;
+        let next = children
+            .clone()
+            .find(|element| !matches!(element, cst::SyntaxElement::Trivia { .. }));
+        if let Some(cst::SyntaxElement::Token(token)) = next {
+            if token.token_type == lexer::TokenType::Semicolon {
+                end_index = token.start_index;
+            }
+        }
+
+        for name in unused {
+            warnings.push(Warning {
+                message: format!("type {} is never used", name.text),
+                start_index: name.start_index,
+                end_index: name.end_index(),
+                fix: removable.then(|| Fix {
+                    start_index,
+                    end_index,
+                    replacement: String::new(),
+                }),
+            });
+        }
+    }
+}
+
+// Whether the only reference to what `name` declares is the declaration
+fn is_unused(name: &cst::SyntaxToken, resolution: &resolver::Resolution) -> bool {
+    let reference = resolution.reference_at(name.start_index);
+    match reference.and_then(|reference| reference.symbol) {
+        Some(symbol) => resolution.references_to(symbol).count() == 1,
+        None => false,
+    }
+}
+
+fn is_identifier(token: &&cst::SyntaxToken) -> bool {
+    token.token_type == lexer::TokenType::Identifier
+}
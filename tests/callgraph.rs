@@ -0,0 +1,86 @@
+use rlang::generator::Entry;
+use rlang::passes::PassManager;
+use std::fs;
+use std::process::Command;
+
+const SOURCE: &str = "fn leaf() { return 1; }
+fn twice() { return leaf() + leaf(); }
+fn unused() { return 3; }
+twice();
+";
+
+// The call graph of `SOURCE` when the program starts at `entry`
+fn graph(entry: &str) -> rlang::callgraph::CallGraph {
+    let options = rlang::Options {
+        prelude: false,
+        ..Default::default()
+    };
+    rlang::compile_program(SOURCE, &options, &mut PassManager::new(), |env| {
+        env.entry = Entry::Symbol(entry.to_owned());
+    })
+    .unwrap()
+    .graph
+}
+
+#[test]
+fn dot_has_every_function_and_call_once() {
+    assert_eq!(
+        graph("_start").to_dot(),
+        "digraph callgraph {
+    \"_start\";
+    \"leaf\";
+    \"twice\";
+    \"unused\";
+    \"twice\" -> \"leaf\";
+    \"_start\" -> \"twice\";
+}
+"
+    );
+}
+
+#[test]
+fn json_has_every_function_and_call_once() {
+    assert_eq!(
+        graph("_start").to_json(),
+        "{\"nodes\": [\"_start\", \"leaf\", \"twice\", \"unused\"], \
+         \"edges\": [[\"twice\", \"leaf\"], [\"_start\", \"twice\"]]}\n"
+    );
+}
+
+#[test]
+fn entry_node_is_named_after_the_entry() {
+    let graph = graph("main");
+    assert_eq!(graph.nodes[0], "main");
+    assert!(graph
+        .edges
+        .contains(&("main".to_owned(), "twice".to_owned())));
+    assert!(!graph.to_dot().contains("_start"));
+    assert!(graph.reachable().contains("leaf"));
+    assert!(!graph.reachable().contains("unused"));
+}
+
+// The graph is written before the program is assembled, so this doesn't
+// need nasm
+#[test]
+fn build_emits_the_graph_next_to_the_output() {
+    let dir = std::env::temp_dir().join(format!("rlang-callgraph-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("prog.rl");
+    fs::write(&input, SOURCE).unwrap();
+    let output = dir.join("prog");
+    Command::new(env!("CARGO_BIN_EXE_rlang"))
+        .arg("build")
+        .arg(&input)
+        .arg("-o")
+        .arg(&output)
+        .args(["--emit", "callgraph", "--emit", "callgraph-json"])
+        .args(["--entry", "main"])
+        .output()
+        .unwrap();
+
+    let dot = fs::read_to_string(dir.join("prog.dot")).unwrap();
+    assert!(dot.contains("\"main\" -> \"twice\";"), "{dot}");
+    assert!(!dot.contains("_start"), "{dot}");
+    let json = fs::read_to_string(dir.join("prog.json")).unwrap();
+    assert!(json.contains("[\"main\", \"twice\"]"), "{json}");
+}
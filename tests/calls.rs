@@ -0,0 +1,95 @@
+use rlang::calls::{self, CallError};
+use rlang::{lexer, parser, resolver};
+
+fn check(source: &str) -> Vec<CallError> {
+    let (tokens, _) = lexer::lex(source.to_owned());
+    let (_, cst) = parser::parse(source, tokens).unwrap();
+    calls::check(&cst, &resolver::resolve(&cst))
+}
+
+// The text of the source between two indices, inclusive
+fn span(source: &str, start_index: usize, end_index: usize) -> &str {
+    &source[start_index..=end_index]
+}
+
+#[test]
+fn argument_counts_point_at_the_declaration() {
+    let source = "fn add(int a, int b) -> int { return a + b; }\nadd(1, 2, 3);";
+    let errors = check(source);
+    assert_eq!(errors.len(), 1, "{errors:?}");
+    let err = &errors[0];
+    assert_eq!(err.message, "expected 2 arguments, found 3");
+    assert_eq!(span(source, err.start_index, err.end_index), "add(1, 2, 3)");
+    assert_eq!(err.label.message, "add is declared here");
+    assert_eq!(
+        span(source, err.label.start_index, err.label.end_index),
+        "fn add(int a, int b) -> int"
+    );
+
+    let source = "extern fn printf(int format, ...) -> int;\nprintf();\nprintf(1, 2, 3);";
+    let errors = check(source);
+    assert_eq!(errors.len(), 1, "{errors:?}");
+    assert_eq!(errors[0].message, "expected at least 1 argument, found 0");
+}
+
+#[test]
+fn arguments_match_their_parameters() {
+    let source = "struct vec2 { int x; int y; };\nfn scale(float by, vec2* v) { return; }\nint x = 1;\nvec2 v = { 1, 2 };\nscale(2.0, &x);\nscale((1.5), &v);\nscale(x, &v);";
+    let errors = check(source);
+    assert_eq!(errors.len(), 1, "{errors:?}");
+    assert_eq!(
+        errors[0].message,
+        "mismatched types: expected vec2*, found int*"
+    );
+    assert_eq!(
+        span(source, errors[0].start_index, errors[0].end_index),
+        "&x"
+    );
+    assert_eq!(
+        span(
+            source,
+            errors[0].label.start_index,
+            errors[0].label.end_index
+        ),
+        "vec2* v"
+    );
+
+    // ints and bools convert, and arguments of unclear type are left to the
+    // generator
+    assert!(check(
+        "fn f(int a, bool b) -> int { return a; }\nint x = 1;\nf(true, x);\nf(x + 1, f(1, 0));"
+    )
+    .is_empty());
+
+    // So are structs and typedefs, which can name the same type differently
+    assert!(check(
+        "struct vec2 { int x; int y; };\nfn scale(float by) { return; }\nvec2 v = { 1, 2 };\nscale(v);"
+    )
+    .is_empty());
+    assert!(check(
+        "typedef fn(int) -> int op;\ntypedef fn(int) -> int op2;\nfn dbl(int x) -> int { return x * 2; }\nfn apply(op f, int x) -> int { return f(x); }\nop2 g = dbl;\napply(g, 21);"
+    )
+    .is_empty());
+
+    // Numbers convert like stored values do, and pointers become bools, but
+    // floats need a cast to become bools and ints one to become pointers
+    let errors = check(
+        "fn f(float a, char b, bool c, int d) -> int { return d; }\nint* p = null;\nf(1, 'a', p, 2.5);\nf(true, 1, 1.5, 'b');\nf(p, 1, true, 1);"
+    );
+    let messages: Vec<&str> = errors.iter().map(|err| err.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        [
+            "mismatched types: expected bool, found float",
+            "mismatched types: expected float, found int*"
+        ]
+    );
+
+    // null is passed for any pointer, but nothing else
+    let errors = check("fn f(int* p, int n) -> int { return n; }\nf(null, 1);\nf(null, null);");
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].message,
+        "mismatched types: expected int, found null"
+    );
+}
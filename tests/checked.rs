@@ -0,0 +1,71 @@
+use rlang::passes::PassManager;
+use rlang::toolchain;
+use std::fs;
+use std::process::Command;
+
+fn compile(source: &str) -> String {
+    rlang::compile_with(
+        source,
+        &Default::default(),
+        &mut PassManager::new(),
+        |env| env.checked = true,
+    )
+    .unwrap()
+}
+
+#[test]
+fn divisors_are_checked() {
+    let code = compile("int d = 2;\n10 / d;");
+    assert!(
+        code.contains("test rcx, rcx\n    jnz .divisor_ok_"),
+        "{code}"
+    );
+
+    let plain = rlang::compile(
+        "int d = 2;\n10 / d;",
+        &Default::default(),
+        &mut PassManager::new(),
+    );
+    assert!(!plain.unwrap().contains("divisor_ok"));
+}
+
+#[test]
+fn zero_divisors_abort_the_program() {
+    let (Some(nasm), Some(ld)) = (toolchain::assembler(), toolchain::linker()) else {
+        eprintln!("skipping checked division test, nasm or ld is missing");
+        return;
+    };
+    if !cfg!(target_os = "linux") {
+        eprintln!("skipping checked division test, it can only run on Linux");
+        return;
+    }
+
+    let code = compile("int d = 0;\n10 / d;");
+    let dir = std::env::temp_dir().join(format!("rlang-checked-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let (asm, object, binary) = (dir.join("x.asm"), dir.join("x.o"), dir.join("x"));
+    fs::write(&asm, code).unwrap();
+    let assembled = Command::new(nasm)
+        .arg("-felf64")
+        .arg(&asm)
+        .arg("-o")
+        .arg(&object)
+        .status()
+        .unwrap();
+    assert!(assembled.success());
+    let linked = Command::new(ld)
+        .arg(&object)
+        .arg("-o")
+        .arg(&binary)
+        .status()
+        .unwrap();
+    assert!(linked.success());
+
+    let output = Command::new(&binary).output().unwrap();
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "checked: division by zero\n"
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
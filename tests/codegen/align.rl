@@ -0,0 +1,14 @@
+// Like in C, properties are aligned to their size and the struct's size is
+// padded to a multiple of its largest alignment, so `flags` takes 12 bytes
+struct flags {
+    bool a;
+    int b;
+    bool c;
+};
+flags f = { true, 2, false };
+bool after = true;
+
+// CHECK: mov byte [rbp-12], al
+// CHECK: mov dword [rbp-8], eax
+// CHECK: mov byte [rbp-4], al
+// CHECK: mov byte [rbp-13], al
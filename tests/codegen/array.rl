@@ -0,0 +1,10 @@
+// Arrays take the space of all their elements and are aligned like one
+// element, without any code to initialize them
+bool flag = true;
+int a[10];
+int after = 1;
+
+// CHECK: sub rsp, 48
+// CHECK: mov byte [rbp-1], al
+// CHECK-NOT: [rbp-44]
+// CHECK: mov dword [rbp-48], eax
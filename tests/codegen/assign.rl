@@ -0,0 +1,8 @@
+// Assignments write back to the variable's slot and chain to the right
+int a = 1;
+int b = 2;
+a = b = 3;
+
+// CHECK: mov rax, 3
+// CHECK: mov dword [rbp-8], eax
+// CHECK: mov dword [rbp-4], eax
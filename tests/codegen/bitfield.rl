@@ -0,0 +1,25 @@
+// Bitfields share the unit of their type's size. They're read by shifting
+// the bits above them out and themselves down, and written by clearing
+// their bits in the unit and or-ing the new ones in
+struct flags {
+    bool ready : 1;
+    int mode : 3;
+};
+flags f = { true, 2 };
+f.mode = 5;
+int m = f.mode;
+
+// CHECK: mov dl, byte [rbp-4]
+// CHECK-NEXT: and dl, -2
+// CHECK: mov rax, 5
+// CHECK: shl rax, 61
+// CHECK-NEXT: mov rcx, rax
+// CHECK-NEXT: shr rcx, 60
+// CHECK-NEXT: sar rax, 61
+// CHECK-NEXT: mov edx, dword [rbp-4]
+// CHECK-NEXT: and edx, -15
+// CHECK-NEXT: or edx, ecx
+// CHECK-NEXT: mov dword [rbp-4], edx
+// CHECK: movsxd rax, dword [rbp-4]
+// CHECK-NEXT: shl rax, 60
+// CHECK-NEXT: sar rax, 61
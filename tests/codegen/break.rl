@@ -0,0 +1,20 @@
+// break leaves the innermost loop, continue jumps to where the next
+// iteration starts: the condition, or the step of a for loop
+while (true) {
+    for (int i = 0; i < 3;) {
+        continue;
+    }
+    break;
+}
+do {
+    continue;
+} while (false);
+
+// CHECK: .while_0:
+// CHECK: .for_2:
+// CHECK: jmp .for_step_3
+// CHECK: .for_step_3:
+// CHECK: .for_end_4:
+// CHECK-NEXT: jmp .while_end_1
+// CHECK: jmp .do_cond_6
+// CHECK: .do_cond_6:
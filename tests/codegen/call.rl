@@ -0,0 +1,32 @@
+// Arguments are evaluated left to right and kept on the stack until they're
+// all known, then popped into their registers. Past the sixth they're
+// stored in the stack space reserved below the saved rsp
+fn sum(int a, int b, int c, int d, int e, int f, int g) -> int {
+    a + g;
+}
+sum(1, 2, 3, 4, 5, 6, 7);
+
+// CHECK: mov rax, rsp
+// CHECK-NEXT: and rsp, -16
+// CHECK-NEXT: push rax
+// CHECK-NEXT: sub rsp, 8
+// CHECK: mov rax, 1
+// CHECK: push rax
+// CHECK: mov rax, 7
+// CHECK: mov qword [rsp+48], rax
+// CHECK-NEXT: pop r9
+// CHECK-NEXT: pop r8
+// CHECK-NEXT: pop rcx
+// CHECK-NEXT: pop rdx
+// CHECK-NEXT: pop rsi
+// CHECK-NEXT: pop rdi
+// CHECK-NEXT: call sum
+// CHECK-NEXT: add rsp, 8
+// CHECK-NEXT: pop rsp
+
+// The callee copies its arguments into its own frame
+// CHECK: sum:
+// CHECK: mov dword [rbp-4], edi
+// CHECK: mov dword [rbp-24], r9d
+// CHECK-NEXT: mov eax, dword [rbp+16]
+// CHECK-NEXT: mov dword [rbp-28], eax
@@ -0,0 +1,20 @@
+// Casts to int truncate floats toward zero and keep the low dword of a
+// pointer, while casts between pointers leave the address as it is
+int x = 1;
+int* p = &x;
+float f = 2.5;
+int i = f as int;
+int n = p as int;
+bool* b = p as bool*;
+int* z = 0 as int*;
+
+// CHECK: movd xmm0, eax
+// CHECK-NEXT: cvttss2si rax, xmm0
+// CHECK: mov dword [rbp-24], eax
+// CHECK: mov rax, qword [rbp-16]
+// CHECK: movsxd rax, eax
+// CHECK: mov dword [rbp-28], eax
+// CHECK: mov rax, qword [rbp-16]
+// CHECK-NOT: movsxd
+// CHECK: mov qword [rbp-40], rax
+// CHECK: mov rax, 0
@@ -0,0 +1,12 @@
+// Chars are signed bytes, so they're stored from al and sign-extended when
+// they're read
+char c = 'A';
+char n = '\n';
+int i = c + n;
+
+// CHECK: mov rax, 65
+// CHECK: mov byte [rbp-1], al
+// CHECK: mov rax, 10
+// CHECK: mov byte [rbp-2], al
+// CHECK: movsx rax, byte [rbp-1]
+// CHECK: movsx rax, byte [rbp-2]
@@ -0,0 +1,13 @@
+// Compound assignments read, modify and write back the variable
+int a = 7;
+a -= 2;
+a %= 3;
+
+// CHECK: movsxd rax, dword [rbp-4]
+// CHECK: mov rax, 2
+// CHECK: sub rax, rcx
+// CHECK: mov dword [rbp-4], eax
+// CHECK: cqo
+// CHECK-NEXT: idiv rcx
+// CHECK-NEXT: mov rax, rdx
+// CHECK: mov dword [rbp-4], eax
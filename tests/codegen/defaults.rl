@@ -0,0 +1,15 @@
+// Properties left out of a struct literal are set to their default, after
+// the values that were given
+struct particle {
+    int x;
+    int speed = -3;
+    float mass = 2;
+};
+particle p = { 5 };
+
+// CHECK: mov rax, 5
+// CHECK: mov dword [rbp-12], eax
+// CHECK: mov rax, -3
+// CHECK-NEXT: mov dword [rbp-8], eax
+// CHECK: mov rax, 1073741824
+// CHECK-NEXT: mov dword [rbp-4], eax
@@ -0,0 +1,15 @@
+// Named values go to their property whatever order they're written in, and
+// unnamed ones continue with the property after the previous value
+struct color {
+    int r;
+    int g;
+    int b;
+};
+color c = { b: 3, r: 1, 2 };
+
+// CHECK: mov rax, 3
+// CHECK: mov dword [rbp-4], eax
+// CHECK: mov rax, 1
+// CHECK: mov dword [rbp-12], eax
+// CHECK: mov rax, 2
+// CHECK: mov dword [rbp-8], eax
@@ -0,0 +1,15 @@
+// The body of a do while loop comes before its condition, which jumps back
+// to the top while it holds. continue jumps to the condition
+int n = 0;
+do {
+    n++;
+    continue;
+} while (n < 3);
+
+// CHECK: .do_0:
+// CHECK: inc dword [rbp-4]
+// CHECK: jmp .do_cond_1
+// CHECK: .do_cond_1:
+// CHECK: cmp
+// CHECK: jnz .do_0
+// CHECK: .do_end_2:
@@ -0,0 +1,27 @@
+// Blocks can be empty. The statements around them still jump where they
+// would with a body
+fn nothing() {}
+int x = 3;
+if (x) {}
+while (0) {}
+for (; x < 5; x++) {}
+do {} while (0);
+{}
+nothing();
+
+// CHECK: jz .else_1
+// CHECK: jmp .if_end_2
+// CHECK-NEXT: .else_1:
+// CHECK: .while_3:
+// CHECK: jz .while_end_4
+// CHECK: jmp .while_3
+// CHECK-NEXT: .while_end_4:
+// CHECK: jz .for_end_7
+// CHECK: .for_step_6:
+// CHECK: jmp .for_5
+// CHECK: .do_8:
+// CHECK: .do_cond_9:
+// CHECK: jnz .do_8
+// CHECK: call nothing
+// CHECK: nothing:
+// CHECK-NEXT: push rbp
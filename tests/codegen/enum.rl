@@ -0,0 +1,18 @@
+// Members are immediates, and enums are stored like ints
+enum color { red, green, blue };
+color c = blue;
+int i = c + green;
+
+// CHECK: mov rax, 2
+// CHECK: mov dword [rbp-4], eax
+// CHECK: movsxd rax, dword [rbp-4]
+// CHECK: mov rax, 1
+// CHECK: mov dword [rbp-8], eax
+
+// Members without a value count up from the one before them
+enum level { low = -1, normal, high = 10, higher };
+int h = higher;
+int l = low;
+
+// CHECK: mov rax, 11
+// CHECK: mov rax, -1
@@ -0,0 +1,12 @@
+// Loads fill the whole register: ints are sign extended and bools zero
+// extended, so the variables next to them never leak into the value
+int a = -1;
+bool b = true;
+int c = 2;
+a;
+b;
+c;
+
+// CHECK: movsxd rax, dword [rbp-4]
+// CHECK: movzx rax, byte [rbp-5]
+// CHECK: movsxd rax, dword [rbp-12]
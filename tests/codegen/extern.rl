@@ -0,0 +1,17 @@
+// Functions defined elsewhere are called the C way: floats go in xmm
+// registers, are promoted to doubles when they're variadic arguments, and al
+// holds how many xmm registers are used
+extern fn printf(int format, ...) -> int;
+printf(0, 1.5, 2);
+
+// CHECK: extern printf
+// CHECK: section .text
+// CHECK: push rax
+// CHECK-NOT: call
+// CHECK: pop rsi
+// CHECK-NEXT: pop rax
+// CHECK-NEXT: movd xmm0, eax
+// CHECK-NEXT: cvtss2sd xmm0, xmm0
+// CHECK-NEXT: pop rdi
+// CHECK-NEXT: mov al, 1
+// CHECK-NEXT: call printf
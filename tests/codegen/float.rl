@@ -0,0 +1,6 @@
+// Floats are loaded rip-relative from read-only data
+1.5;
+
+// CHECK: mov eax, dword [rel ..@float_0]
+// CHECK: section .rodata
+// CHECK-NEXT: ..@float_0: dd 0x3fc00000
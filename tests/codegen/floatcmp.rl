@@ -0,0 +1,23 @@
+// Float comparisons go through ucomiss. NaN is unordered, which sets ZF, PF
+// and CF together, so == also requires PF to be clear and < swaps its
+// operands to be tested like >
+float a = 1.5;
+float b = 2.5;
+a == b;
+a < b;
+
+// CHECK: movd xmm0, eax
+// CHECK-NEXT: movd xmm1, ecx
+// CHECK-NEXT: ucomiss xmm0, xmm1
+// CHECK-NEXT: sete al
+// CHECK-NEXT: setnp cl
+// CHECK-NEXT: and al, cl
+// CHECK: ucomiss xmm1, xmm0
+// CHECK-NEXT: seta al
+
+// An int operand is converted before the operation
+a + 1;
+// CHECK: movd xmm0, eax
+// CHECK-NEXT: cvtsi2ss xmm1, rcx
+// CHECK-NEXT: addss xmm0, xmm1
+// CHECK-NEXT: movd eax, xmm0
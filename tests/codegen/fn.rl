@@ -0,0 +1,11 @@
+// Nothing calls the function, so it's pruned from the output along with
+// its body
+fn unused(int a, int b) -> int {
+    a + b;
+}
+1;
+
+// CHECK: _start:
+// CHECK: mov rax, 1
+// CHECK: syscall
+// CHECK-NOT: unused:
@@ -0,0 +1,16 @@
+// Function pointers are the address of the function's label, calls through
+// them load it once the arguments are in their registers
+typedef fn(int) -> int unary;
+
+fn neg(int a) -> int {
+    return -a;
+}
+
+unary f = neg;
+f(1);
+
+// CHECK: lea rax, [rel neg]
+// CHECK: mov qword [rbp-8], rax
+// CHECK: pop rdi
+// CHECK-NEXT: mov rax, qword [rbp-8]
+// CHECK-NEXT: call rax
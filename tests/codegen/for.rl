@@ -0,0 +1,25 @@
+// A for loop runs its initializer once, tests the condition at the top and
+// runs the step at the label continue jumps to. Without a condition it
+// loops until something breaks out of it
+for (int i = 0; i < 3; i++) {
+    continue;
+}
+for (;;) {
+    break;
+}
+
+// CHECK: mov dword [rbp-4], eax
+// CHECK: .for_0:
+// CHECK: cmp
+// CHECK: jz .for_end_2
+// CHECK: jmp .for_step_1
+// CHECK: .for_step_1:
+// CHECK: inc dword [rbp-4]
+// CHECK: jmp .for_0
+// CHECK-NEXT: .for_end_2:
+// CHECK: .for_3:
+// CHECK-NOT: jz
+// CHECK: jmp .for_end_5
+// CHECK: .for_step_4:
+// CHECK: jmp .for_3
+// CHECK-NEXT: .for_end_5:
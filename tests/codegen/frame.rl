@@ -0,0 +1,34 @@
+// Every function has a frame of its own, which is left out if it's empty
+fn one() -> int {
+    return 1;
+}
+
+// The frame is as big as the deepest point the variables reach. Sibling
+// scopes share their space, nested ones add to it
+int a = 1;
+{
+    int b = 2;
+    {
+        int c = 3;
+    }
+}
+{
+    int d = 4;
+    int e = 5;
+    int f = 6;
+    int g = 7;
+}
+one();
+
+// CHECK: global _start:function
+// CHECK-NEXT: _start:
+// CHECK-NEXT: push rbp
+// CHECK-NEXT: mov rbp, rsp
+// CHECK-NEXT: sub rsp, 32
+// CHECK: mov dword [rbp-20], eax
+
+// CHECK: one:
+// CHECK-NEXT: push rbp
+// CHECK-NEXT: mov rbp, rsp
+// CHECK-NOT: sub rsp
+// CHECK: ret
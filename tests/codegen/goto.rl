@@ -0,0 +1,12 @@
+// Labels get their asm names before any code is generated, so goto can
+// jump forward as well as backward
+goto done;
+retry:
+1;
+goto retry;
+done:
+
+// CHECK: jmp .done_1
+// CHECK: .retry_0:
+// CHECK: jmp .retry_0
+// CHECK: .done_1:
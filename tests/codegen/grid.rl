@@ -0,0 +1,19 @@
+// Indices into every dimension add up to a single address. Constant ones
+// are folded into the offset, computed ones into one distance in bytes
+int grid[3][6];
+int i = 1;
+int j = 2;
+grid[2][1] = 1;
+grid[i][j] = 2;
+
+// CHECK: sub rsp, 80
+// CHECK: mov dword [rbp-20], eax
+// CHECK-NEXT: movsxd rax, dword [rbp-76]
+// CHECK-NEXT: imul rax, rax, 24
+// CHECK-NEXT: mov r11, rax
+// CHECK-NEXT: push r11
+// CHECK-NEXT: movsxd rax, dword [rbp-80]
+// CHECK-NEXT: imul rax, rax, 4
+// CHECK-NEXT: pop r11
+// CHECK-NEXT: lea r11, [rax+r11*1]
+// CHECK: mov dword [rbp+r11*1-72], eax
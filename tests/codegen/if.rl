@@ -0,0 +1,17 @@
+// The condition jumps past the then branch when it's zero
+{
+    int a = 1;
+    if (a) {
+        int b = 2;
+    } else {
+        int c = 3;
+    }
+}
+
+// CHECK: test rax, rax
+// CHECK-NEXT: jz .else_0
+// CHECK: mov rax, 2
+// CHECK: jmp .if_end_1
+// CHECK-NEXT: .else_0:
+// CHECK: mov rax, 3
+// CHECK: .if_end_1:
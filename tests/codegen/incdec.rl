@@ -0,0 +1,10 @@
+// Postfix forms evaluate to the value from before the change, prefix forms
+// to the value after it
+int i = 0;
+i++;
+--i;
+
+// CHECK: movsxd rax, dword [rbp-4]
+// CHECK-NEXT: inc dword [rbp-4]
+// CHECK: dec dword [rbp-4]
+// CHECK-NEXT: movsxd rax, dword [rbp-4]
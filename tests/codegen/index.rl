@@ -0,0 +1,17 @@
+// Constant indices are folded into the offset of the element, others are
+// scaled by the element size in the address
+int a[4];
+bool b[3];
+int i = 2;
+a[3] = 7;
+a[i] = b[i];
+
+// CHECK: mov dword [rbp-24], eax
+// CHECK: mov dword [rbp-4], eax
+// CHECK-NEXT: movsxd rax, dword [rbp-24]
+// CHECK-NEXT: mov r11, rax
+// CHECK-NEXT: push r11
+// CHECK: mov r11, rax
+// CHECK-NEXT: movzx rax, byte [rbp+r11*1-19]
+// CHECK-NEXT: pop r11
+// CHECK: mov dword [rbp+r11*4-16], eax
@@ -0,0 +1,25 @@
+// Every function starts on a 16 byte boundary and ends with a label, which
+// gives the exported ones a size in the symbol table. The others are local
+// and keep just their address
+fn helper() -> int {
+    return 2;
+}
+
+helper();
+
+// CHECK: align 16
+// CHECK-NEXT: global _start:function (_start.end - _start)
+// CHECK-NEXT: _start:
+// CHECK: syscall
+// CHECK-NEXT: mov rsp, rbp
+// CHECK-NEXT: pop rbp
+// CHECK-NEXT: ret
+// CHECK-NEXT: _start.end:
+
+// CHECK: align 16
+// CHECK-NEXT: helper:
+// CHECK: .return_0:
+// CHECK-NEXT: mov rsp, rbp
+// CHECK-NEXT: pop rbp
+// CHECK-NEXT: ret
+// CHECK-NEXT: helper.end:
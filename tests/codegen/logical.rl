@@ -0,0 +1,11 @@
+// Logical operators short-circuit instead of lowering to their bitwise versions
+{
+    bool a = true || false && true;
+}
+
+// CHECK-NOT: or rax, rcx
+// CHECK: jnz .or_end_0
+// CHECK: jz .and_end_1
+// CHECK: .and_end_1:
+// CHECK: .or_end_0:
+// CHECK-NOT: and rax, rcx
@@ -0,0 +1,13 @@
+// A property is read at its offset from the start of the struct, with the
+// width of its own type
+struct pair {
+    bool flag;
+    int value;
+};
+pair p = { true, 7 };
+p.value + p.flag;
+
+// CHECK: mov byte [rbp-8], al
+// CHECK: mov dword [rbp-4], eax
+// CHECK: movsxd rax, dword [rbp-4]
+// CHECK: movzx rax, byte [rbp-8]
@@ -0,0 +1,17 @@
+// Assigning to a property stores only its own bytes, so the properties
+// next to it are left alone
+struct pair {
+    bool flag;
+    int value;
+};
+pair p = { true, 7 };
+p.flag = false;
+p.value += 2;
+p.value++;
+
+// CHECK: mov rax, 0
+// CHECK: mov byte [rbp-8], al
+// CHECK: movsxd rax, dword [rbp-4]
+// CHECK: add rax, rcx
+// CHECK: mov dword [rbp-4], eax
+// CHECK: inc dword [rbp-4]
@@ -0,0 +1,14 @@
+// Binary operators evaluate the left operand into rax and the right one into rcx
+{
+    int a = 6 << 2 | 1;
+}
+
+// CHECK: mov rax, 6
+// CHECK: push rax
+// CHECK: mov rax, 2
+// CHECK: mov rcx, rax
+// CHECK-NEXT: pop rax
+// CHECK-NEXT: shl rax, cl
+// CHECK: mov rax, 1
+// CHECK: or rax, rcx
+// CHECK: mov dword [rbp-4], eax
@@ -0,0 +1,22 @@
+// Operands and arguments are evaluated from left to right, whatever
+// registers they end up in
+fn f(int x) -> int {
+    return x;
+}
+fn g(int a, int b) -> int {
+    return a - b;
+}
+f(1) - f(2);
+g(f(3), f(4));
+
+// CHECK: mov rax, 1
+// CHECK: call f
+// CHECK: mov rax, 2
+// CHECK: call f
+// CHECK: mov rax, 3
+// CHECK: call f
+// CHECK: mov rax, 4
+// CHECK: call f
+// CHECK: pop rsi
+// CHECK-NEXT: pop rdi
+// CHECK-NEXT: call g
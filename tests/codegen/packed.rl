@@ -0,0 +1,13 @@
+// Packed structs have no padding, properties follow each other byte for byte
+struct packed header {
+    bool kind;
+    int length;
+    bool last;
+};
+header h = { true, 2, false };
+bool after = true;
+
+// CHECK: mov byte [rbp-6], al
+// CHECK: mov dword [rbp-5], eax
+// CHECK: mov byte [rbp-1], al
+// CHECK: mov byte [rbp-7], al
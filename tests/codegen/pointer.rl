@@ -0,0 +1,18 @@
+// Taking an address is a lea, and pointers are loaded into the index
+// register to read and write through them
+int x = 1;
+int* p = &x;
+*p = 5;
+x = *p + 1;
+
+// CHECK: lea rax, [rbp-4]
+// CHECK: mov qword [rbp-16], rax
+// CHECK-NEXT: mov rax, qword [rbp-16]
+// CHECK-NEXT: mov r11, rax
+// CHECK-NEXT: push r11
+// CHECK-NEXT: mov rax, 5
+// CHECK-NEXT: pop r11
+// CHECK: mov dword [r11], eax
+// CHECK-NEXT: mov rax, qword [rbp-16]
+// CHECK-NEXT: mov r11, rax
+// CHECK-NEXT: movsxd rax, dword [r11]
@@ -0,0 +1,18 @@
+// Integers added to pointers are scaled by the size of what they point to,
+// and the difference of two pointers is divided by it
+int a[4];
+int* p = &a[0];
+int* q = p + 3;
+q - p;
+p++;
+
+// CHECK: mov rax, 3
+// CHECK: mov rcx, rax
+// CHECK-NEXT: pop rax
+// CHECK-NEXT: imul rcx, rcx, 4
+// CHECK-NEXT: add rax, rcx
+// CHECK: sub rax, rcx
+// CHECK-NEXT: mov rcx, 4
+// CHECK-NEXT: cqo
+// CHECK-NEXT: idiv rcx
+// CHECK: add qword [rbp-24], 4
@@ -0,0 +1,22 @@
+// Functions the entry point can't reach are dropped. A function is reached
+// when it's called, also by another reachable function, or when its address
+// is taken, since it can be called through the pointer
+typedef fn() -> int thunk;
+fn leaf() -> int { return 1; }
+fn called() -> int { return leaf(); }
+fn pointed() -> int { return 2; }
+fn unused() -> int { return unused_helper(); }
+fn unused_helper() -> int { return 3; }
+thunk p = pointed;
+called() + p();
+
+// CHECK-NOT: unused
+// CHECK: _start:
+// CHECK: lea rax, [rel pointed]
+// CHECK: call called
+// CHECK-NOT: unused
+// CHECK: leaf:
+// CHECK: called:
+// CHECK: call leaf
+// CHECK: pointed:
+// CHECK-NOT: unused
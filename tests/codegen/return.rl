@@ -0,0 +1,18 @@
+// return leaves the value in rax and jumps to the function's epilogue
+fn pick(bool first) -> int {
+    if (first) {
+        return 1;
+    }
+    return 2;
+}
+pick(true);
+
+// CHECK: pick:
+// CHECK: mov rax, 1
+// CHECK: jmp .return_0
+// CHECK: mov rax, 2
+// CHECK: jmp .return_0
+// CHECK: .return_0:
+// CHECK-NEXT: mov rsp, rbp
+// CHECK-NEXT: pop rbp
+// CHECK-NEXT: ret
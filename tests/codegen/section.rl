@@ -0,0 +1,15 @@
+// A function can be placed in a section of its own, which a linker script
+// can put somewhere specific. The code after it is back in .text
+@section(".text.boot")
+fn boot() -> int {
+    return 1;
+}
+boot();
+
+// CHECK: section .text.boot progbits alloc exec nowrite align=16
+// CHECK-NEXT: align 16
+// CHECK-NEXT: boot:
+// CHECK: pop rbp
+// CHECK-NEXT: ret
+// CHECK-NEXT: boot.end:
+// CHECK-NEXT: section .text
@@ -0,0 +1,15 @@
+// Sizes are immediates, and the operand of sizeof generates no code
+struct vec2 { int x; int y; };
+vec2 v = { 1, 2 };
+int n = 0;
+int a = sizeof(vec2);
+int b = sizeof(v.y);
+int c = sizeof(n++);
+
+// CHECK: mov rax, 8
+// CHECK: mov dword [rbp-16], eax
+// CHECK: mov rax, 4
+// CHECK: mov dword [rbp-20], eax
+// CHECK: mov rax, 4
+// CHECK: mov dword [rbp-24], eax
+// CHECK-NOT: inc
@@ -0,0 +1,12 @@
+// A struct variable starts at its lowest address and its properties follow
+// in declaration order, like in C
+struct vec {
+    int x;
+    int y;
+};
+vec v = { 1, 2 };
+
+// CHECK: mov rax, 1
+// CHECK: mov dword [rbp-8], eax
+// CHECK: mov rax, 2
+// CHECK: mov dword [rbp-4], eax
@@ -0,0 +1,39 @@
+// Structs of up to 16 bytes are passed in as many registers as they have
+// eightbytes, larger ones are copied to the stack
+struct vec3 {
+    int x;
+    int y;
+    int z;
+};
+struct big {
+    int a;
+    int b;
+    int c;
+    int d;
+    int e;
+};
+fn f(int k, vec3 v, big b) -> int {
+    return k + v.z + b.e;
+}
+vec3 v = { 1, 2, 3 };
+big b = { 1, 2, 3, 4, 5 };
+f(1, v, b);
+
+// CHECK: mov qword [rbp-48], rcx
+// CHECK-NEXT: mov ecx, dword [rbp-4]
+// CHECK-NEXT: mov dword [rbp-40], ecx
+// CHECK-NEXT: push qword [rbp-48]
+// CHECK-NEXT: push qword [rbp-40]
+// CHECK-NEXT: mov rcx, qword [rbp-32]
+// CHECK-NEXT: mov qword [rsp+24], rcx
+// CHECK: pop rdx
+// CHECK-NEXT: pop rsi
+// CHECK-NEXT: pop rdi
+// CHECK-NEXT: call f
+// CHECK: f:
+// CHECK: mov qword [rbp-32], rsi
+// CHECK-NEXT: mov qword [rbp-24], rdx
+// CHECK-NEXT: mov rcx, qword [rbp-32]
+// CHECK-NEXT: mov qword [rbp-16], rcx
+// CHECK: mov rcx, qword [rbp+16]
+// CHECK-NEXT: mov qword [rbp-52], rcx
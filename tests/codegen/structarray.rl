@@ -0,0 +1,25 @@
+// Elements are as far apart as their struct is big. A whole element's
+// address is kept while the value's, which may need the index register
+// too, is computed
+struct vec3 {
+    int x;
+    int y;
+    int z;
+};
+vec3 cubes[3];
+int i = 1;
+int j = 2;
+cubes[i] = cubes[j];
+cubes[j].y = 7;
+
+// CHECK: movsxd rax, dword [rbp-40]
+// CHECK-NEXT: imul rax, rax, 12
+// CHECK-NEXT: mov r11, rax
+// CHECK-NEXT: lea rax, [rbp+r11*1-36]
+// CHECK-NEXT: push rax
+// CHECK: pop rdx
+// CHECK-NEXT: mov rcx, qword [rbp+r11*1-36]
+// CHECK-NEXT: mov qword [rdx], rcx
+// CHECK-NEXT: mov ecx, dword [rbp+r11*1-28]
+// CHECK-NEXT: mov dword [rdx+8], ecx
+// CHECK: mov dword [rbp+r11*1-32], eax
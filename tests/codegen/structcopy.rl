@@ -0,0 +1,19 @@
+// Structs are copied with the widest moves that fit their size, through rcx
+struct vec3 {
+    int x;
+    int y;
+    int z;
+};
+vec3 a = { 1, 2, 3 };
+vec3 b = a;
+b = a;
+
+// CHECK: mov rcx, qword [rbp-12]
+// CHECK-NEXT: mov qword [rbp-24], rcx
+// CHECK-NEXT: mov ecx, dword [rbp-4]
+// CHECK-NEXT: mov dword [rbp-16], ecx
+// CHECK: mov rcx, qword [rbp-12]
+// CHECK-NEXT: mov qword [rbp-24], rcx
+// CHECK-NEXT: mov ecx, dword [rbp-4]
+// CHECK-NEXT: mov dword [rbp-16], ecx
+// CHECK-NEXT: lea rax, [rbp-24]
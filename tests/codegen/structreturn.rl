@@ -0,0 +1,54 @@
+// Structs of up to 16 bytes are returned in rax and rdx, larger ones are
+// written to memory the caller passes the address of in rdi. A variable
+// initialized by the call is that memory, so nothing is copied
+struct vec2 {
+    int x;
+    int y;
+};
+struct big {
+    int a;
+    int b;
+    int c;
+    int d;
+    int e;
+};
+fn make_vec() -> vec2 {
+    return { 1, 2 };
+}
+fn make_big() -> big {
+    big b = { 1, 2, 3, 4, 5 };
+    return b;
+}
+fn sum(big b) -> int {
+    return b.a + b.e;
+}
+vec2 v = make_vec();
+big b = make_big();
+int s = sum(make_big());
+
+// CHECK: call make_vec
+// CHECK-NEXT: add rsp, 8
+// CHECK-NEXT: pop rsp
+// CHECK-NEXT: mov qword [rbp-24], rax
+// CHECK-NEXT: mov qword [rbp-16], rdx
+// CHECK-NEXT: lea rax, [rbp-24]
+// CHECK-NEXT: mov rcx, qword [rax]
+// CHECK-NEXT: mov qword [rbp-8], rcx
+// CHECK: lea rdi, [rbp-28]
+// CHECK-NEXT: call make_big
+// CHECK-NEXT: add rsp, 8
+// CHECK-NEXT: pop rsp
+// CHECK-NOT: mov qword [rbp-28], rcx
+// CHECK: lea rdi, [rbp-56]
+// CHECK-NEXT: call make_big
+// CHECK: mov rcx, qword [rax]
+// CHECK: call sum
+// CHECK: make_vec:
+// CHECK: mov rax, qword [rbp-24]
+// CHECK-NOT: rdx
+// CHECK: make_big:
+// CHECK: mov qword [rbp-8], rdi
+// CHECK: mov rdx, qword [rbp-8]
+// CHECK-NEXT: mov rcx, qword [rbp-28]
+// CHECK-NEXT: mov qword [rdx], rcx
+// CHECK: mov rax, rdx
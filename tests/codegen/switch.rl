@@ -0,0 +1,34 @@
+// Dense cases are dispatched through a jump table, gaps go to default
+int a = 2;
+switch (a) {
+case 0:
+case 1:
+    1;
+case 3:
+    break;
+case 4:
+    2;
+default:
+    3;
+}
+
+// CHECK: sub rax, 0
+// CHECK-NEXT: cmp rax, 4
+// CHECK-NEXT: ja .case_5
+// CHECK: dq .case_1, .case_2, .case_5, .case_3, .case_4
+// CHECK: .case_3:
+// CHECK-NEXT: jmp .switch_end_0
+
+// Sparse ones are compared one by one
+switch (a) {
+case 100:
+    break;
+case -7:
+    4;
+}
+
+// CHECK: cmp rax, 100
+// CHECK-NEXT: je .case_8
+// CHECK-NEXT: cmp rax, -7
+// CHECK-NEXT: je .case_9
+// CHECK-NEXT: jmp .switch_end_7
@@ -0,0 +1,12 @@
+// The system call number goes in rax and the arguments in the kernel's
+// registers, which differ from a function call's in using r10
+syscall(1, 2, 3, 4, 5);
+
+// CHECK: mov rax, 5
+// CHECK: push rax
+// CHECK-NEXT: pop r10
+// CHECK-NEXT: pop rdx
+// CHECK-NEXT: pop rsi
+// CHECK-NEXT: pop rdi
+// CHECK-NEXT: pop rax
+// CHECK-NEXT: syscall
@@ -0,0 +1,23 @@
+// Assigning a struct literal builds it in a temporary below the variables
+// and then copies it over, so the literal can still read the old values
+struct vec {
+    int x;
+    int y;
+    bool z;
+};
+vec v = { 1, 2, true };
+v = { 3, 4, false };
+
+// CHECK: mov dword [rbp-24], eax
+// CHECK: mov dword [rbp-20], eax
+// CHECK: mov byte [rbp-16], al
+// CHECK-NEXT: mov rcx, qword [rbp-24]
+// CHECK-NEXT: mov qword [rbp-12], rcx
+// CHECK-NEXT: mov ecx, dword [rbp-16]
+// CHECK-NEXT: mov dword [rbp-4], ecx
+// CHECK-NEXT: lea rax, [rbp-12]
+
+// Temporaries only live until the end of their statement, after that the
+// space goes to the next variable
+int a = 5;
+// CHECK: mov dword [rbp-16], eax
@@ -0,0 +1,13 @@
+// Only the chosen branch is evaluated
+int a = 1;
+int b = a ? 2 : 3;
+
+// CHECK: movsxd rax, dword [rbp-4]
+// CHECK: test rax, rax
+// CHECK-NEXT: jz .ternary_else_0
+// CHECK-NEXT: mov rax, 2
+// CHECK: jmp .ternary_end_1
+// CHECK-NEXT: .ternary_else_0:
+// CHECK-NEXT: mov rax, 3
+// CHECK: .ternary_end_1:
+// CHECK: mov dword [rbp-8], eax
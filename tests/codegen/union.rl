@@ -0,0 +1,10 @@
+// Every property of a union is at its start, read and written at its own width
+union number { int i; float f; bool b; };
+number n = { f: 1.0 };
+n.i = 300;
+bool low = n.b;
+
+// CHECK: mov dword [rbp-4], eax
+// CHECK: mov rax, 300
+// CHECK: mov dword [rbp-4], eax
+// CHECK: movzx rax, byte [rbp-4]
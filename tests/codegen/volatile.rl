@@ -0,0 +1,13 @@
+// Volatile accesses read and write the int at an address exactly once,
+// where they're written
+int status = volatile_load(753664);
+volatile_store(753664, status | 256);
+
+// CHECK: mov rax, 753664
+// CHECK: movsxd rax, dword [rax]
+// CHECK: mov dword [rbp-4], eax
+// CHECK: mov rax, 753664
+// CHECK: push rax
+// CHECK: or rax, rcx
+// CHECK: pop rcx
+// CHECK-NEXT: mov dword [rcx], eax
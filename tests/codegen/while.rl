@@ -0,0 +1,14 @@
+// The condition is tested at the top of every iteration
+{
+    int a = 3;
+    while (a) {
+        int b = a;
+    }
+}
+
+// CHECK: .while_0:
+// CHECK-NEXT: movsxd rax, dword [rbp-4]
+// CHECK: test rax, rax
+// CHECK-NEXT: jz .while_end_1
+// CHECK: jmp .while_0
+// CHECK-NEXT: .while_end_1:
@@ -0,0 +1,254 @@
+use rlang::conversions::{comparable, explicit, implicit, Class, Conversion};
+use rlang::generator::{Datatype, Property};
+use rlang::passes::PassManager;
+
+fn int() -> Datatype {
+    Datatype::Single {
+        size: 4,
+        signed: true,
+    }
+}
+
+fn bool() -> Datatype {
+    Datatype::Single {
+        size: 1,
+        signed: false,
+    }
+}
+
+fn color() -> Datatype {
+    Datatype::Enum {
+        name: "color".to_owned(),
+    }
+}
+
+fn pointer(target: Datatype) -> Datatype {
+    Datatype::Pointer {
+        target: Box::new(target),
+    }
+}
+
+fn single_property(datatype: Datatype) -> Datatype {
+    Datatype::Struct {
+        size: datatype.size(),
+        align: datatype.align(),
+        properties: vec![Property {
+            name: "value".to_owned(),
+            offset: 0,
+            datatype,
+            default: None,
+            bits: None,
+        }],
+    }
+}
+
+// One datatype of every class, in the order of the table below
+fn samples() -> Vec<Datatype> {
+    vec![
+        bool(),
+        int(),
+        color(),
+        Datatype::Float { size: 4 },
+        pointer(int()),
+        Datatype::Null,
+        Datatype::Array {
+            element: Box::new(int()),
+            len: 4,
+        },
+        single_property(int()),
+        Datatype::Function {
+            params: vec![int()],
+            return_type: Some(Box::new(int())),
+        },
+    ]
+}
+
+#[test]
+fn every_pair_of_classes_converts_as_listed() {
+    use Conversion::*;
+    // Rows convert from, columns to: bool, int, enum, float, pointer, null,
+    // array, struct, function
+    let expected = [
+        [
+            Some(Keep),
+            Some(Keep),
+            None,
+            Some(IntToFloat),
+            None,
+            None,
+            None,
+            None,
+            None,
+        ],
+        [
+            Some(ToBool),
+            Some(Keep),
+            None,
+            Some(IntToFloat),
+            None,
+            None,
+            None,
+            None,
+            None,
+        ],
+        [
+            Some(ToBool),
+            Some(Keep),
+            Some(Keep),
+            Some(IntToFloat),
+            None,
+            None,
+            None,
+            None,
+            None,
+        ],
+        [
+            None,
+            Some(FloatToInt),
+            None,
+            Some(Keep),
+            None,
+            None,
+            None,
+            None,
+            None,
+        ],
+        [
+            Some(ToBool),
+            None,
+            None,
+            None,
+            Some(Keep),
+            None,
+            None,
+            None,
+            None,
+        ],
+        [
+            Some(ToBool),
+            None,
+            None,
+            None,
+            Some(Keep),
+            None,
+            None,
+            None,
+            Some(Keep),
+        ],
+        [None, None, None, None, Some(Decay), None, None, None, None],
+        [None, None, None, None, None, None, None, Some(Keep), None],
+        [None, None, None, None, None, None, None, None, Some(Keep)],
+    ];
+    let samples = samples();
+    for (from, row) in samples.iter().zip(expected) {
+        for (to, conversion) in samples.iter().zip(row) {
+            assert_eq!(
+                implicit(from, to),
+                conversion,
+                "{:?} to {:?}",
+                Class::of(from),
+                Class::of(to)
+            );
+        }
+    }
+}
+
+#[test]
+fn every_datatype_has_a_class() {
+    let classes: Vec<Class> = samples().iter().map(Class::of).collect();
+    assert_eq!(
+        classes,
+        [
+            Class::Bool,
+            Class::Integer,
+            Class::Enum,
+            Class::Float,
+            Class::Pointer,
+            Class::Null,
+            Class::Array,
+            Class::Struct,
+            Class::Function
+        ]
+    );
+}
+
+#[test]
+fn pointers_enums_structs_and_functions_only_convert_to_their_own_type() {
+    assert_eq!(implicit(&pointer(int()), &pointer(bool())), None);
+    let shape = Datatype::Enum {
+        name: "shape".to_owned(),
+    };
+    assert_eq!(implicit(&color(), &shape), None);
+    assert_eq!(
+        implicit(&pointer(pointer(int())), &pointer(pointer(int()))),
+        Some(Conversion::Keep)
+    );
+
+    let ints = Datatype::Array {
+        element: Box::new(int()),
+        len: 2,
+    };
+    assert_eq!(implicit(&ints, &pointer(bool())), None);
+    assert_eq!(implicit(&ints, &pointer(ints.clone())), None);
+
+    assert_eq!(
+        implicit(&single_property(int()), &single_property(bool())),
+        None
+    );
+
+    let function = |params| Datatype::Function {
+        params,
+        return_type: None,
+    };
+    assert_eq!(implicit(&function(vec![int()]), &function(vec![])), None);
+}
+
+#[test]
+fn pointers_compare_with_null_and_their_own_type() {
+    assert!(comparable(&pointer(int()), &Datatype::Null));
+    assert!(comparable(&Datatype::Null, &pointer(bool())));
+    assert!(comparable(&pointer(int()), &pointer(int())));
+    assert!(!comparable(&pointer(int()), &pointer(bool())));
+    assert!(!comparable(&pointer(int()), &int()));
+    assert!(!comparable(&Datatype::Null, &int()));
+    assert!(comparable(&int(), &Datatype::Float { size: 4 }));
+}
+
+#[test]
+fn casts_reach_what_conversions_cannot() {
+    let float = Datatype::Float { size: 4 };
+    assert_eq!(explicit(&int(), &float), Some(Conversion::IntToFloat));
+    assert_eq!(explicit(&int(), &pointer(int())), Some(Conversion::Keep));
+    assert_eq!(explicit(&pointer(int()), &int()), Some(Conversion::Narrow));
+    assert_eq!(
+        explicit(&pointer(int()), &pointer(bool())),
+        Some(Conversion::Keep)
+    );
+    assert_eq!(explicit(&float, &pointer(int())), None);
+    assert_eq!(explicit(&pointer(int()), &float), None);
+    assert_eq!(explicit(&single_property(int()), &int()), None);
+
+    let shape = Datatype::Enum {
+        name: "shape".to_owned(),
+    };
+    assert_eq!(explicit(&int(), &color()), Some(Conversion::Keep));
+    assert_eq!(explicit(&shape, &color()), Some(Conversion::Keep));
+    assert_eq!(explicit(&float, &color()), None);
+}
+
+fn compile(source: &str) -> Result<String, String> {
+    rlang::compile(source, &Default::default(), &mut PassManager::new())
+}
+
+// The values of a struct literal are stored like any other, converted to
+// the type of their property
+#[test]
+fn struct_literal_values_convert_to_their_property() {
+    let asm = compile("struct P { float x; int y; };\nP p = { 1, 2.9 };\np.y;").unwrap();
+    let to_float = asm.find("cvtsi2ss xmm0, rax").expect(&asm);
+    let to_int = asm.find("cvttss2si rax, xmm0").expect(&asm);
+    assert!(to_float < to_int, "{asm}");
+
+    let err = compile("struct P { int* p; };\nP p = { 1.5 };").unwrap_err();
+    assert!(err.starts_with("MismatchedTypes"), "{err}");
+}
@@ -0,0 +1,68 @@
+use rlang::generator::{Environment, Instrumentation};
+use rlang::{coverage, lexer, parser};
+
+#[test]
+fn every_statement_that_runs_is_counted() {
+    let source = "fn one() -> int {\n    return 1;\n}\nint x = one();\nif (x) {\n    x = 2;\n}\nx;";
+    let (tokens, _) = lexer::lex(source.to_owned());
+    let (ast, _, statements) = parser::parse_statements(source, tokens).unwrap();
+    let mut env = Environment::new();
+    env.instrumentation = Some(Instrumentation::Coverage {
+        path: "/tmp/out.coverage".to_owned(),
+        statements,
+    });
+    let code = ast.generate(&mut env).unwrap();
+
+    // The function declaration itself isn't counted
+    let counted: Vec<String> = env
+        .counters
+        .borrow()
+        .iter()
+        .map(|index| {
+            let index: usize = index.parse().unwrap();
+            source[index..].lines().next().unwrap().to_owned()
+        })
+        .collect();
+    assert_eq!(
+        counted,
+        [
+            "return 1;",
+            "int x = one();",
+            "if (x) {",
+            "{",
+            "x = 2;",
+            "x;"
+        ]
+    );
+    assert!(code.contains("..@profile_counters: resq 6"), "{code}");
+}
+
+#[test]
+fn lines_show_how_often_their_first_statement_ran() {
+    let source = "int x = 0;\nfor (;;) { x++; }\nif (x) {\n    x = 1;\n}\n";
+    let statements = [0, 11, 20, 22, 29, 36, 42];
+    let counts = [1, 1, 1, 3, 1, 1, 0];
+    assert_eq!(
+        coverage::render(source, &statements, &counts, 8),
+        "       1 | int x = 0;\n       1 | for (;;) { x++; }\n       1 | if (x) {\n   ##### |     x = 1;\n         | }\n"
+    );
+}
+
+#[test]
+fn tabs_are_expanded_behind_the_counts() {
+    let source = "日 = 0;\n\tx = 1;\n";
+    assert_eq!(
+        coverage::render(source, &[0, 8], &[1, 2], 4),
+        "       1 | 日 = 0;\n       2 |     x = 1;\n"
+    );
+}
+
+#[test]
+fn coverage_maps_are_read_back() {
+    let map = coverage::map("/src/main.rl", &["4".to_owned(), "17".to_owned()]);
+    assert_eq!(
+        coverage::parse_map(&map).unwrap(),
+        ("/src/main.rl".to_owned(), vec![4, 17])
+    );
+    assert!(coverage::parse_map("/src/main.rl\nfour\n").is_err());
+}
@@ -0,0 +1,29 @@
+use rlang::{lexer, parser};
+
+fn round_trip(source: &str) -> String {
+    let (tokens, errors) = lexer::lex(source.to_owned());
+    assert!(errors.is_empty());
+    let (_, cst) = parser::parse(source, tokens).unwrap();
+    cst.to_string()
+}
+
+#[test]
+fn printing_the_tree_gives_back_the_source() {
+    let source = "fn f(int a) -> int {\n\treturn a * 2; // twice\n}\n\nint x = f(1);\nx;\n";
+    assert_eq!(round_trip(source), source);
+}
+
+// Token indices count characters, so trivia after a multi-byte character
+// used to be cut from the wrong place
+#[test]
+fn non_ascii_comments_and_strings_round_trip() {
+    let source = "// héllo wörld\n@section(\".tëxt\") fn f() -> int { return 1; }\nint 日本 = f(); // 日本\n日本;\n";
+    assert_eq!(round_trip(source), source);
+
+    let (tokens, _) = lexer::lex(source.to_owned());
+    let (_, cst) = parser::parse(source, tokens).unwrap();
+    // The two characters of `日本` on the last line
+    let last = cst.child_nodes().last().unwrap();
+    let start = source.chars().count() - "日本;\n".chars().count();
+    assert_eq!(last.range(), Some((start, start + 1)));
+}
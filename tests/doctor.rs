@@ -0,0 +1,74 @@
+#![cfg(unix)]
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::process::Command;
+
+// Runs doctor with only `path` to look for tools in, giving what it
+// printed to stdout and stderr
+fn doctor(path: &Path) -> (String, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_rlang"))
+        .arg("doctor")
+        .env("PATH", path)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    (
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+// A directory of its own for the test `name`
+fn dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("rlang-doctor-test-{}-{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn missing_tools_are_reported_as_problems() {
+    let (stdout, stderr) = doctor(&dir("missing"));
+    assert!(
+        stdout.contains("problem: nasm not found, install it or add it to PATH\n"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("problem: ld not found, install binutils or lld\n"),
+        "{stdout}"
+    );
+    assert!(stdout.contains(" is writable\n"), "{stdout}");
+    assert!(
+        stdout.contains("problem: smoke test failed: Couldn't find nasm, is it installed?\n"),
+        "{stdout}"
+    );
+    assert!(stderr.ends_with("checks failed\n"), "{stderr}");
+}
+
+#[test]
+fn found_tools_are_reported_with_their_version() {
+    let dir = dir("found");
+    for (tool, version) in [
+        ("nasm", "NASM version 2.16.01"),
+        ("ld", "GNU ld 2.42\n  elf_x86_64"),
+    ] {
+        let path = dir.join(tool);
+        fs::write(&path, format!("#!/bin/sh\necho '{version}'\n")).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+    let (stdout, _) = doctor(&dir);
+    let nasm = format!(
+        "ok:      nasm at {} (NASM version 2.16.01)\n",
+        dir.join("nasm").display()
+    );
+    assert!(stdout.contains(&nasm), "{stdout}");
+    let ld = format!(
+        "ok:      linker at {} (GNU ld 2.42)\n",
+        dir.join("ld").display()
+    );
+    assert!(stdout.contains(&ld), "{stdout}");
+    // The fake nasm doesn't assemble anything
+    assert!(stdout.contains("problem: smoke test"), "{stdout}");
+}
@@ -0,0 +1,45 @@
+use rlang::generator::{Entry, Environment};
+use rlang::{lexer, parser};
+
+fn generate(source: &str, entry: Entry) -> Result<String, String> {
+    let (tokens, _) = lexer::lex(source.to_owned());
+    let (ast, _) = parser::parse(source, tokens).map_err(|err| format!("{err:?}"))?;
+    let mut env = Environment::new();
+    env.entry = entry;
+    ast.generate(&mut env).map_err(|err| format!("{err:?}"))
+}
+
+#[test]
+fn top_level_code_starts_at_the_entry_symbol() {
+    let code = generate("1 + 2;", Entry::Symbol("begin".to_owned())).unwrap();
+    assert!(
+        code.contains("global begin:function (begin.end - begin)\nbegin:"),
+        "{code}"
+    );
+    assert!(!code.contains("_start"), "{code}");
+
+    let err = generate(
+        "fn begin() -> int { return 1; }\nbegin();",
+        Entry::Symbol("begin".to_owned()),
+    )
+    .unwrap_err();
+    assert_eq!(err, "FunctionAlreadyExists");
+}
+
+#[test]
+fn freestanding_programs_export_their_functions() {
+    let code = generate(
+        "fn add(int a, int b) -> int { return a + b; }",
+        Entry::Freestanding,
+    )
+    .unwrap();
+    assert!(
+        code.contains("global add:function (add.end - add)\nadd:"),
+        "{code}"
+    );
+    assert!(!code.contains("_start"), "{code}");
+    assert!(!code.contains("syscall"), "{code}");
+
+    let err = generate("int x = 1;", Entry::Freestanding).unwrap_err();
+    assert_eq!(err, "StatementOutsideFunction");
+}
@@ -0,0 +1,607 @@
+use rlang::features::Features;
+use rlang::passes::PassManager;
+
+// What compiling `source` fails with, without where in the source that is,
+// which `errors_point_at_the_source` checks
+fn compile(source: &str) -> Result<String, String> {
+    report(source).map_err(|err| match err.split_once("\n  --> ") {
+        Some((message, _)) => message.to_owned(),
+        None => err,
+    })
+}
+
+fn report(source: &str) -> Result<String, String> {
+    rlang::compile(source, &Features::default(), &mut PassManager::new())
+}
+
+// Struct literals used to generate nothing at all outside of declarations
+#[test]
+fn struct_literal_outside_declaration() {
+    let declaration = "struct vec2 { int x; int y; };\nvec2 v = { 1, 2 };\n";
+    assert!(compile(declaration).is_ok());
+
+    for stmt in [
+        "int x = 1 + { 3, 4 };",
+        "fn f(int a) -> int { a; }\nf({ 3, 4 });",
+    ] {
+        let err = compile(&format!("{declaration}{stmt}")).unwrap_err();
+        assert_eq!(err, "StructLiteralOutsideDeclaration", "{stmt}");
+    }
+}
+
+#[test]
+fn return_outside_function() {
+    assert_eq!(compile("return 1;").unwrap_err(), "ReturnOutsideFunction");
+    assert!(compile("fn f() -> int { return 1; }\nf();").is_ok());
+}
+
+#[test]
+fn functions_are_declared_once() {
+    let err =
+        compile("fn f() -> int { return 1; }\nfn f() -> int { return 2; }\nf();").unwrap_err();
+    assert_eq!(err, "FunctionAlreadyExists");
+    assert_eq!(compile("g();").unwrap_err(), "FunctionDoesNotExist");
+}
+
+#[test]
+fn calls_need_a_function() {
+    assert_eq!(compile("int x = 1;\nx();").unwrap_err(), "NotAFunction");
+}
+
+#[test]
+fn integer_operators_reject_floats() {
+    assert_eq!(compile("1.5 % 2;").unwrap_err(), "NotAnInteger");
+    assert_eq!(compile("~1.5;").unwrap_err(), "NotAnInteger");
+    assert_eq!(compile("float f = 1.5;\n~f;").unwrap_err(), "NotAnInteger");
+    assert_eq!(
+        compile("float f = 1.5;\n1 << f;").unwrap_err(),
+        "NotAnInteger"
+    );
+}
+
+#[test]
+fn members_need_a_struct_property() {
+    let declaration = "struct vec2 { int x; int y; };\nvec2 v = { 1, 2 };\n";
+    assert!(compile(&format!("{declaration}v.y;")).is_ok());
+    assert_eq!(
+        compile(&format!("{declaration}v.z;")).unwrap_err(),
+        "PropertyDoesNotExist"
+    );
+    assert_eq!(compile("int x = 1;\nx.y;").unwrap_err(), "NotAStruct");
+}
+
+#[test]
+fn struct_literals_initialize_every_property_once() {
+    let declaration = "struct vec2 { int x; int y; };\n";
+    for (literal, err) in [
+        ("{ 1 }", Some("MissingProperty")),
+        ("{ y: 2 }", Some("MissingProperty")),
+        ("{ 1, 2, 3 }", Some("TooManyValues")),
+        ("{ y: 2, 3 }", Some("TooManyValues")),
+        ("{ z: 1, y: 2 }", Some("PropertyDoesNotExist")),
+        ("{ 1, x: 2 }", Some("DuplicateProperty")),
+        ("{ y: 2, x: 1 }", None),
+    ] {
+        let result = compile(&format!("{declaration}vec2 v = {literal};"));
+        assert_eq!(result.err().as_deref(), err, "{literal}");
+    }
+}
+
+#[test]
+fn only_known_attributes_are_accepted() {
+    assert!(compile("@section(\".init\") fn f() -> int { return 1; }\nf();").is_ok());
+    assert_eq!(
+        compile("@inline fn f() -> int { return 1; }\nf();").unwrap_err(),
+        "UnknownAttribute(\"inline\")"
+    );
+    assert_eq!(
+        compile("@section(\".init) fn f() {}").unwrap_err(),
+        "Unterminated string"
+    );
+}
+
+#[test]
+fn defaults_are_constants() {
+    let declaration = "struct vec2 { int x = 1; int y = -2; };\n";
+    assert!(compile(&format!("{declaration}vec2 v = {{}};")).is_ok());
+    assert!(compile(&format!("{declaration}vec2 v = {{ y: 3 }};")).is_ok());
+    assert_eq!(
+        compile("int a = 1;\nstruct vec2 { int x = a; int y; };").unwrap_err(),
+        "DefaultNotConstant"
+    );
+}
+
+#[test]
+fn intrinsics_take_a_fixed_number_of_arguments() {
+    assert_eq!(
+        compile("volatile_store(1);").unwrap_err(),
+        "WrongArgumentCount"
+    );
+    assert_eq!(
+        compile("syscall(1, 2, 3, 4, 5, 6, 7, 8);").unwrap_err(),
+        "WrongArgumentCount"
+    );
+    // The program's own functions shadow them
+    assert!(compile("fn volatile_load() -> int { return 1; }\nvolatile_load();").is_ok());
+}
+
+#[test]
+fn structs_are_copied_from_the_same_struct() {
+    let declarations = "struct vec2 { int x; int y; };\nstruct pair { int a; int b; bool c; };\n";
+    assert!(compile(&format!("{declarations}vec2 a = {{ 1, 2 }};\nvec2 b = a;")).is_ok());
+    assert_eq!(
+        compile(&format!(
+            "{declarations}pair a = {{ 1, 2, true }};\nvec2 b = a;"
+        ))
+        .unwrap_err(),
+        "MismatchedStruct"
+    );
+    assert_eq!(
+        compile(&format!("{declarations}vec2 b = 1 + 2;")).unwrap_err(),
+        "CannotAssignSingleValuetoStruct"
+    );
+}
+
+#[test]
+fn returned_structs_have_the_declared_type() {
+    let declarations = "struct vec2 { int x; int y; };\nstruct pair { int a; int b; bool c; };\n";
+    assert!(compile(&format!(
+        "{declarations}fn f() -> vec2 {{ return {{ 1, 2 }}; }}\nvec2 v = f();"
+    ))
+    .is_ok());
+    assert_eq!(
+        compile(&format!(
+            "{declarations}fn f() -> pair {{ return {{ 1, 2, true }}; }}\nvec2 v = f();"
+        ))
+        .unwrap_err(),
+        "MismatchedStruct"
+    );
+    assert_eq!(
+        compile(&format!(
+            "{declarations}fn f() -> vec2 {{ return 1; }}\nf();"
+        ))
+        .unwrap_err(),
+        "CannotAssignSingleValuetoStruct"
+    );
+}
+
+#[test]
+fn struct_arguments_have_the_parameter_type() {
+    let declarations = "struct vec2 { int x; int y; };\nstruct pair { int a; int b; bool c; };\nfn f(vec2 v) -> int { return v.x; }\n";
+    assert!(compile(&format!("{declarations}vec2 v = {{ 1, 2 }};\nf(v);")).is_ok());
+    // Structs are left to the generator by the call checker
+    assert!(
+        report(&format!("{declarations}pair p = {{ 1, 2, true }};\nf(p);"))
+            .unwrap_err()
+            .starts_with("MismatchedStruct\n  --> <source>:5:1")
+    );
+    assert_eq!(
+        compile(&format!(
+            "{declarations}fn g() -> pair {{ return {{ 1, 2, true }}; }}\nf(g());"
+        ))
+        .unwrap_err(),
+        "MismatchedStruct"
+    );
+    assert_eq!(
+        compile(&format!("{declarations}f(1);")).unwrap_err(),
+        "CannotAssignSingleValuetoStruct"
+    );
+    assert_eq!(
+        compile(&format!("{declarations}f(1 + 1);")).unwrap_err(),
+        "CannotAssignSingleValuetoStruct"
+    );
+}
+
+#[test]
+fn arrays_are_not_values() {
+    assert!(compile("int a[4];\nint b[1];").is_ok());
+    assert_eq!(compile("int a[0];").unwrap_err(), "EmptyArray");
+    assert_eq!(
+        compile("int a[4];\nint a[2];").unwrap_err(),
+        "VariableAlreadyExists"
+    );
+    assert_eq!(
+        compile("int a[4];\nint x = a;").unwrap_err(),
+        "MismatchedTypes"
+    );
+}
+
+#[test]
+fn indices_are_integers_within_the_array() {
+    let declarations = "int a[4];\nint x = 1;\n";
+    assert!(compile(&format!("{declarations}a[3] = a[x];")).is_ok());
+    assert_eq!(
+        compile(&format!("{declarations}a[4] = 1;")).unwrap_err(),
+        "IndexOutOfBounds"
+    );
+    assert_eq!(
+        compile(&format!("{declarations}x[0] = 1;")).unwrap_err(),
+        "NotAnArray"
+    );
+    assert_eq!(
+        compile(&format!("{declarations}a[1.5] = 1;")).unwrap_err(),
+        "NotAnInteger"
+    );
+}
+
+#[test]
+fn constant_zero_divisors() {
+    assert_eq!(compile("int x = 1 / 0;").unwrap_err(), "DivisionByZero");
+    assert_eq!(
+        compile("int x = 1;\nx %= 0;").unwrap_err(),
+        "DivisionByZero"
+    );
+    // Only known at run time
+    assert!(compile("int x = 0;\nint y = 1 / x;").is_ok());
+    // Float division by zero is infinity, not an error
+    assert!(compile("float x = 1.5 / 0;").is_ok());
+}
+
+#[test]
+fn every_dimension_is_bounded() {
+    let declaration = "int grid[2][3];\nint i = 1;\n";
+    assert!(compile(&format!("{declaration}grid[1][2] = grid[i][i];")).is_ok());
+    assert_eq!(compile("int grid[2][0];").unwrap_err(), "EmptyArray");
+    assert_eq!(
+        compile(&format!("{declaration}grid[i][3] = 1;")).unwrap_err(),
+        "IndexOutOfBounds"
+    );
+    assert_eq!(
+        compile(&format!("{declaration}grid[i] = 1;")).unwrap_err(),
+        "NotAScalar"
+    );
+    assert_eq!(
+        compile(&format!("{declaration}grid[1][2][0] = 1;")).unwrap_err(),
+        "NotAnArray"
+    );
+}
+
+#[test]
+fn struct_elements_have_the_element_type() {
+    let declarations = "struct vec2 { int x; int y; };\nstruct pair { int a; int b; bool c; };\nvec2 points[2];\nint i = 1;\n";
+    assert!(compile(&format!(
+        "{declarations}vec2 v = points[i];\npoints[0] = v;"
+    ))
+    .is_ok());
+    assert_eq!(
+        compile(&format!("{declarations}pair p = points[i];")).unwrap_err(),
+        "MismatchedStruct"
+    );
+    assert_eq!(
+        compile(&format!("{declarations}points[i] = 1;")).unwrap_err(),
+        "CannotAssignSingleValuetoStruct"
+    );
+    assert_eq!(
+        compile(&format!("{declarations}points[i].z = 1;")).unwrap_err(),
+        "PropertyDoesNotExist"
+    );
+}
+
+#[test]
+fn only_pointers_are_dereferenced() {
+    let declarations = "int x = 1;\nint* p = &x;\n";
+    assert!(compile(&format!("{declarations}int** pp = &p;\n**pp = *p + 1;")).is_ok());
+    assert_eq!(
+        compile(&format!("{declarations}*x = 1;")).unwrap_err(),
+        "NotAPointer"
+    );
+    assert_eq!(
+        compile(&format!("{declarations}p = &1;")).unwrap_err(),
+        "NotAnLvalue"
+    );
+    assert_eq!(
+        compile(&format!("{declarations}float* f = &y;")).unwrap_err(),
+        "VariableDoesNotExist"
+    );
+}
+
+#[test]
+fn pointers_only_move_by_integers() {
+    let declarations = "int a[2];\nbool b[2];\nint* p = &a[0];\nint* q = &a[1];\n";
+    assert!(compile(&format!(
+        "{declarations}int n = q - p;\n*(p + n) = *(1 + p);"
+    ))
+    .is_ok());
+    assert_eq!(
+        compile(&format!("{declarations}p + q;")).unwrap_err(),
+        "NotAnInteger"
+    );
+    assert_eq!(
+        compile(&format!("{declarations}1 - p;")).unwrap_err(),
+        "NotAnInteger"
+    );
+    assert_eq!(
+        compile(&format!("{declarations}p - &b[0];")).unwrap_err(),
+        "MismatchedPointers"
+    );
+}
+
+#[test]
+fn values_convert_to_where_they_are_stored() {
+    let declarations = "int a[2];\nint* p = a;\nfloat f = 1;\n";
+    assert!(compile(&format!("{declarations}int i = f;\nbool b = p;")).is_ok());
+    assert_eq!(
+        compile(&format!("{declarations}bool* q = p;")).unwrap_err(),
+        "MismatchedTypes"
+    );
+    assert_eq!(
+        compile(&format!("{declarations}int i = p;")).unwrap_err(),
+        "MismatchedTypes"
+    );
+    assert_eq!(
+        compile(&format!(
+            "{declarations}fn g(int* q) -> int {{ return 1; }}\ng(g);"
+        ))
+        .unwrap_err(),
+        "MismatchedTypes"
+    );
+}
+
+#[test]
+fn null_is_only_a_pointer() {
+    let declarations = "int x = 1;\nint* p = null;\n";
+    assert!(compile(&format!(
+        "{declarations}p = &x;\nbool set = p != null;\nbool same = null == p;\nbool empty = p;"
+    ))
+    .is_ok());
+    assert_eq!(
+        compile(&format!("{declarations}int i = null;")).unwrap_err(),
+        "MismatchedTypes"
+    );
+    assert_eq!(
+        compile(&format!("{declarations}bool zero = p == 0;")).unwrap_err(),
+        "MismatchedTypes"
+    );
+    assert_eq!(
+        compile(&format!("{declarations}bool* b = null;\np == b;")).unwrap_err(),
+        "MismatchedTypes"
+    );
+}
+
+#[test]
+fn casts_convert_between_numbers_and_pointers() {
+    let declarations =
+        "struct vec2 { int x; int y; };\nvec2 v = { 1, 2 };\nint* p = &v.x;\nfloat f = 1.5;\n";
+    assert!(compile(&format!(
+        "{declarations}int n = p as int;\nbool* b = p as bool*;\nint* q = n as int*;\nint i = f as int + true as int;"
+    ))
+    .is_ok());
+    assert_eq!(
+        compile(&format!("{declarations}float* g = f as float*;")).unwrap_err(),
+        "InvalidCast"
+    );
+    assert_eq!(
+        compile(&format!("{declarations}float g = p as float;")).unwrap_err(),
+        "InvalidCast"
+    );
+    assert_eq!(
+        compile(&format!("{declarations}int x = v as int;")).unwrap_err(),
+        "InvalidCast"
+    );
+    assert_eq!(
+        compile(&format!("{declarations}int x = f as long;")).unwrap_err(),
+        "DatatypeDoesNotExist"
+    );
+}
+
+#[test]
+fn sizeof_needs_a_type_or_a_value() {
+    let declarations = "struct vec2 { int x; int y; };\nvec2 v = { 1, 2 };\n";
+    assert!(compile(&format!(
+        "{declarations}int a = sizeof(vec2) + sizeof(vec2*) + sizeof(v) + sizeof(v.x * 2);"
+    ))
+    .is_ok());
+    assert_eq!(
+        compile(&format!("{declarations}int a = sizeof(vec3);")).unwrap_err(),
+        "DatatypeDoesNotExist"
+    );
+    assert_eq!(
+        compile(&format!("{declarations}int a = sizeof(v.z);")).unwrap_err(),
+        "PropertyDoesNotExist"
+    );
+}
+
+#[test]
+fn enum_members_are_ints_of_their_enum() {
+    let declarations = "enum color { red, green, blue };\nenum shape { circle, square };\n";
+    assert!(compile(&format!(
+        "{declarations}color c = blue;\nint i = c + green;\nbool b = red;\nfloat f = c;\nshape s = 1 as shape;"
+    ))
+    .is_ok());
+    assert_eq!(
+        compile(&format!("{declarations}color c = 1;")).unwrap_err(),
+        "MismatchedTypes"
+    );
+    assert_eq!(
+        compile(&format!("{declarations}color c = circle;")).unwrap_err(),
+        "MismatchedTypes"
+    );
+    assert_eq!(
+        compile(&format!("{declarations}enum color {{ cyan }};")).unwrap_err(),
+        "DatatypeAlreadyExists"
+    );
+    assert_eq!(
+        compile("enum color { red, green, red };").unwrap_err(),
+        "VariableAlreadyExists"
+    );
+}
+
+#[test]
+fn enum_values_are_distinct_ints() {
+    assert!(compile("enum e { a = -1, b, c = 2147483647 };\nint x = b;").is_ok());
+    assert_eq!(
+        compile("enum e { a = 1, b = 0, c };").unwrap_err(),
+        "DuplicateEnumValue"
+    );
+    assert_eq!(
+        compile("enum e { a = 2147483648 };").unwrap_err(),
+        "EnumValueOutOfRange"
+    );
+    assert_eq!(
+        compile("enum e { a = -2147483649 };").unwrap_err(),
+        "EnumValueOutOfRange"
+    );
+    // Counting up past the last value overflows too
+    assert_eq!(
+        compile("enum e { a = 2147483647, b };").unwrap_err(),
+        "EnumValueOutOfRange"
+    );
+    assert_eq!(
+        compile("enum e { a = x };").unwrap_err(),
+        "ExpectedToken(Integer)"
+    );
+}
+
+#[test]
+fn union_literals_give_one_property() {
+    let declaration = "union number { int i; float f; };\n";
+    assert!(compile(&format!(
+        "{declaration}number n = {{ f: 1.5 }};\nnumber m = n;"
+    ))
+    .is_ok());
+    assert_eq!(
+        compile(&format!("{declaration}number n = {{ i: 1, f: 1.5 }};")).unwrap_err(),
+        "TooManyValues"
+    );
+    assert_eq!(
+        compile(&format!("{declaration}number n = {{}};")).unwrap_err(),
+        "MissingProperty"
+    );
+    assert_eq!(
+        compile("union number { int i = 1; float f; };").unwrap_err(),
+        "DefaultInUnion"
+    );
+    assert_eq!(
+        compile(&format!(
+            "{declaration}struct pair {{ int i; float f; }};\npair p = {{ 1, 1.5 }};\nnumber n = p;"
+        ))
+        .unwrap_err(),
+        "MismatchedStruct"
+    );
+}
+
+#[test]
+fn bitfields_are_narrow_integers() {
+    assert!(compile("struct s { int a : 3; bool b : 1; };\nunion u { int a : 3; };").is_ok());
+    for declaration in [
+        "struct s { int a : 0; };",
+        "struct s { int a : 33; };",
+        "struct s { bool a : 9; };",
+        "struct s { float a : 3; };",
+        "union u { int* a : 3; };",
+    ] {
+        assert_eq!(compile(declaration).unwrap_err(), "InvalidBitfield");
+    }
+    assert_eq!(
+        compile("struct s { int a : 3; };\ns x = { 1 };\nint* p = &x.a;").unwrap_err(),
+        "NotAnLvalue"
+    );
+}
+
+#[test]
+fn char_literals_are_one_byte() {
+    assert!(compile("char c = '\\'';\nint i = c + '\\n';\ni;").is_ok());
+    assert_eq!(
+        compile("char c = 'ab';").unwrap_err(),
+        "Invalid character 'ab'"
+    );
+    assert_eq!(compile("char c = '';").unwrap_err(), "Invalid character ''");
+    assert_eq!(
+        compile("char c = '\\q';").unwrap_err(),
+        "Invalid character '\\q'"
+    );
+    assert_eq!(
+        compile("char c = 'é';").unwrap_err(),
+        "Invalid character 'é'"
+    );
+    assert_eq!(
+        compile("char c = 'a;\nc;").unwrap_err(),
+        "Invalid character 'a;"
+    );
+}
+
+// The parser used to panic when the file ended in the middle of a statement
+#[test]
+fn truncated_programs_are_errors() {
+    for source in [
+        "",
+        "x",
+        "1 +",
+        "int x = ",
+        "{ int x = 1;",
+        "fn f(",
+        "if (1",
+        "struct a { int x;",
+    ] {
+        assert_eq!(compile(source).unwrap_err(), "UnexpectedEof", "{source:?}");
+    }
+}
+
+#[test]
+fn integers_too_large_for_their_type() {
+    assert_eq!(compile("99999999999;").unwrap_err(), "InvalidToken");
+    assert_eq!(
+        compile("int a[99999999999999999999];\n1;").unwrap_err(),
+        "InvalidToken"
+    );
+}
+
+// Every error says where it is and shows the source there, whether the
+// lexer, the parser or the generator finds it
+#[test]
+fn errors_point_at_the_source() {
+    assert_eq!(
+        report("int a = $;\nint b = #;\n").unwrap_err(),
+        "Illegal character '$'
+  --> <source>:1:9
+  |
+1 | int a = $;
+  |         ^
+error: Illegal character '#'
+  --> <source>:2:9
+  |
+2 | int b = #;
+  |         ^"
+    );
+    assert_eq!(
+        report("float f = 1.2.3;\n").unwrap_err(),
+        "Invalid float 1.2.3
+  --> <source>:1:11
+  |
+1 | float f = 1.2.3;
+  |           ^^^^^"
+    );
+    assert_eq!(
+        report("int x = 1\nint y = 2;\n").unwrap_err(),
+        "ExpectedToken(Semicolon)
+  --> <source>:2:1
+  |
+2 | int y = 2;
+  | ^^^"
+    );
+    // The end of the file is right after the last token
+    assert_eq!(
+        report("int x =\n").unwrap_err(),
+        "UnexpectedEof
+  --> <source>:1:8
+  |
+1 | int x =
+  |        ^"
+    );
+    assert_eq!(
+        report("int x = 1;\nx = 2 + y;\n").unwrap_err(),
+        "VariableDoesNotExist
+  --> <source>:2:9
+  |
+2 | x = 2 + y;
+  |         ^"
+    );
+    assert_eq!(
+        report("fn f() -> int { return 1; }\nfn f() -> int { return 2; }\nf();\n").unwrap_err(),
+        "FunctionAlreadyExists
+  --> <source>:2:1
+  |
+2 | fn f() -> int { return 2; }
+  | ^^^^^^^^^^^^^^^^^^^^^^^^^^^"
+    );
+}
@@ -0,0 +1,110 @@
+use rlang::passes::PassManager;
+use rlang::toolchain;
+use std::fs;
+use std::process::Command;
+
+// Builds and runs every program in examples/, checking the exit code that's
+// documented in its `EXIT:` comment. They're built with the sanitizer and
+// at -O2 as well, neither of which may change how they run. Programs are
+// linked with the libraries of their `LINK:` comments, and their output,
+// which goes to a pipe, has to be the lines of their `OUTPUT:` comments
+#[test]
+fn examples() {
+    let (Some(nasm), Some(ld)) = (toolchain::assembler(), toolchain::linker()) else {
+        eprintln!("skipping examples, nasm or ld is missing");
+        return;
+    };
+    if !cfg!(target_os = "linux") {
+        eprintln!("skipping examples, they can only run on Linux");
+        return;
+    }
+
+    let dir = std::env::temp_dir().join(format!("rlang-examples-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let mut examples: Vec<_> = fs::read_dir("examples")
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "rl"))
+        .collect();
+    examples.sort();
+    assert!(!examples.is_empty());
+
+    for example in examples {
+        let source = fs::read_to_string(&example).unwrap();
+        let expected: i32 = source
+            .lines()
+            .find_map(|line| line.split_once("EXIT:"))
+            .unwrap_or_else(|| panic!("{} has no EXIT: comment", example.display()))
+            .1
+            .trim()
+            .parse()
+            .unwrap();
+        let directives = |name: &str| -> Vec<String> {
+            source
+                .lines()
+                .filter_map(|line| line.split_once(&format!("// {name}:")))
+                .map(|(_, value)| value.trim().to_owned())
+                .collect()
+        };
+        let libraries = directives("LINK");
+        let output: String = directives("OUTPUT")
+            .iter()
+            .map(|line| format!("{line}\n"))
+            .collect();
+
+        for (sanitize, optimize) in [(false, 0), (true, 0), (false, 2)] {
+            let asm = rlang::compile_with(
+                &source,
+                &Default::default(),
+                &mut PassManager::new(),
+                |env| {
+                    env.sanitize = sanitize;
+                    env.optimize = optimize;
+                    env.libc = libraries.iter().any(|lib| lib == "c");
+                },
+            )
+            .unwrap_or_else(|err| panic!("{}: {}", example.display(), err));
+            let name = example.file_stem().unwrap();
+            let asm_path = dir.join(name).with_extension("asm");
+            let object = dir.join(name).with_extension("o");
+            let binary = dir.join(name);
+            fs::write(&asm_path, asm).unwrap();
+
+            let assembled = Command::new(&nasm)
+                .arg("-felf64")
+                .arg(&asm_path)
+                .arg("-o")
+                .arg(&object)
+                .status()
+                .unwrap();
+            assert!(assembled.success(), "{}: nasm failed", example.display());
+            let mut link = Command::new(&ld);
+            link.arg(&object).arg("-o").arg(&binary);
+            if !libraries.is_empty() {
+                link.args(["-dynamic-linker", toolchain::DYNAMIC_LINKER]);
+            }
+            let linked = link
+                .args(libraries.iter().map(|lib| format!("-l{lib}")))
+                .status()
+                .unwrap();
+            assert!(linked.success(), "{}: ld failed", example.display());
+
+            let run = Command::new(&binary).output().unwrap();
+            assert_eq!(
+                String::from_utf8_lossy(&run.stdout),
+                output,
+                "{} printed the wrong output (sanitize: {sanitize}, -O{optimize})",
+                example.display()
+            );
+            assert_eq!(
+                run.status.code(),
+                Some(expected),
+                "{} exited with the wrong code (sanitize: {sanitize}, -O{optimize})",
+                example.display()
+            );
+        }
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,37 @@
+use rlang::features::{Edition, Feature, FeatureError, Features};
+use rlang::passes::PassManager;
+
+// goto shipped before editions, programs using it can't need a flag
+#[test]
+fn shipped_syntax_needs_no_flag() {
+    let source = "int x = 0;\nagain:\nx++;\nif (x < 3) goto again;\nx;";
+    assert!(rlang::compile(source, &Features::default(), &mut PassManager::new()).is_ok());
+    assert!(Features::default().is_enabled(Feature::Goto));
+    assert!(Features::new(Edition::E2024, []).is_enabled(Feature::Goto));
+}
+
+#[test]
+fn gate_errors_say_how_to_enable_the_feature() {
+    let err = FeatureError {
+        feature: Feature::Goto,
+        start_index: 0,
+    };
+    assert_eq!(
+        err.to_string(),
+        "goto requires edition 2024, enable it with --edition 2024 or --feature goto"
+    );
+}
+
+#[test]
+fn unknown_editions_and_features_are_rejected() {
+    assert_eq!("2024".parse::<Edition>(), Ok(Edition::E2024));
+    assert_eq!(
+        "2023".parse::<Edition>().unwrap_err(),
+        "Unknown edition 2023, the latest is 2024"
+    );
+    assert_eq!("goto".parse::<Feature>(), Ok(Feature::Goto));
+    assert_eq!(
+        "generics".parse::<Feature>().unwrap_err(),
+        "Unknown feature generics"
+    );
+}
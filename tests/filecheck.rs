@@ -0,0 +1,24 @@
+use rlang::filecheck;
+use rlang::passes::PassManager;
+use std::fs;
+
+// Compiles every fixture in tests/codegen and checks the generated assembly
+// against the CHECK directives in its comments
+#[test]
+fn codegen_fixtures() {
+    let mut fixtures: Vec<_> = fs::read_dir("tests/codegen")
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    fixtures.sort();
+    assert!(!fixtures.is_empty());
+
+    for fixture in fixtures {
+        let source = fs::read_to_string(&fixture).unwrap();
+        let asm = rlang::compile(&source, &Default::default(), &mut PassManager::new())
+            .unwrap_or_else(|err| panic!("{}: {}", fixture.display(), err));
+        if let Err(err) = filecheck::check(&source, &asm) {
+            panic!("{}: {}\n\n{}", fixture.display(), err, asm);
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use rlang::log::{Filter, Level};
+use std::fs;
+use std::process::Command;
+
+#[test]
+fn messages_below_the_level_are_dropped() {
+    let filter: Filter = "parser=debug".parse().unwrap();
+    assert!(filter.enabled("parser", Level::Error));
+    assert!(filter.enabled("parser", Level::Debug));
+    assert!(!filter.enabled("parser", Level::Trace));
+}
+
+#[test]
+fn targets_override_the_default() {
+    let filter: Filter = "warn,codegen=trace,lexer=error".parse().unwrap();
+    assert!(filter.enabled("codegen", Level::Trace));
+    assert!(!filter.enabled("lexer", Level::Warn));
+    assert!(filter.enabled("parser", Level::Warn));
+    assert!(!filter.enabled("parser", Level::Info));
+}
+
+#[test]
+fn nothing_is_logged_without_a_level() {
+    let filter: Filter = "parser=trace".parse().unwrap();
+    assert!(!filter.enabled("codegen", Level::Error));
+    assert!(!Filter::default().enabled("parser", Level::Error));
+}
+
+#[test]
+fn unknown_levels_are_rejected() {
+    assert_eq!(
+        "parser=loud".parse::<Filter>().unwrap_err(),
+        "Unknown log level loud"
+    );
+}
+
+// What building a program with a function that gets pruned logs with the
+// filter `log`
+fn logged(log: &str) -> String {
+    let dir = std::env::temp_dir().join(format!("rlang-log-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(format!("{log}.rl"));
+    fs::write(&path, "fn unused() -> int { return 1; }\n2;\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_rlang"))
+        .args(["--log", log, "build"])
+        .arg(&path)
+        .arg("-o")
+        .arg(dir.join(log))
+        .output()
+        .unwrap();
+    String::from_utf8(output.stderr).unwrap()
+}
+
+#[test]
+fn build_logs_only_enabled_messages() {
+    assert!(logged("callgraph=debug").contains("[DEBUG callgraph] pruned fn unused()"));
+    let info = logged("callgraph=info");
+    assert!(!info.contains("[DEBUG"), "{info}");
+    let other = logged("parser=debug");
+    assert!(!other.contains("callgraph"), "{other}");
+}
@@ -0,0 +1,69 @@
+use rlang::generator::{Entry, Environment};
+use rlang::passes::PassManager;
+use rlang::{lexer, parser};
+
+fn compile(source: &str, entry: Entry) -> String {
+    rlang::compile_with(
+        source,
+        &Default::default(),
+        &mut PassManager::new(),
+        |env| {
+            env.optimize = 2;
+            env.entry = entry;
+        },
+    )
+    .unwrap()
+}
+
+#[test]
+fn small_functions_take_their_arguments_in_registers() {
+    let code = compile(
+        "fn add(int a, int b) -> int { return a + b; }\nadd(1, 2);",
+        Entry::default(),
+    );
+    // No frame, the parameters are read straight out of the registers
+    assert!(code.contains("add:\n    movsxd rax, edi\n"), "{code}");
+    assert!(code.contains("movsxd rax, esi"), "{code}");
+    assert!(!code.contains("add:\n    push rbp"), "{code}");
+    // The caller doesn't realign the stack either
+    assert!(
+        code.contains("mov rsi, rax\n    pop rdi\n    call add"),
+        "{code}"
+    );
+    assert!(!code.contains("and rsp, -16"), "{code}");
+
+    let plain = rlang::compile(
+        "fn add(int a, int b) -> int { return a + b; }\nadd(1, 2);",
+        &Default::default(),
+        &mut PassManager::new(),
+    );
+    assert!(!plain.unwrap().contains("movsxd rax, edi"));
+}
+
+#[test]
+fn other_functions_keep_the_standard_convention() {
+    // Locals need a frame
+    let code = compile(
+        "fn f(int a) -> int { int b = a; return b; }\nf(1);",
+        Entry::default(),
+    );
+    assert!(code.contains("f:\n    push rbp"), "{code}");
+
+    // Something might call it through the pointer
+    let code = compile(
+        "typedef fn(int) -> int unary;\nfn f(int a) -> int { return a; }\nunary g = f;\ng(1);",
+        Entry::default(),
+    );
+    assert!(code.contains("f:\n    push rbp"), "{code}");
+
+    // Exported functions are called from outside. compile() would prune
+    // them since nothing here calls them
+    let source = "fn f(int a) -> int { return a; }";
+    let (tokens, _) = lexer::lex(source.to_owned());
+    let (ast, _) = parser::parse(source, tokens).unwrap();
+    let mut env = Environment::new();
+    env.optimize = 2;
+    env.entry = Entry::Freestanding;
+    let code = ast.generate(&mut env).unwrap();
+    assert!(code.contains("f:\n    push rbp"), "{code}");
+}
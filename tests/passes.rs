@@ -0,0 +1,93 @@
+use rlang::ast::Node;
+use rlang::passes::{Pass, PassManager, PassPoint};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+const SOURCE: &str =
+    "fn used() -> int { return 1; }\nfn unused() -> int { return 2; }\nused();\n41;\n";
+
+// Replaces the value of every top-level integer statement `from` with `to`,
+// and records the functions the program has when it runs
+struct Replace {
+    from: i32,
+    to: i32,
+    functions: Rc<RefCell<Vec<String>>>,
+}
+
+impl Pass for Replace {
+    fn name(&self) -> &str {
+        "replace"
+    }
+
+    fn run(&mut self, program: &mut Node) -> Result<(), String> {
+        let Node::Program { body, .. } = program else {
+            return Err("not a program".to_owned());
+        };
+        for stmt in body {
+            match stmt {
+                Node::Integer { value, .. } if *value == self.from => *value = self.to,
+                Node::FnDecl { name, .. } => self.functions.borrow_mut().push(name.clone()),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+struct Fail;
+
+impl Pass for Fail {
+    fn name(&self) -> &str {
+        "fail"
+    }
+
+    fn run(&mut self, _: &mut Node) -> Result<(), String> {
+        Err("refused".to_owned())
+    }
+}
+
+#[test]
+fn passes_run_at_their_point_and_change_the_program() {
+    let after_parse = Rc::new(RefCell::new(vec![]));
+    let before_codegen = Rc::new(RefCell::new(vec![]));
+    let mut passes = PassManager::new();
+    passes.register(
+        PassPoint::BeforeCodegen,
+        Replace {
+            from: 42,
+            to: 43,
+            functions: before_codegen.clone(),
+        },
+    );
+    passes.register(
+        PassPoint::AfterParse,
+        Replace {
+            from: 41,
+            to: 42,
+            functions: after_parse.clone(),
+        },
+    );
+    let asm = rlang::compile(SOURCE, &Default::default(), &mut passes).unwrap();
+
+    // Passes after parsing see every function, the ones before generating
+    // code only those that can be called
+    assert!(after_parse.borrow().contains(&"unused".to_owned()));
+    assert!(before_codegen.borrow().contains(&"used".to_owned()));
+    assert!(!before_codegen.borrow().contains(&"unused".to_owned()));
+
+    // The AfterParse pass ran first, so the BeforeCodegen one saw its 42
+    assert!(asm.contains("mov rax, 43"), "{asm}");
+    assert!(!asm.contains("mov rax, 41"), "{asm}");
+}
+
+#[test]
+fn failing_passes_stop_the_build() {
+    for point in [PassPoint::AfterParse, PassPoint::BeforeCodegen] {
+        let mut passes = PassManager::new();
+        passes.register(point, Fail);
+        assert_eq!(
+            rlang::compile(SOURCE, &Default::default(), &mut passes).unwrap_err(),
+            "PassError { pass: \"fail\", message: \"refused\" }"
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const SOURCE: &str = "1 + 2;\n";
+
+// An empty directory of its own for the test `name`
+fn dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rlang-paths-{}-{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+// Builds `input` in `dir`, giving what was printed to stderr and whether
+// it succeeded
+fn build(dir: &Path, input: &str, args: &[&str]) -> (String, bool) {
+    let output = Command::new(env!("CARGO_BIN_EXE_rlang"))
+        .current_dir(dir)
+        .arg("build")
+        .arg(input)
+        .args(args)
+        .output()
+        .unwrap();
+    (
+        String::from_utf8(output.stderr).unwrap(),
+        output.status.success(),
+    )
+}
+
+#[test]
+fn refuses_to_overwrite_the_input() {
+    let dir = dir("overwrite");
+    fs::create_dir(dir.join("sub")).unwrap();
+    fs::write(dir.join("prog.rl"), SOURCE).unwrap();
+    for output in ["prog.rl", "./prog.rl", "sub/../prog.rl"] {
+        let (err, ok) = build(&dir, "prog.rl", &["-o", output]);
+        assert!(!ok);
+        assert_eq!(err, "error: Refusing to overwrite the input file prog.rl\n");
+    }
+    assert_eq!(fs::read_to_string(dir.join("prog.rl")).unwrap(), SOURCE);
+}
+
+#[test]
+fn refuses_to_overwrite_the_input_with_an_artifact() {
+    let dir = dir("artifact");
+    fs::write(dir.join("prog.asm"), SOURCE).unwrap();
+    let (err, ok) = build(&dir, "prog.asm", &["-o", "prog"]);
+    assert!(!ok);
+    assert_eq!(
+        err,
+        "error: Building would overwrite the input file prog.asm with prog.asm, pick another output with -o\n"
+    );
+    assert_eq!(fs::read_to_string(dir.join("prog.asm")).unwrap(), SOURCE);
+}
+
+#[test]
+fn reports_a_missing_output_directory() {
+    let dir = dir("missing-dir");
+    fs::write(dir.join("prog.rl"), SOURCE).unwrap();
+    let (err, ok) = build(&dir, "prog.rl", &["-o", "out/prog"]);
+    assert!(!ok);
+    assert_eq!(err, "error: Output directory out does not exist\n");
+}
+
+#[test]
+fn reports_a_missing_input() {
+    let dir = dir("missing-input");
+    let (err, ok) = build(&dir, "prog.rl", &[]);
+    assert!(!ok);
+    assert_eq!(
+        err,
+        "error: Couldn't read prog.rl: no such file or directory\n"
+    );
+}
+
+// The paths are accepted when the assembly is written, which happens
+// before nasm runs, so these don't need nasm
+#[test]
+fn input_without_an_extension_builds_next_to_itself() {
+    let dir = dir("no-extension");
+    fs::write(dir.join("prog"), SOURCE).unwrap();
+    build(&dir, "prog", &[]);
+    assert!(dir.join("_prog.asm").is_file());
+    assert_eq!(fs::read_to_string(dir.join("prog")).unwrap(), SOURCE);
+}
+
+#[test]
+fn output_in_another_directory() {
+    let dir = dir("other-dir");
+    fs::create_dir(dir.join("out")).unwrap();
+    fs::write(dir.join("prog.rl"), SOURCE).unwrap();
+    build(&dir, "prog.rl", &["-o", "out/./prog"]);
+    assert!(dir.join("out/prog.asm").is_file());
+}
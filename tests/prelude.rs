@@ -0,0 +1,30 @@
+use rlang::features::Features;
+use rlang::passes::PassManager;
+
+fn compile(source: &str) -> Result<String, String> {
+    rlang::compile(source, &Features::default(), &mut PassManager::new())
+}
+
+#[test]
+fn only_called_functions_are_added() {
+    let code = compile("1;").unwrap();
+    assert!(
+        !code.contains("exit:") && !code.contains("assert:"),
+        "{code}"
+    );
+
+    // assert calls abort in turn
+    let code = compile("assert(true);").unwrap();
+    assert!(
+        code.contains("assert:") && code.contains("abort:"),
+        "{code}"
+    );
+    assert!(!code.contains("exit:"), "{code}");
+}
+
+#[test]
+fn programs_can_define_prelude_functions() {
+    let code = compile("fn exit(int status) -> int { return status; }\nexit(1);").unwrap();
+    assert_eq!(code.matches("exit:").count(), 1, "{code}");
+    assert!(!code.contains("mov rax, 60\n    push rax"), "{code}");
+}
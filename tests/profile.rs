@@ -0,0 +1,55 @@
+use rlang::generator::{Environment, Instrumentation};
+use rlang::{lexer, parser, profile};
+
+fn generate(source: &str, profile: Option<&str>) -> String {
+    let (tokens, _) = lexer::lex(source.to_owned());
+    let (ast, _) = parser::parse(source, tokens).unwrap();
+    let mut env = Environment::new();
+    env.instrumentation = profile.map(|path| Instrumentation::Profile {
+        path: path.to_owned(),
+    });
+    ast.generate(&mut env).unwrap()
+}
+
+#[test]
+fn profiled_functions_count_their_calls() {
+    let source = "fn twice(int x) -> int { return x * 2; }\ntwice(3);";
+    let code = generate(source, Some("/tmp/out.profile"));
+    assert!(
+        code.contains("_start:\n    push rbp\n    mov rbp, rsp\n    \n    inc qword [rel ..@profile_counters]\n"),
+        "{code}"
+    );
+    assert!(
+        code.contains("inc qword [rel ..@profile_counters+8]"),
+        "{code}"
+    );
+    assert!(code.contains("call ..@profile_dump"), "{code}");
+    assert!(code.contains("..@profile_counters: resq 2"), "{code}");
+
+    let code = generate(source, None);
+    assert!(!code.contains("profile"), "{code}");
+}
+
+#[test]
+fn profiles_are_read_back_in_order() {
+    let mut data = b"_start\nfib\n\n".to_vec();
+    data.extend(1u64.to_le_bytes());
+    data.extend(177u64.to_le_bytes());
+    let counts = profile::parse(&data).unwrap();
+    assert_eq!(
+        counts,
+        vec![("_start".to_owned(), 1), ("fib".to_owned(), 177)]
+    );
+    assert_eq!(
+        profile::report(counts),
+        "function  calls\nfib       177\n_start    1\n"
+    );
+}
+
+#[test]
+fn truncated_profiles_are_rejected() {
+    let mut data = b"_start\nfib\n\n".to_vec();
+    data.extend(1u64.to_le_bytes());
+    assert!(profile::parse(&data).is_err());
+    assert!(profile::parse(b"_start").is_err());
+}
@@ -0,0 +1,90 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+const SOURCE: &str = "struct point { int x; int y; };
+int x = 1;
+point p = { 2, 3 };
+{
+    int x = p.x;
+    x + 1;
+}
+x + p.y;
+";
+
+// Writes `source` to a file of its own and runs a command on it, giving
+// what it printed and whether it succeeded
+fn run(name: &str, source: &str, args: &[&str]) -> (String, bool) {
+    let dir = std::env::temp_dir().join(format!("rlang-refs-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path: PathBuf = dir.join(format!("{name}.rl"));
+    fs::write(&path, source).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_rlang"))
+        .arg(args[0])
+        .arg(&path)
+        .args(&args[1..])
+        .output()
+        .unwrap();
+    let printed =
+        String::from_utf8(output.stdout).unwrap() + &String::from_utf8(output.stderr).unwrap();
+    (
+        printed.replace(path.to_str().unwrap(), "main.rl"),
+        output.status.success(),
+    )
+}
+
+#[test]
+fn refs_stop_at_shadowing_declarations() {
+    let (outer, ok) = run("outer", SOURCE, &["refs", "--line", "8", "--col", "1"]);
+    assert!(ok, "{outer}");
+    assert_eq!(outer, "main.rl:2:5\nmain.rl:8:1\n");
+
+    let (inner, _) = run("inner", SOURCE, &["refs", "--line", "6", "--col", "5"]);
+    assert_eq!(inner, "main.rl:5:9\nmain.rl:6:5\n");
+}
+
+// `p.x` names a property, not the variable x
+#[test]
+fn fields_are_not_variables() {
+    let (refs, _) = run("fields", SOURCE, &["refs", "--line", "2", "--col", "5"]);
+    assert!(!refs.contains("main.rl:5:15"), "{refs}");
+
+    let (def, ok) = run("field", SOURCE, &["def", "--line", "5", "--col", "15"]);
+    assert!(!ok);
+    assert_eq!(def, "error: No variable or type at main.rl:5:15\n");
+}
+
+#[test]
+fn def_finds_the_declaration_in_scope() {
+    let (inner, _) = run("def-inner", SOURCE, &["def", "--line", "6", "--col", "5"]);
+    assert_eq!(inner, "main.rl:5:9\n");
+    let (outer, _) = run("def-outer", SOURCE, &["def", "--line", "8", "--col", "1"]);
+    assert_eq!(outer, "main.rl:2:5\n");
+    let (datatype, _) = run("def-type", SOURCE, &["def", "--line", "3", "--col", "1"]);
+    assert_eq!(datatype, "main.rl:1:8\n");
+}
+
+#[test]
+fn unresolved_names_have_no_definition() {
+    let source = "int x = y;\nx;\n";
+    let (def, ok) = run("unresolved", source, &["def", "--line", "1", "--col", "9"]);
+    assert!(!ok);
+    assert_eq!(def, "error: No variable or type at main.rl:1:9\n");
+}
+
+// Editors ask while the file is half typed, which has to be an error
+// rather than a crash
+#[test]
+fn half_typed_files_are_reported() {
+    for command in ["refs", "def"] {
+        let (printed, ok) = run(
+            command,
+            "int x = 1;\nx +",
+            &[command, "--line", "1", "--col", "5"],
+        );
+        assert!(!ok);
+        assert!(printed.starts_with("error: "), "{printed}");
+        assert!(printed.contains("UnexpectedEof"), "{printed}");
+        assert!(!printed.contains("panicked"), "{printed}");
+    }
+}
@@ -0,0 +1,79 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+// Renames the symbol at `line`:`col` of `source` to `to`, giving the file
+// afterwards and what the command printed
+fn rename(name: &str, source: &str, line: usize, col: usize, to: &str) -> (String, String) {
+    let dir = std::env::temp_dir().join(format!("rlang-rename-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path: PathBuf = dir.join(format!("{name}.rl"));
+    fs::write(&path, source).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_rlang"))
+        .arg("rename")
+        .arg(&path)
+        .args([
+            "--line",
+            &line.to_string(),
+            "--col",
+            &col.to_string(),
+            "--to",
+            to,
+        ])
+        .output()
+        .unwrap();
+    let printed =
+        String::from_utf8(output.stdout).unwrap() + &String::from_utf8(output.stderr).unwrap();
+    (fs::read_to_string(&path).unwrap(), printed)
+}
+
+#[test]
+fn only_the_shadowing_variable_is_renamed() {
+    let source = "int x = 1;\n{\n    int x = 2;\n    x + 1;\n}\nx;\n";
+    let (renamed, printed) = rename("shadowing", source, 4, 5, "inner");
+    assert_eq!(printed, "Renamed 2 references\n");
+    assert_eq!(
+        renamed,
+        "int x = 1;\n{\n    int inner = 2;\n    inner + 1;\n}\nx;\n"
+    );
+}
+
+#[test]
+fn renames_that_change_what_names_refer_to_are_refused() {
+    // `a + b` would refer to the inner b
+    let source = "int a = 1;\n{\n    int b = 2;\n    a + b;\n}\na;\n";
+    let (renamed, printed) = rename("captured", source, 1, 5, "b");
+    assert_eq!(renamed, source);
+    assert_eq!(
+        printed,
+        "error: Renaming a to b would change the meaning of the program\n"
+    );
+
+    // The inner a would hide the outer one from `a;`
+    let source = "int a = 1;\n{\n    int b = 2;\n    a;\n}\n";
+    let (renamed, _) = rename("shadows", source, 3, 9, "a");
+    assert_eq!(renamed, source);
+
+    let (renamed, printed) = rename("invalid", source, 1, 5, "1a");
+    assert_eq!(renamed, source);
+    assert_eq!(printed, "error: 1a is not a valid identifier\n");
+}
+
+// References are found at character indices, which aren't the byte
+// offsets the file is edited at once a multi-byte character comes first
+#[test]
+fn non_ascii_identifiers_are_renamed() {
+    let source = "// größe\nint 日本 = 1;\nint y = 日本 + 2;\ny;\n";
+    let (renamed, printed) = rename("wide", source, 3, 9, "nihon");
+    assert_eq!(printed, "Renamed 2 references\n");
+    assert_eq!(
+        renamed,
+        "// größe\nint nihon = 1;\nint y = nihon + 2;\ny;\n"
+    );
+
+    let (renamed, _) = rename("to-wide", source, 4, 1, "größe");
+    assert_eq!(
+        renamed,
+        "// größe\nint 日本 = 1;\nint größe = 日本 + 2;\ngröße;\n"
+    );
+}
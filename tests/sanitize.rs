@@ -0,0 +1,82 @@
+use rlang::passes::PassManager;
+use rlang::toolchain;
+use std::fs;
+use std::process::Command;
+
+fn compile(source: &str) -> String {
+    rlang::compile_with(
+        source,
+        &Default::default(),
+        &mut PassManager::new(),
+        |env| env.sanitize = true,
+    )
+    .unwrap()
+}
+
+#[test]
+fn variables_are_guarded_by_canaries() {
+    let code = compile("int a = 1;\n{\n    int b = 2;\n}\na;");
+    // The canary sits above the variable and is checked before the program exits
+    assert!(
+        code.contains("mov qword [rbp-8], rcx\n    mov rax, 1"),
+        "{code}"
+    );
+    assert!(code.contains("cmp qword [rbp-8], rcx"), "{code}");
+    // The scope's canary is checked and its slots poisoned when it ends
+    assert!(code.contains("cmp qword [rbp-24], rcx"), "{code}");
+    assert!(
+        code.contains("mov rcx, 6510615555426900570\n    mov qword [rbp-20], rcx\n    mov qword [rbp-28], rcx"),
+        "{code}"
+    );
+    assert!(code.contains("..@sanitizer_fail:"), "{code}");
+
+    let plain = rlang::compile(
+        "int a = 1;\na;",
+        &Default::default(),
+        &mut PassManager::new(),
+    );
+    assert!(!plain.unwrap().contains("sanitizer"));
+}
+
+#[test]
+fn overwritten_canaries_abort_the_program() {
+    let (Some(nasm), Some(ld)) = (toolchain::assembler(), toolchain::linker()) else {
+        eprintln!("skipping sanitizer test, nasm or ld is missing");
+        return;
+    };
+    if !cfg!(target_os = "linux") {
+        eprintln!("skipping sanitizer test, it can only run on Linux");
+        return;
+    }
+
+    // The index is only known at run time, so nothing stops the write
+    let code = compile("int a[2];\nint i = 2;\na[i] = 1;\n0;");
+
+    let dir = std::env::temp_dir().join(format!("rlang-sanitize-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let (asm, object, binary) = (dir.join("x.asm"), dir.join("x.o"), dir.join("x"));
+    fs::write(&asm, code).unwrap();
+    let assembled = Command::new(nasm)
+        .arg("-felf64")
+        .arg(&asm)
+        .arg("-o")
+        .arg(&object)
+        .status()
+        .unwrap();
+    assert!(assembled.success());
+    let linked = Command::new(ld)
+        .arg(&object)
+        .arg("-o")
+        .arg(&binary)
+        .status()
+        .unwrap();
+    assert!(linked.success());
+
+    let output = Command::new(&binary).output().unwrap();
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "sanitizer: the canary above a was overwritten\n"
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,100 @@
+use rlang::source::{self, FileId, SourceMap};
+use rlang::{lexer, parser};
+
+#[test]
+fn indices_convert_to_lines_and_columns_and_back() {
+    let source = "int x = 1;\n\nx + 2;\n";
+    let mut map = SourceMap::new();
+    let file = map.add("main.rl", source);
+
+    assert_eq!(map.position(file, 0), (1, 1));
+    assert_eq!(map.position(file, 4), (1, 5));
+    // The line break belongs to the line it ends
+    assert_eq!(map.position(file, 10), (1, 11));
+    assert_eq!(map.position(file, 11), (2, 1));
+    assert_eq!(map.position(file, 12), (3, 1));
+    assert_eq!(map.location(file, 16), "main.rl:3:5");
+
+    for index in [0, 4, 12, 16] {
+        let (line, col) = map.position(file, index);
+        assert_eq!(map.index(file, line, col), Some(index));
+    }
+    assert_eq!(map.index(file, 1, 11), None);
+    assert_eq!(map.index(file, 2, 1), None);
+    assert_eq!(map.index(file, 0, 1), None);
+    assert_eq!(map.index(file, 1, 0), None);
+    assert_eq!(map.index(file, 5, 1), None);
+}
+
+#[test]
+fn the_last_line_needs_no_line_break() {
+    let mut map = SourceMap::new();
+    let file = map.add("main.rl", "a;\nb;");
+    assert_eq!(map.position(file, 4), (2, 2));
+    assert_eq!(map.index(file, 2, 2), Some(4));
+    assert_eq!(map.index(file, 2, 3), None);
+}
+
+#[test]
+fn columns_are_where_characters_show_up() {
+    let source = "\tx = 1;\n日本 = 2;\na\tb\tc;\n";
+    for (tab_width, columns) in [(8, [9, 6, 9, 17]), (4, [5, 6, 5, 9])] {
+        let mut map = SourceMap::with_tab_width(tab_width);
+        let file = map.add("main.rl", source);
+        // x, =, b and c
+        let indices = [1, 11, 18, 20];
+        let lines = [1, 2, 3, 3];
+        for ((index, line), col) in indices.into_iter().zip(lines).zip(columns) {
+            assert_eq!(map.position(file, index), (line, col));
+            assert_eq!(map.index(file, line, col), Some(index));
+        }
+        // Columns within a tab or a wide character give that character
+        assert_eq!(map.index(file, 1, 2), Some(0));
+        assert_eq!(map.index(file, 2, 2), Some(8));
+        assert_eq!(map.index(file, 2, 3), Some(9));
+    }
+}
+
+#[test]
+fn snippets_underline_their_span() {
+    let mut map = SourceMap::with_tab_width(4);
+    let file = map.add("main.rl", "x;\n\t日 = f(1,\n  2);\n");
+    assert_eq!(
+        map.snippet(file, 7, 9),
+        "  |\n2 |     日 = f(1,\n  |         ^^^"
+    );
+    // Spans going on past the line are underlined to its end
+    assert_eq!(
+        map.snippet(file, 7, 17),
+        "  |\n2 |     日 = f(1,\n  |         ^^^^^"
+    );
+    assert_eq!(source::expand_tabs("a\tb\t\tc", 4), "a   b       c");
+    assert_eq!(source::width('日'), 2);
+}
+
+#[test]
+fn every_file_has_its_own_lines() {
+    let mut map = SourceMap::new();
+    let main = map.add("main.rl", "int x = 1;\nx;");
+    let other = map.add("other.rl", "\n\n\nint y = 2;");
+    assert_eq!((main, other), (FileId(0), FileId(1)));
+
+    assert_eq!(map.location(main, 11), "main.rl:2:1");
+    assert_eq!(map.location(other, 11), "other.rl:4:9");
+    assert_eq!(map.index(main, 4, 1), None);
+    assert_eq!(map.index(other, 4, 1), Some(3));
+}
+
+#[test]
+fn tokens_and_spans_carry_their_file() {
+    let source = "int y = 2;";
+    let (tokens, _) = lexer::lex_file(source.to_owned(), FileId(1));
+    assert!(tokens.iter().all(|token| token.file == FileId(1)));
+
+    let parsed = parser::parse_program(source, tokens, rlang::ast::NodeId(0)).unwrap();
+    let span = parsed.spans.get(parsed.ast.id()).unwrap();
+    assert_eq!(span.file, FileId(1));
+
+    let (tokens, _) = lexer::lex(source.to_owned());
+    assert!(tokens.iter().all(|token| token.file == FileId(0)));
+}
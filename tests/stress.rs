@@ -0,0 +1,29 @@
+use rlang::passes::PassManager;
+use rlang::stress;
+
+// The stress generator is only useful if what it generates compiles
+#[test]
+fn generated_programs_compile() {
+    for seed in 1..=20 {
+        let source = stress::generate(300, 6, seed);
+        if let Err(err) = rlang::compile(&source, &Default::default(), &mut PassManager::new()) {
+            panic!("seed {seed}: {err}\n\n{source}");
+        }
+    }
+}
+
+// Every push in an expression has to be matched by a pop, or the values
+// of later statements end up in the wrong place
+#[test]
+fn generated_programs_keep_the_stack_balanced() {
+    for seed in 1..=20 {
+        let source = stress::generate(300, 6, seed);
+        let asm = rlang::compile(&source, &Default::default(), &mut PassManager::new()).unwrap();
+        let count = |op: &str| {
+            asm.lines()
+                .filter(|line| line.trim().starts_with(op))
+                .count()
+        };
+        assert_eq!(count("push "), count("pop "), "seed {seed}\n\n{source}");
+    }
+}
@@ -0,0 +1,129 @@
+use rlang::ast::{Node, NodeId};
+use rlang::generator::Environment;
+use rlang::parser::{self, Parsed};
+use rlang::source::SourceMap;
+use rlang::{lexer, prelude, resolver, tables};
+
+fn parse(source: &str) -> Parsed {
+    let (tokens, _) = lexer::lex(source.to_owned());
+    parser::parse_program(source, tokens, NodeId(0)).unwrap()
+}
+
+fn ids(node: &Node, ids: &mut Vec<NodeId>) {
+    for child in node.children() {
+        self::ids(child, ids);
+    }
+    ids.push(node.id());
+}
+
+fn all_ids(program: &Node) -> Vec<NodeId> {
+    let mut all = vec![];
+    ids(program, &mut all);
+    all
+}
+
+#[test]
+fn every_node_has_its_own_id() {
+    let parsed =
+        parse("int x = 1;\nfn f(int a) -> int { return a * 2; }\nwhile (x < 3) { x = f(x); }");
+    let mut all = all_ids(&parsed.ast);
+    // Children are finished first, so the program comes last
+    assert_eq!(all.last(), Some(&parsed.ast.id()));
+    assert!(all.windows(2).all(|pair| pair[0] < pair[1]));
+    let count = all.len();
+    all.sort();
+    all.dedup();
+    assert_eq!(all.len(), count);
+    assert_eq!(parsed.ast.next_id(), NodeId(count));
+}
+
+#[test]
+fn the_prelude_is_numbered_after_the_program() {
+    let mut program = parse("assert(true);").ast;
+    let before = program.next_id();
+    prelude::inject(&mut program, &mut SourceMap::new());
+    let mut all = all_ids(&program);
+    let count = all.len();
+    all.sort();
+    all.dedup();
+    assert_eq!(all.len(), count);
+    assert!(program.next_id() > before);
+}
+
+#[test]
+fn spans_cover_the_source_of_the_node() {
+    let source = "int x = -(1 + 2) * 3;\nx.y[x]++;";
+    let parsed = parse(source);
+    let text = |node: &Node| {
+        let span = parsed.spans.get(node.id()).unwrap();
+        &source[span.start..=span.end]
+    };
+    let Node::Program { body, .. } = &parsed.ast else {
+        unreachable!("the parser returns a program")
+    };
+    let value = body[0].children()[0];
+    assert_eq!(text(&body[0]), "int x = -(1 + 2) * 3");
+    assert_eq!(text(value), "-(1 + 2) * 3");
+    assert_eq!(text(value.children()[0]), "-(1 + 2)");
+    // Parentheses aren't nodes of their own
+    assert_eq!(text(value.children()[0].children()[0]), "1 + 2");
+    assert_eq!(text(&body[1]), "x.y[x]++");
+    assert_eq!(text(body[1].children()[0]), "x.y[x]");
+}
+
+#[test]
+fn names_point_at_their_symbol() {
+    let source = "int x = 1;\nfn f(int x) -> int { return x; }\nf(x);";
+    let parsed = parse(source);
+    let resolution = resolver::resolve(&parsed.cst);
+    let symbols = tables::symbols(&parsed.ast, &parsed.spans, &resolution);
+
+    let mut names = vec![];
+    let mut stack = vec![&parsed.ast];
+    while let Some(node) = stack.pop() {
+        if let Node::Identifier { value, id }
+        | Node::Call {
+            name: value, id, ..
+        } = node
+        {
+            let span = parsed.spans.get(*id).unwrap();
+            names.push((span.start, value.clone(), symbols.get(*id).copied()));
+        }
+        stack.extend(node.children());
+    }
+    names.sort();
+
+    let symbol = |index: usize| resolution.reference_at(index).unwrap().symbol;
+    let global = symbol(source.find("x =").unwrap());
+    let param = symbol(source.find("x)").unwrap());
+    let function = symbol(source.find("f(").unwrap());
+    assert_ne!(global, param);
+    assert_eq!(
+        names,
+        [
+            (source.find("x; }").unwrap(), "x".to_owned(), param),
+            (source.rfind("f(").unwrap(), "f".to_owned(), function),
+            (source.rfind("x)").unwrap(), "x".to_owned(), global),
+        ]
+    );
+}
+
+#[test]
+fn property_defaults_are_recorded_as_constants() {
+    let source = "struct point { int x = -3; float y = 1.5; };\npoint p = {};";
+    let parsed = parse(source);
+    let mut env = Environment::new();
+    parsed.ast.generate(&mut env).unwrap();
+
+    let Node::Program { body, .. } = &parsed.ast else {
+        unreachable!("the parser returns a program")
+    };
+    let Node::StructDecl { properties, .. } = &body[0] else {
+        unreachable!("the first statement declares the struct")
+    };
+    let values: Vec<Option<i64>> = properties
+        .iter()
+        .map(|property| env.value_of(property.default.as_ref().unwrap()))
+        .collect();
+    assert_eq!(values, [Some(-3), Some(1.5f32.to_bits() as i64)]);
+}
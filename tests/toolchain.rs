@@ -0,0 +1,84 @@
+#![cfg(unix)]
+
+use std::ffi::OsString;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+use rlang::toolchain;
+
+// A directory of its own for the test `name`, holding fake tools that print
+// `version` when asked for it
+fn bin(name: &str, tools: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rlang-toolchain-{}-{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    for (tool, version) in tools {
+        let path = dir.join(tool);
+        fs::write(&path, format!("#!/bin/sh\necho '{version}'\n")).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+    dir
+}
+
+fn linker(dir: &Path, linux: bool) -> Option<String> {
+    let found = toolchain::linker_in(&OsString::from(dir), linux)?;
+    Some(found.file_name()?.to_str()?.to_owned())
+}
+
+#[test]
+fn linux_uses_the_host_ld() {
+    let dir = bin("linux", &[("ld", "GNU ld"), ("ld.lld", "LLD 17")]);
+    assert_eq!(linker(&dir, true).as_deref(), Some("ld"));
+}
+
+#[test]
+fn elsewhere_prefers_a_cross_linker() {
+    let dir = bin(
+        "cross",
+        &[("ld", "  elf_x86_64"), ("x86_64-linux-gnu-ld", "GNU ld")],
+    );
+    assert_eq!(linker(&dir, false).as_deref(), Some("x86_64-linux-gnu-ld"));
+    let dir = bin("lld", &[("ld", "  elf_x86_64"), ("ld.lld", "LLD 17")]);
+    assert_eq!(linker(&dir, false).as_deref(), Some("ld.lld"));
+}
+
+#[test]
+fn elsewhere_skips_an_ld_that_cant_link_elf() {
+    let dir = bin("ld64", &[("ld", "@(#)PROGRAM:ld  PROJECT:ld64-820")]);
+    assert_eq!(linker(&dir, false), None);
+    let dir = bin("mingw", &[("ld", "  i386pep\n  i386pe")]);
+    assert_eq!(linker(&dir, false), None);
+}
+
+#[test]
+fn elsewhere_accepts_an_ld_that_emulates_elf() {
+    let dir = bin(
+        "elf",
+        &[("ld", "GNU ld\n  Supported emulations:\n   elf_x86_64")],
+    );
+    assert_eq!(linker(&dir, false).as_deref(), Some("ld"));
+}
+
+#[test]
+fn find_takes_the_first_name_found() {
+    let dir = bin("find", &[("nasm", "NASM"), ("yasm", "yasm")]);
+    let paths = OsString::from(&dir);
+    assert_eq!(
+        toolchain::find_in(&paths, &["missing", "yasm", "nasm"]),
+        Some(dir.join("yasm"))
+    );
+    assert_eq!(toolchain::find_in(&paths, &["missing"]), None);
+}
+
+#[test]
+fn artifacts_append_their_extension() {
+    assert_eq!(
+        toolchain::artifact(Path::new("out/prog.v2"), "asm"),
+        PathBuf::from("out/prog.v2.asm")
+    );
+    assert_eq!(
+        toolchain::artifact(Path::new("prog"), "o"),
+        PathBuf::from("prog.o")
+    );
+}
@@ -0,0 +1,57 @@
+use rlang::ast::{Node, NodeId};
+use rlang::generator::{Datatype, Environment};
+use rlang::{lexer, parser};
+
+// The statements of the program along with the environment that generated them
+fn generate(source: &str) -> (Vec<Node>, Environment<'static>) {
+    let (tokens, _) = lexer::lex(source.to_owned());
+    let (ast, _) = parser::parse(source, tokens).unwrap();
+    let mut env = Environment::new();
+    ast.generate(&mut env).unwrap();
+    let Node::Program { body, .. } = ast else {
+        unreachable!("the parser returns a program")
+    };
+    (body, env)
+}
+
+#[test]
+fn expression_types_are_recorded_while_generating() {
+    let (body, env) = generate(
+        "int x = 1;\nint* p = &x;\nfloat f = x;\nint* q = p + 1;\nint n = q - p;\nbool b = f > 0;",
+    );
+    let int = Datatype::Single {
+        size: 4,
+        signed: true,
+    };
+    let pointer = Datatype::Pointer {
+        target: Box::new(int.clone()),
+    };
+
+    let types: Vec<Option<Datatype>> = body
+        .iter()
+        .map(|stmt| match stmt {
+            Node::VarDecl { value, .. } => env.type_of(value),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        types,
+        [
+            Some(int.clone()),
+            Some(pointer.clone()),
+            Some(int.clone()),
+            Some(pointer.clone()),
+            Some(int.clone()),
+            Some(int.clone()),
+        ]
+    );
+
+    // Types go with the node's id, so a copy of a node has the same type
+    // and a node made up outside the program has none
+    assert_eq!(env.type_of(&body[1].children()[0].clone()), Some(pointer));
+    let detached = Node::Integer {
+        value: 1,
+        id: NodeId::DETACHED,
+    };
+    assert_eq!(env.type_of(&detached), None);
+}
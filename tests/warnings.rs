@@ -0,0 +1,117 @@
+use rlang::passes::PassManager;
+
+// The warnings compiling `source` gives
+fn warnings(source: &str) -> Vec<String> {
+    let options = rlang::Options {
+        filename: "main.rl".to_owned(),
+        ..Default::default()
+    };
+    rlang::compile_program(source, &options, &mut PassManager::new(), |_| {})
+        .unwrap()
+        .warnings
+}
+
+#[test]
+fn unused_struct() {
+    assert_eq!(
+        warnings("struct point { int x; int y; };\n1;\n"),
+        ["type point is never used
+  --> main.rl:1:8
+  |
+1 | struct point { int x; int y; };
+  |        ^^^^^
+  help: remove main.rl:1:1-1:31"]
+    );
+}
+
+#[test]
+fn used_struct() {
+    assert!(warnings("struct point { int x; int y; };\npoint p = { 1, 2 };\np.x;\n").is_empty());
+}
+
+#[test]
+fn unused_union() {
+    let warnings = warnings("union word { int i; float f; };\n1;\n");
+    assert_eq!(warnings.len(), 1);
+    assert!(
+        warnings[0].starts_with("type word is never used"),
+        "{warnings:?}"
+    );
+    assert!(
+        warnings[0].contains("help: remove main.rl:1:1-1:31"),
+        "{warnings:?}"
+    );
+}
+
+#[test]
+fn used_union() {
+    assert!(warnings("union word { int i; float f; };\nword w = { i: 1 };\nw.i;\n").is_empty());
+}
+
+#[test]
+fn unused_typedef() {
+    let warnings = warnings("typedef fn() -> int thunk;\n1;\n");
+    assert_eq!(warnings.len(), 1);
+    assert!(
+        warnings[0].starts_with("type thunk is never used"),
+        "{warnings:?}"
+    );
+    assert!(
+        warnings[0].contains("help: remove main.rl:1:1-1:26"),
+        "{warnings:?}"
+    );
+}
+
+#[test]
+fn used_typedef() {
+    assert!(warnings(
+        "typedef fn() -> int thunk;\nfn one() -> int { return 1; }\nthunk t = one;\nt();\n"
+    )
+    .is_empty());
+}
+
+// Both names of a typedef'd struct are reported, and only if neither is
+// used can the declaration go
+#[test]
+fn typedef_struct_names() {
+    let both = warnings("typedef struct vec2 { int x; int y; } vec2_t;\n1;\n");
+    assert_eq!(both.len(), 2, "{both:?}");
+    assert!(both[0].starts_with("type vec2 is never used"), "{both:?}");
+    assert!(both[1].starts_with("type vec2_t is never used"), "{both:?}");
+    assert!(both.iter().all(|warning| warning.contains("help: remove")));
+
+    let one = warnings("typedef struct vec2 { int x; int y; } vec2_t;\nsizeof(vec2_t);\n");
+    assert_eq!(one.len(), 1, "{one:?}");
+    assert!(one[0].starts_with("type vec2 is never used"), "{one:?}");
+    assert!(!one[0].contains("help: remove"), "{one:?}");
+}
+
+#[test]
+fn unused_types_in_blocks() {
+    let warnings =
+        warnings("fn f() -> int {\n    struct inner { int x; };\n    return 1;\n}\nf();\n");
+    assert_eq!(warnings.len(), 1, "{warnings:?}");
+    assert!(warnings[0].contains("--> main.rl:2:12"), "{warnings:?}");
+}
+
+#[test]
+fn unused_enum() {
+    let warnings = warnings("enum color { red, green = 4, blue };\n1;\n");
+    assert_eq!(warnings.len(), 1, "{warnings:?}");
+    assert!(
+        warnings[0].starts_with("type color is never used"),
+        "{warnings:?}"
+    );
+    assert!(warnings[0].contains("--> main.rl:1:6"), "{warnings:?}");
+    assert!(
+        warnings[0].contains("help: remove main.rl:1:1-1:36"),
+        "{warnings:?}"
+    );
+}
+
+// Using a member is using the enum, its name doesn't have to come up
+#[test]
+fn used_enum() {
+    assert!(warnings("enum color { red, green };\ncolor c = red;\nc;\n").is_empty());
+    assert!(warnings("enum color { red, green };\nint i = green;\ni;\n").is_empty());
+}